@@ -0,0 +1,116 @@
+//! Compares allocations of `add_message` and `add_bytes_zerocopy` when
+//! forwarding the same payload repeatedly.
+//!
+//! Run with `cargo bench --features bytes --bench bytes_allocations`.
+
+use bytes::Bytes;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+use tlq_client::TlqClient;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+const ITERATIONS: usize = 1_000;
+const PAYLOAD_SIZE: usize = 32 * 1024;
+
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static ALLOCATED_BYTES: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        ALLOCATED_BYTES.fetch_add(layout.size(), Ordering::Relaxed);
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+// Accepts connections forever, drains each request and answers with a small
+// message so the client-side cost dominates.
+async fn start_server() -> u16 {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let body = r#"{"id":"0198fbd8-344a-7c3e-a7d7-8b0d3e9c4a21","body":"ok","state":"Ready","lock_until":null,"retry_count":0}"#;
+    let response = format!(
+        "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        body.len(),
+        body
+    );
+
+    tokio::spawn(async move {
+        loop {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let response = response.clone();
+            tokio::spawn(async move {
+                let mut buf = vec![0u8; 64 * 1024];
+                let mut received = Vec::new();
+                loop {
+                    let n = stream.read(&mut buf).await.unwrap_or(0);
+                    if n == 0 {
+                        return;
+                    }
+                    received.extend_from_slice(&buf[..n]);
+                    if received.ends_with(b"\"}") {
+                        break;
+                    }
+                }
+                let _ = stream.write_all(response.as_bytes()).await;
+                let _ = stream.shutdown().await;
+            });
+        }
+    });
+
+    port
+}
+
+async fn measure<F, Fut>(name: &str, mut operation: F)
+where
+    F: FnMut() -> Fut,
+    Fut: std::future::Future<Output = ()>,
+{
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+    let bytes = ALLOCATED_BYTES.load(Ordering::Relaxed);
+    let start = Instant::now();
+
+    for _ in 0..ITERATIONS {
+        operation().await;
+    }
+
+    let elapsed = start.elapsed();
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed) - allocations;
+    let bytes = ALLOCATED_BYTES.load(Ordering::Relaxed) - bytes;
+    println!(
+        "{:<20} {:>8.1} allocs/op {:>10.0} bytes/op {:>8.1?}/op",
+        name,
+        allocations as f64 / ITERATIONS as f64,
+        bytes as f64 / ITERATIONS as f64,
+        elapsed / ITERATIONS as u32
+    );
+}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() {
+    let port = start_server().await;
+    let client = TlqClient::new("127.0.0.1", port).unwrap();
+    let payload = Bytes::from(vec![b'x'; PAYLOAD_SIZE]);
+
+    measure("add_message", || async {
+        let body = String::from_utf8(payload.to_vec()).unwrap();
+        client.add_message(body).await.unwrap();
+    })
+    .await;
+
+    measure("add_bytes_zerocopy", || async {
+        client.add_bytes_zerocopy(payload.clone()).await.unwrap();
+    })
+    .await;
+}
@@ -15,38 +15,32 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     println!("\nRetrieving messages in batches...");
     let batch_size = 5;
-    let messages = client.get_messages(batch_size).await?;
+    let mut batch = client.get_batch(batch_size).await?;
 
-    println!("Retrieved {} messages:", messages.len());
-    for msg in &messages {
+    println!("Retrieved {} messages:", batch.len());
+    for msg in batch.messages() {
         println!("  - {}: {}", msg.id, msg.body);
     }
 
     println!("\nProcessing batch...");
-    let mut successful_ids = Vec::new();
-    let mut failed_ids = Vec::new();
-
-    for msg in &messages {
-        if msg.body.contains("#3") || msg.body.contains("#7") {
-            println!("  ❌ Failed to process: {}", msg.body);
-            failed_ids.push(msg.id);
+    let outcomes: Vec<_> = batch
+        .messages()
+        .iter()
+        .map(|msg| (msg.id, msg.body.clone()))
+        .collect();
+
+    for (id, body) in outcomes {
+        if body.contains("#3") || body.contains("#7") {
+            println!("  ❌ Failed to process: {}", body);
+            batch.nack(id);
         } else {
-            println!("  ✅ Successfully processed: {}", msg.body);
-            successful_ids.push(msg.id);
+            println!("  ✅ Successfully processed: {}", body);
+            batch.ack(id);
         }
     }
 
-    if !successful_ids.is_empty() {
-        println!("\nDeleting {} successful messages...", successful_ids.len());
-        let deleted = client.delete_messages(&successful_ids).await?;
-        println!("Deleted {} messages", deleted);
-    }
-
-    if !failed_ids.is_empty() {
-        println!("\nRetrying {} failed messages...", failed_ids.len());
-        let retried = client.retry_messages(&failed_ids).await?;
-        println!("Retried {} messages", retried);
-    }
+    println!("\nCommitting acknowledgements...");
+    batch.commit().await?;
 
     println!("\nPurging remaining messages...");
     let purged = client.purge_queue().await?;
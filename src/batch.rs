@@ -0,0 +1,339 @@
+use crate::{
+    client::TlqClient,
+    error::{Result, TlqError},
+    message::Message,
+};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// A batch of retrieved messages whose acknowledgements are committed together.
+///
+/// Returned by [`TlqClient::get_batch`]. Each message is marked as acknowledged
+/// ([`ack`](Self::ack), deleted on commit) or negatively acknowledged
+/// ([`nack`](Self::nack), retried on commit). [`commit`](Self::commit) then issues
+/// at most one delete and one retry request for the whole batch, instead of one
+/// round-trip per message. A later decision for the same message replaces an
+/// earlier one; messages left undecided are not touched by the commit.
+///
+/// If [`requeue_on_drop`](Self::requeue_on_drop) is enabled and the batch is
+/// dropped without being committed, all of its messages are retried in a
+/// background task so they don't stay locked in the `Processing` state.
+///
+/// # Examples
+///
+/// ```no_run
+/// use tlq_client::TlqClient;
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), tlq_client::TlqError> {
+///     let client = TlqClient::new("localhost", 1337)?;
+///
+///     let mut batch = client.get_batch(10).await?.requeue_on_drop(true);
+///     let ids: Vec<_> = batch.messages().iter().map(|m| m.id).collect();
+///     for id in ids {
+///         // Process the message...
+///         batch.ack(id);
+///     }
+///     batch.commit().await?;
+///
+///     Ok(())
+/// }
+/// ```
+pub struct Batch<'a> {
+    client: &'a TlqClient,
+    messages: Vec<Message>,
+    decisions: HashMap<Uuid, bool>,
+    requeue_on_drop: bool,
+    committed: bool,
+}
+
+impl<'a> Batch<'a> {
+    pub(crate) fn new(client: &'a TlqClient, messages: Vec<Message>) -> Self {
+        Self {
+            client,
+            messages,
+            decisions: HashMap::new(),
+            requeue_on_drop: false,
+            committed: false,
+        }
+    }
+
+    /// Sets whether uncommitted messages are retried when the batch is dropped.
+    ///
+    /// Requeueing happens in a spawned task on the current Tokio runtime and is
+    /// best-effort: failures are ignored, and nothing is sent when dropped
+    /// outside a runtime.
+    pub fn requeue_on_drop(mut self, enabled: bool) -> Self {
+        self.requeue_on_drop = enabled;
+        self
+    }
+
+    /// Returns the messages in this batch.
+    pub fn messages(&self) -> &[Message] {
+        &self.messages
+    }
+
+    /// Returns the number of messages in this batch.
+    pub fn len(&self) -> usize {
+        self.messages.len()
+    }
+
+    /// Returns `true` if the batch contains no messages.
+    pub fn is_empty(&self) -> bool {
+        self.messages.is_empty()
+    }
+
+    /// Marks a message to be deleted on commit.
+    ///
+    /// Returns `false` if the message is not part of this batch.
+    pub fn ack(&mut self, id: Uuid) -> bool {
+        self.decide(id, true)
+    }
+
+    /// Marks a message to be retried on commit.
+    ///
+    /// Returns `false` if the message is not part of this batch.
+    pub fn nack(&mut self, id: Uuid) -> bool {
+        self.decide(id, false)
+    }
+
+    /// Marks every message in the batch to be deleted on commit.
+    pub fn ack_all(&mut self) {
+        for message in &self.messages {
+            self.decisions.insert(message.id, true);
+        }
+    }
+
+    /// Marks every message in the batch to be retried on commit.
+    pub fn nack_all(&mut self) {
+        for message in &self.messages {
+            self.decisions.insert(message.id, false);
+        }
+    }
+
+    /// Sends the recorded acknowledgements to the server.
+    ///
+    /// Acknowledged messages are removed with a single
+    /// [`delete_messages`](TlqClient::delete_messages) call and negatively
+    /// acknowledged ones are returned with a single
+    /// [`retry_messages`](TlqClient::retry_messages) call. Requests with no IDs
+    /// are skipped.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error from the delete or retry request. The batch counts
+    /// as committed either way and will not requeue on drop.
+    pub async fn commit(mut self) -> Result<()> {
+        self.committed = true;
+
+        let mut acked = Vec::new();
+        let mut nacked = Vec::new();
+        for message in &self.messages {
+            match self.decisions.get(&message.id) {
+                Some(true) => acked.push(message.id),
+                Some(false) => nacked.push(message.id),
+                None => {}
+            }
+        }
+
+        if !acked.is_empty() {
+            self.client.delete_messages(&acked).await?;
+        }
+        if !nacked.is_empty() {
+            self.client.retry_messages(&nacked).await?;
+        }
+        Ok(())
+    }
+
+    fn decide(&mut self, id: Uuid, ack: bool) -> bool {
+        if self.messages.iter().any(|m| m.id == id) {
+            self.decisions.insert(id, ack);
+            true
+        } else {
+            false
+        }
+    }
+}
+
+impl Drop for Batch<'_> {
+    fn drop(&mut self) {
+        if self.committed || !self.requeue_on_drop || self.messages.is_empty() {
+            return;
+        }
+
+        let ids: Vec<Uuid> = self.messages.iter().map(|m| m.id).collect();
+        if let Ok(handle) = tokio::runtime::Handle::try_current() {
+            let client = self.client.clone();
+            handle.spawn(async move {
+                let _ = client.retry_messages(&ids).await;
+            });
+        }
+    }
+}
+
+/// Options for bulk operations that may be split into several requests.
+///
+/// Used by [`TlqClient::delete_messages_with`] and
+/// [`TlqClient::retry_messages_with`]. The default sends all IDs in one request
+/// and returns its error, like [`delete_messages`](TlqClient::delete_messages)
+/// and [`retry_messages`](TlqClient::retry_messages).
+///
+/// # Examples
+///
+/// ```
+/// use tlq_client::BatchOptions;
+///
+/// let options = BatchOptions {
+///     chunk_size: Some(500),
+///     fail_fast: false,
+/// };
+/// assert!(BatchOptions::default().fail_fast);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BatchOptions {
+    /// Maximum number of IDs per request (`None` sends all IDs in one request)
+    pub chunk_size: Option<usize>,
+    /// Stop at the first failed chunk and return its error, rather than
+    /// continuing with the remaining chunks and collecting the failures
+    pub fail_fast: bool,
+}
+
+impl Default for BatchOptions {
+    fn default() -> Self {
+        Self {
+            chunk_size: None,
+            fail_fast: true,
+        }
+    }
+}
+
+/// The result of a chunked bulk operation.
+///
+/// Returned by [`TlqClient::delete_messages_with`] and
+/// [`TlqClient::retry_messages_with`]. With
+/// [`fail_fast`](BatchOptions::fail_fast) enabled, `failures` is always empty
+/// since the first failure is returned as an error instead.
+#[derive(Debug, Default)]
+pub struct BatchOutcome {
+    /// Number of IDs in chunks the server accepted
+    pub processed: usize,
+    /// Chunks that failed, in request order
+    pub failures: Vec<ChunkFailure>,
+}
+
+impl BatchOutcome {
+    /// Returns `true` if every chunk succeeded.
+    pub fn is_complete(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+/// A chunk of a bulk operation that failed, as reported in [`BatchOutcome`].
+#[derive(Debug)]
+pub struct ChunkFailure {
+    /// The IDs sent in the failed request
+    pub ids: Vec<Uuid>,
+    /// The error the request failed with
+    pub error: TlqError,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{message_json, mock_server, Captured};
+    use std::time::Duration;
+
+    async fn batch_server(ids: Vec<Uuid>) -> (TlqClient, Captured) {
+        let (port, captured) = mock_server(move |path, _| match path {
+            "/get" => {
+                let messages: Vec<_> = ids
+                    .iter()
+                    .map(|id| message_json(*id, "work", "Processing"))
+                    .collect();
+                (200, format!("[{}]", messages.join(",")))
+            }
+            _ => (200, "\"Success\"".to_string()),
+        })
+        .await;
+        (TlqClient::new("127.0.0.1", port).unwrap(), captured)
+    }
+
+    #[tokio::test]
+    async fn test_commit_groups_acks_and_nacks() {
+        let ids = vec![Uuid::now_v7(), Uuid::now_v7(), Uuid::now_v7()];
+        let (client, captured) = batch_server(ids.clone()).await;
+
+        let mut batch = client.get_batch(3).await.unwrap();
+        assert_eq!(batch.len(), 3);
+        assert!(batch.ack(ids[0]));
+        assert!(batch.nack(ids[1]));
+        assert!(!batch.ack(Uuid::now_v7()));
+        batch.commit().await.unwrap();
+
+        let requests = captured.lock().unwrap();
+        assert_eq!(requests.len(), 3);
+        assert_eq!(requests[1].path, "/delete");
+        assert!(requests[1].body.contains(&ids[0].to_string()));
+        assert!(!requests[1].body.contains(&ids[2].to_string()));
+        assert_eq!(requests[2].path, "/retry");
+        assert!(requests[2].body.contains(&ids[1].to_string()));
+        assert!(!requests[2].body.contains(&ids[2].to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_ack_all_sends_single_delete() {
+        let ids = vec![Uuid::now_v7(), Uuid::now_v7()];
+        let (client, captured) = batch_server(ids.clone()).await;
+
+        let mut batch = client.get_batch(2).await.unwrap();
+        batch.nack(ids[0]);
+        batch.ack_all();
+        batch.commit().await.unwrap();
+
+        let requests = captured.lock().unwrap();
+        let paths: Vec<_> = requests.iter().map(|r| r.path.as_str()).collect();
+        assert_eq!(paths, vec!["/get", "/delete"]);
+        assert!(requests[1].body.contains(&ids[0].to_string()));
+        assert!(requests[1].body.contains(&ids[1].to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_nack_all_sends_single_retry() {
+        let ids = vec![Uuid::now_v7(), Uuid::now_v7()];
+        let (client, captured) = batch_server(ids).await;
+
+        let mut batch = client.get_batch(2).await.unwrap();
+        batch.nack_all();
+        batch.commit().await.unwrap();
+
+        let requests = captured.lock().unwrap();
+        let paths: Vec<_> = requests.iter().map(|r| r.path.as_str()).collect();
+        assert_eq!(paths, vec!["/get", "/retry"]);
+    }
+
+    #[tokio::test]
+    async fn test_drop_requeues_when_enabled() {
+        let ids = vec![Uuid::now_v7(), Uuid::now_v7()];
+        let (client, captured) = batch_server(ids.clone()).await;
+
+        let batch = client.get_batch(2).await.unwrap().requeue_on_drop(true);
+        drop(batch);
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        let requests = captured.lock().unwrap();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[1].path, "/retry");
+        assert!(requests[1].body.contains(&ids[0].to_string()));
+        assert!(requests[1].body.contains(&ids[1].to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_drop_without_requeue_sends_nothing() {
+        let (client, captured) = batch_server(vec![Uuid::now_v7()]).await;
+
+        drop(client.get_batch(1).await.unwrap());
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        assert_eq!(captured.lock().unwrap().len(), 1);
+    }
+}
@@ -1,17 +1,53 @@
+#[cfg(feature = "stream")]
+use crate::stream::{BodyReader, MessageBody, MessageStream};
 use crate::{
-    config::{Config, ConfigBuilder},
+    batch::{Batch, BatchOptions, BatchOutcome, ChunkFailure},
+    config::{Config, ConfigBuilder, IpPreference},
+    consumer::{ConsumeOptions, PendingAcks},
     error::{Result, TlqError},
     message::*,
-    retry::RetryStrategy,
+    producer::{Producer, ProducerOptions},
+    queue::{QueueInfo, QueueOptions, QueueStats},
+    response::{Response, ResponseMeta},
+    retry::{random_delay, RetryStrategy},
+    server::HelloInfo,
+    stats::{AtomicStats, ClientStats},
+    transport::Transport,
 };
 use serde::{de::DeserializeOwned, Serialize};
-use std::time::Duration;
-use tokio::io::{AsyncReadExt, AsyncWriteExt};
-use tokio::net::TcpStream;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::io::{self, IoSlice};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::net::{lookup_host, TcpSocket, TcpStream};
+use tokio::sync::{mpsc, OnceCell, Semaphore};
+use tokio::task::JoinHandle;
 use tokio::time::timeout;
+#[cfg(feature = "tls")]
+use tokio_rustls::{
+    rustls::{self, pki_types::ServerName},
+    TlsConnector,
+};
+use tokio_util::sync::CancellationToken;
 use uuid::Uuid;
 
-const MAX_MESSAGE_SIZE: usize = 65536;
+const REPLY_POLL_BATCH: u32 = 10;
+const REPLY_POLL_INTERVAL: Duration = Duration::from_millis(100);
+const JSON_CONTENT_TYPE: &str = "application/json";
+const RAW_CONTENT_TYPE: &str = "text/plain; charset=utf-8";
+const NDJSON_CONTENT_TYPE: &str = "application/x-ndjson";
+const MESSAGE_LIST_ENDPOINTS: [&str; 2] = ["/get", "/peek"];
+const PEEK_NEXT_FALLBACK_COUNT: u32 = 100;
+pub(crate) const CONSUMER_POLL_INTERVAL: Duration = Duration::from_millis(100);
+const HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+// Version of the request shapes this client sends, advertised to the server
+const CLIENT_PROTOCOL_VERSION: &str = "1";
+const CLIENT_PROTOCOL_HEADER: &str = "X-TLQ-Client-Protocol";
+const SERVER_PROTOCOL_HEADER: &str = "x-tlq-server-protocol";
 
 /// The main client for interacting with TLQ (Tiny Little Queue) servers.
 ///
@@ -42,9 +78,22 @@ const MAX_MESSAGE_SIZE: usize = 65536;
 ///     Ok(())
 /// }
 /// ```
+#[derive(Clone)]
 pub struct TlqClient {
     config: Config,
     base_url: String,
+    retry_strategy: RetryStrategy,
+    retry_slots: Arc<Semaphore>,
+    retry_limit: usize,
+    server_max_size: Arc<OnceCell<usize>>,
+    #[cfg(feature = "tls")]
+    tls_connector: Option<TlsConnector>,
+    stats: Arc<AtomicStats>,
+    startup_jitter: Arc<OnceCell<()>>,
+    server_clock_offset: Arc<Mutex<Option<i64>>>,
+    in_flight: Arc<AtomicUsize>,
+    routes: Arc<Mutex<HashMap<&'static str, bool>>>,
+    // Notified whenever a connect attempt has failed on every address
 }
 
 impl TlqClient {
@@ -79,6 +128,43 @@ impl TlqClient {
         Ok(Self::with_config(config))
     }
 
+    /// Creates a new TLQ client and verifies the server is reachable.
+    ///
+    /// Unlike [`new`](Self::new), which connects lazily on the first request,
+    /// this performs a [`health_check`](Self::health_check) up front, so an
+    /// unreachable server is reported at startup rather than on first use. When
+    /// the configuration enables [`auto_max_size`](ConfigBuilder::auto_max_size),
+    /// the server's size limit is fetched here too, keeping the extra round-trip
+    /// off the first [`add_message`](Self::add_message).
+    ///
+    /// # Arguments
+    ///
+    /// * `host` - The hostname or IP address of the TLQ server
+    /// * `port` - The port number of the TLQ server
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tlq_client::TlqClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), tlq_client::TlqError> {
+    ///     let client = TlqClient::new_warmed("localhost", 1337).await?;
+    ///     client.add_message("first message").await?;
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// * [`TlqError::Connection`] if the server can't be reached or fails the health check
+    /// * [`TlqError::Timeout`] if the health check times out
+    pub async fn new_warmed(host: impl Into<String>, port: u16) -> Result<Self> {
+        let client = Self::new(host, port)?;
+        client.warm_up().await?;
+        Ok(client)
+    }
+
     /// Creates a new TLQ client with custom configuration.
     ///
     /// Use this method when you need to customize timeout, retry behavior,
@@ -106,8 +192,192 @@ impl TlqClient {
     /// # }
     /// ```
     pub fn with_config(config: Config) -> Self {
-        let base_url = format!("{}:{}", config.host, config.port);
-        Self { config, base_url }
+        let base_url = host_port(&config.host, config.port);
+        let retry_strategy = RetryStrategy::new(config.max_retries, config.retry_delay)
+            .with_backoff(config.backoff)
+            .with_max_delay(config.max_retry_delay)
+            .with_jitter(config.jitter)
+            .with_observer(config.on_retry.clone());
+        let retry_limit = config
+            .max_concurrent_retries
+            .unwrap_or(Semaphore::MAX_PERMITS)
+            .min(Semaphore::MAX_PERMITS);
+        #[cfg(feature = "tls")]
+        let tls_connector = tls_connector(&config);
+        Self {
+            config,
+            base_url,
+            retry_strategy,
+            retry_slots: Arc::new(Semaphore::new(retry_limit)),
+            retry_limit,
+            server_max_size: Arc::new(OnceCell::new()),
+            #[cfg(feature = "tls")]
+            tls_connector,
+            stats: Arc::new(AtomicStats::default()),
+            startup_jitter: Arc::new(OnceCell::new()),
+            server_clock_offset: Arc::new(Mutex::new(None)),
+            in_flight: Arc::new(AtomicUsize::new(0)),
+            routes: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Creates a new TLQ client from a connection URL.
+    ///
+    /// The URL has the form `tlq://host[:port][?key=value&...]`, where the port
+    /// defaults to 1337 and query parameters override [`Config`] fields. See
+    /// [`ConfigBuilder::from_url`] for the supported parameters, or to adjust
+    /// the parsed settings before creating the client.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - The connection URL
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tlq_client::TlqClient;
+    ///
+    /// # fn example() -> Result<(), tlq_client::TlqError> {
+    /// let client = TlqClient::from_url("tlq://queue.example.com:8080?timeout_ms=5000&max_retries=2")?;
+    ///
+    /// // Or from the environment
+    /// let url = std::env::var("TLQ_URL").unwrap_or_else(|_| "tlq://localhost".to_string());
+    /// let client = TlqClient::from_url(&url)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TlqError::Validation`] if the URL is malformed.
+    pub fn from_url(url: &str) -> Result<Self> {
+        Ok(Self::with_config(ConfigBuilder::from_url(url)?.build()))
+    }
+
+    /// Returns a client that targets the named `queue`.
+    ///
+    /// The returned client shares this client's settings and retry limits, but
+    /// sends all operations to `queue` (see [`ConfigBuilder::queue`]). Creating
+    /// it is cheap, so it works well for per-call overrides.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tlq_client::TlqClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), tlq_client::TlqError> {
+    ///     let client = TlqClient::new("localhost", 1337)?;
+    ///
+    ///     client.with_queue("orders").add_message("order 42").await?;
+    ///     let invoices = client.with_queue("invoices").get_messages(10).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn with_queue(&self, queue: impl Into<String>) -> Self {
+        let mut client = self.clone();
+        client.config.queue = queue.into();
+        client
+    }
+
+    /// Returns a client that uses `timeout` for its requests.
+    ///
+    /// Like [`with_queue`](Self::with_queue), the returned client is a cheap
+    /// clone: it keeps every other setting and shares this client's retry
+    /// limits and statistics. Per-operation overrides from
+    /// [`ConfigBuilder::operation_timeouts`] still take precedence.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tlq_client::TlqClient;
+    /// use std::time::Duration;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), tlq_client::TlqError> {
+    ///     let client = TlqClient::new("localhost", 1337)?;
+    ///
+    ///     let impatient = client.with_timeout(Duration::from_millis(500));
+    ///     let messages = impatient.get_messages(10).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn with_timeout(&self, timeout: Duration) -> Self {
+        let mut client = self.clone();
+        client.config.timeout = timeout;
+        client
+    }
+
+    /// Returns a client that retries failed requests up to `max_retries` times.
+    ///
+    /// The returned client is a cheap clone that keeps every other setting,
+    /// including the retry delay and backoff, and shares this client's retry
+    /// limits and statistics. Use `0` to disable retries, for example where
+    /// the caller handles failures itself.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tlq_client::TlqClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), tlq_client::TlqError> {
+    ///     let client = TlqClient::new("localhost", 1337)?;
+    ///
+    ///     let no_retries = client.with_retries(0);
+    ///     no_retries.add_message("best effort").await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn with_retries(&self, max_retries: u32) -> Self {
+        let mut client = self.clone();
+        client.config.max_retries = max_retries;
+        client.retry_strategy = RetryStrategy::new(max_retries, client.config.retry_delay)
+            .with_backoff(client.config.backoff)
+            .with_max_delay(client.config.max_retry_delay)
+            .with_jitter(client.config.jitter)
+            .with_observer(client.config.on_retry.clone());
+        client
+    }
+
+    /// Returns a snapshot of this client's request statistics.
+    ///
+    /// The counters are shared by all clones of the client, including those
+    /// created with [`with_queue`](Self::with_queue),
+    /// [`with_timeout`](Self::with_timeout) and
+    /// [`with_retries`](Self::with_retries). See [`ClientStats`] for
+    /// what is counted.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tlq_client::TlqClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), tlq_client::TlqError> {
+    ///     let client = TlqClient::new("localhost", 1337)?;
+    ///     client.add_message("hello").await?;
+    ///
+    ///     let stats = client.stats();
+    ///     println!("{} requests, avg {:?}", stats.requests, stats.avg_latency);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn stats(&self) -> ClientStats {
+        self.stats.snapshot()
+    }
+
+    /// Resets all request statistics to zero.
+    ///
+    /// Useful for interval-based reporting: read [`stats`](Self::stats), emit
+    /// it, then reset. Requests in flight while resetting may be split across
+    /// both intervals.
+    pub fn reset_stats(&self) {
+        self.stats.reset();
     }
 
     /// Returns a [`ConfigBuilder`] for creating custom configurations.
@@ -139,54 +409,404 @@ impl TlqClient {
         T: Serialize,
         R: DeserializeOwned,
     {
-        let retry_strategy = RetryStrategy::new(self.config.max_retries, self.config.retry_delay);
+        Ok(self.request_detailed(endpoint, body).await?.value)
+    }
 
-        retry_strategy
-            .execute(|| async { self.single_request(endpoint, body).await })
+    // Like `request`, but also returns the response metadata.
+    async fn request_detailed<T, R>(&self, endpoint: &str, body: &T) -> Result<Response<R>>
+    where
+        T: Serialize,
+        R: DeserializeOwned,
+    {
+        let json_body = serde_json::to_vec(body)?;
+        self.request_parts_detailed(endpoint, JSON_CONTENT_TYPE, &[&json_body])
             .await
     }
 
+    // Like `request`, but sends a body that is already encoded, given as parts
+    // that are written back to back without being joined first.
+    #[cfg(feature = "bytes")]
+    async fn request_parts<R>(
+        &self,
+        endpoint: &str,
+        content_type: &str,
+        body: &[&[u8]],
+    ) -> Result<R>
+    where
+        R: DeserializeOwned,
+    {
+        Ok(self
+            .request_parts_detailed(endpoint, content_type, body)
+            .await?
+            .value)
+    }
+
+    // Like `request_parts`, but also returns the response metadata. With the
+    // `tracing` feature, the whole operation, retries included, runs in a
+    // `tlq_request` span that records the number of attempts.
+    async fn request_parts_detailed<R>(
+        &self,
+        endpoint: &str,
+        content_type: &str,
+        body: &[&[u8]],
+    ) -> Result<Response<R>>
+    where
+        R: DeserializeOwned,
+    {
+        self.check_request_size(body)?;
+        self.wait_startup_jitter().await;
+
+        traced(endpoint, async {
+            if self.retry_strategy.is_enabled() {
+                // Adds aren't idempotent, so a timed out add is only retried if allowed
+                let retry_timeouts =
+                    self.config.retry_on_timeout || !matches!(endpoint, "/add" | "/add_batch");
+
+                self.run_with_retries(
+                    || async {
+                        self.single_request_parts(endpoint, content_type, body)
+                            .await
+                    },
+                    |err| {
+                        err.is_retryable()
+                            && (retry_timeouts || !matches!(err, TlqError::Timeout(_)))
+                    },
+                )
+                .await
+            } else {
+                #[cfg(feature = "tracing")]
+                tracing::Span::current().record("attempt", 1);
+                self.single_request_parts(endpoint, content_type, body)
+                    .await
+            }
+        })
+        .await
+    }
+
+    // Rejects a request body larger than `max_request_body`, before it is sent.
+    fn check_request_size(&self, body: &[&[u8]]) -> Result<()> {
+        let size: usize = body.iter().map(|part| part.len()).sum();
+        match self.config.max_request_body {
+            Some(limit) if size > limit => Err(TlqError::RequestTooLarge { size }),
+            _ => Ok(()),
+        }
+    }
+
+    // Runs `operation` with the retry strategy, retrying errors accepted by
+    // `should_retry`. Entering the retry phase takes a slot from the shared
+    // `max_concurrent_retries` limit; without a free slot the error is returned
+    // right away. The slot is held until the operation finishes. Retries wait
+    // at least as long as a `Retry-After` the server sent, unless it exceeds
+    // the retry delay cap, in which case the error is returned. A transient
+    // error that persists through every retry is wrapped in
+    // `MaxRetriesExceeded`; other errors are returned as they are. With the
+    // `tracing` feature, each retry logs a warning with the error, and the
+    // number of attempts is recorded on the current span.
+    async fn run_with_retries<T, F, Fut, P>(&self, operation: F, should_retry: P) -> Result<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: std::future::Future<Output = Result<T>>,
+        P: Fn(&TlqError) -> bool,
+    {
+        let mut slot = None;
+        let mut retries = 0;
+        let result = self
+            .retry_strategy
+            .execute_advised(
+                operation,
+                |err| {
+                    if !should_retry(err) {
+                        return false;
+                    }
+                    if slot.is_none() {
+                        slot = self.retry_slots.clone().try_acquire_owned().ok();
+                    }
+                    if slot.is_some() {
+                        self.stats.record_retry();
+                        retries += 1;
+                        #[cfg(feature = "tracing")]
+                        tracing::warn!(retry = retries, error = %err, "retrying request");
+                    }
+                    slot.is_some()
+                },
+                TlqError::retry_after,
+            )
+            .await;
+        #[cfg(feature = "tracing")]
+        tracing::Span::current().record("attempt", retries + 1);
+
+        let max_retries = self.config.max_retries;
+        result.map_err(|err| {
+            if max_retries > 0 && retries == max_retries && err.is_retryable() {
+                TlqError::MaxRetriesExceeded {
+                    max_retries,
+                    source: Box::new(err),
+                }
+            } else {
+                err
+            }
+        })
+    }
+
+    // Sends one request without retries. With the `tracing` feature, it runs
+    // in a `tlq_request` span.
     async fn single_request<T, R>(&self, endpoint: &str, body: &T) -> Result<R>
     where
         T: Serialize,
         R: DeserializeOwned,
     {
         let json_body = serde_json::to_vec(body)?;
+        self.check_request_size(&[&json_body])?;
 
-        let request = format!(
+        let response = traced(
+            endpoint,
+            self.single_request_parts(endpoint, JSON_CONTENT_TYPE, &[&json_body]),
+        )
+        .await?;
+        Ok(response.value)
+    }
+
+    async fn single_request_parts<R>(
+        &self,
+        endpoint: &str,
+        content_type: &str,
+        body: &[&[u8]],
+    ) -> Result<Response<R>>
+    where
+        R: DeserializeOwned,
+    {
+        let _in_flight = InFlightGuard::new(&self.in_flight);
+        let request_timeout = self.config.timeout_for(endpoint);
+        let started = Instant::now();
+
+        let result = timeout(request_timeout, self.exchange(endpoint, content_type, body))
+            .await
+            .map_err(|_| TlqError::Timeout(request_timeout.as_millis() as u64))
+            .and_then(|result| result);
+
+        self.stats.record_request(
+            started.elapsed(),
+            result.is_ok(),
+            matches!(result, Err(TlqError::Timeout(_))),
+        );
+        result
+    }
+
+    async fn exchange<R>(
+        &self,
+        endpoint: &str,
+        content_type: &str,
+        body: &[&[u8]],
+    ) -> Result<Response<R>>
+    where
+        R: DeserializeOwned,
+    {
+        let content_length: usize = body.iter().map(|part| part.len()).sum();
+        let request = self.post_head(endpoint, content_type, content_length, true)?;
+        #[cfg(feature = "otel")]
+        crate::otel::record_body_size(content_length);
+
+        let mut stream = self.open().await?;
+
+        let mut parts = Vec::with_capacity(body.len() + 1);
+        parts.push(IoSlice::new(request.as_bytes()));
+        parts.extend(body.iter().map(|part| IoSlice::new(part)));
+        write_all_vectored(&mut stream, &mut parts).await?;
+        stream.flush().await?;
+        self.stats.record_sent(request.len() + content_length);
+
+        let response = read_response(&mut stream).await?;
+        self.stats.record_received(response.len());
+
+        self.parse_response(endpoint, &response)
+    }
+
+    // The request line and headers of a POST to `endpoint`. With `close`, the
+    // server is asked to close the connection after responding.
+    fn post_head(
+        &self,
+        endpoint: &str,
+        content_type: &str,
+        content_length: usize,
+        close: bool,
+    ) -> Result<String> {
+        Ok(format!(
             "POST {} HTTP/1.1\r\n\
              Host: {}\r\n\
-             Content-Type: application/json\r\n\
+             Content-Type: {}\r\n\
              Content-Length: {}\r\n\
-             Connection: close\r\n\
+             Connection: {}\r\n\
+             {}\
              \r\n",
-            endpoint,
+            self.request_target(endpoint),
             self.base_url,
-            json_body.len()
-        );
+            content_type,
+            content_length,
+            if close { "close" } else { "keep-alive" },
+            self.extra_headers()?
+        ))
+    }
+
+    // Parses the raw response to a request to `endpoint`, failing on error
+    // statuses.
+    fn parse_response<R>(&self, endpoint: &str, response: &[u8]) -> Result<Response<R>>
+    where
+        R: DeserializeOwned,
+    {
+        let response_str = self.decode_response(response)?;
+        self.record_server_date(&response_str);
+        #[cfg(any(feature = "tracing", feature = "otel"))]
+        if let Some(status) = response_status(&response_str) {
+            #[cfg(feature = "tracing")]
+            tracing::Span::current().record("status", status);
+            #[cfg(feature = "otel")]
+            crate::otel::record_status(status);
+        }
+        let body = Self::parse_http_response(&response_str)?;
+        let headers = &response_str[..response_str.len() - body.len()];
+        let meta = ResponseMeta::from_headers(headers.trim_end());
+
+        let value = if is_ndjson(&response_str) {
+            serde_json::from_str(&ndjson_to_array(body))?
+        } else {
+            match serde_json::from_str(body) {
+                // Streaming servers may send message lists as NDJSON without saying so
+                Err(err) if MESSAGE_LIST_ENDPOINTS.contains(&endpoint) => {
+                    serde_json::from_str(&ndjson_to_array(body)).map_err(|_| err)?
+                }
+                result => result?,
+            }
+        };
+        Ok(Response { value, meta })
+    }
+
+    // Opens a connection to the server, wrapped in TLS when enabled.
+    async fn open(&self) -> Result<Transport> {
+        let stream = self.connect().await?;
+
+        #[cfg(feature = "tls")]
+        if let Some(connector) = &self.tls_connector {
+            let server_name = ServerName::try_from(self.config.host.clone()).map_err(|_| {
+                TlqError::Connection(format!("Invalid TLS server name: {}", self.config.host))
+            })?;
+            let stream = connector
+                .connect(server_name, stream)
+                .await
+                .map_err(|e| TlqError::Connection(format!("TLS handshake failed: {}", e)))?;
+            return Ok(Transport::Tls(Box::new(stream)));
+        }
+
+        Ok(Transport::Plain(stream))
+    }
 
-        let mut stream = timeout(self.config.timeout, TcpStream::connect(&self.base_url))
+    // Resolves the server address and connects to the first reachable address
+    // allowed by the configured IP preference. Refused or failed connects are
+    // retried `connect_retries` times, `connect_retry_delay` apart.
+    async fn connect(&self) -> Result<TcpStream> {
+        let preference = self.config.ip_version;
+
+        let addrs: Vec<_> = lookup_host(&self.base_url)
             .await
-            .map_err(|_| TlqError::Timeout(self.config.timeout.as_millis() as u64))?
-            .map_err(|e| TlqError::Connection(e.to_string()))?;
+            .map_err(|e| TlqError::Connection(e.to_string()))?
+            .filter(|addr| preference.allows(addr))
+            .collect();
 
-        stream.write_all(request.as_bytes()).await?;
-        stream.write_all(&json_body).await?;
-        stream.flush().await?;
+        if addrs.is_empty() {
+            let family = match preference {
+                IpPreference::Auto => "",
+                IpPreference::V4Only => "IPv4 ",
+                IpPreference::V6Only => "IPv6 ",
+            };
+            return Err(TlqError::Connection(format!(
+                "No {}address resolved for {}",
+                family, self.base_url
+            )));
+        }
 
-        let mut response = Vec::new();
-        stream.read_to_end(&mut response).await?;
+        let mut last_error = None;
+        for attempt in 0..=self.config.connect_retries {
+            if attempt > 0 {
+                tokio::time::sleep(self.config.connect_retry_delay).await;
+            }
+            for addr in &addrs {
+                match self.connect_to(*addr).await {
+                    Ok(stream) => return Ok(stream),
+                    Err(e) => last_error = Some(e),
+                }
+            }
+        }
+        Err(TlqError::Connection(
+            last_error.map(|e| e.to_string()).unwrap_or_default(),
+        ))
+    }
 
-        let response_str = String::from_utf8_lossy(&response);
-        let body = Self::parse_http_response(&response_str)?;
-        serde_json::from_str(body).map_err(Into::into)
+    // Connects to `addr`, first binding to the configured local address and
+    // port range if any. Ports that are already in use are skipped.
+    async fn connect_to(&self, addr: SocketAddr) -> io::Result<TcpStream> {
+        let config = &self.config;
+        if config.bind_address.is_none() && config.local_port_range.is_none() {
+            return TcpStream::connect(addr).await;
+        }
+
+        let ip = config.bind_address.unwrap_or(match addr {
+            SocketAddr::V4(_) => IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+            SocketAddr::V6(_) => IpAddr::V6(Ipv6Addr::UNSPECIFIED),
+        });
+        let ports = config.local_port_range.clone().unwrap_or(0..=0);
+
+        let mut last_error = None;
+        for port in ports {
+            let local = SocketAddr::new(ip, port);
+            let socket = match addr {
+                SocketAddr::V4(_) => TcpSocket::new_v4()?,
+                SocketAddr::V6(_) => TcpSocket::new_v6()?,
+            };
+            match socket.bind(local) {
+                Ok(()) => {}
+                Err(e) if e.kind() == io::ErrorKind::AddrInUse => {
+                    last_error = Some(e);
+                    continue;
+                }
+                Err(e) => {
+                    return Err(io::Error::new(
+                        e.kind(),
+                        format!("Failed to bind to local address {}: {}", local, e),
+                    ))
+                }
+            }
+            match socket.connect(addr).await {
+                Ok(stream) => return Ok(stream),
+                // The local port is free but already used for a connection to
+                // this server, e.g. one lingering in TIME_WAIT.
+                Err(e)
+                    if matches!(
+                        e.kind(),
+                        io::ErrorKind::AddrInUse | io::ErrorKind::AddrNotAvailable
+                    ) =>
+                {
+                    last_error = Some(e)
+                }
+                Err(e) => return Err(e),
+            }
+        }
+
+        Err(io::Error::new(
+            io::ErrorKind::AddrInUse,
+            format!(
+                "No usable local port in {:?} on {}{}",
+                config.local_port_range.as_ref().unwrap_or(&(0..=0)),
+                ip,
+                last_error.map(|e| format!(": {}", e)).unwrap_or_default()
+            ),
+        ))
     }
 
     /// Performs a health check against the TLQ server.
     ///
     /// This method sends a GET request to the `/hello` endpoint to verify
-    /// that the server is responding. It uses a fixed 5-second timeout
-    /// regardless of the client's configured timeout.
+    /// that the server is responding. It uses a 5-second timeout regardless of
+    /// the client's configured timeout, unless overridden with
+    /// [`OperationTimeouts::health`](crate::OperationTimeouts::health).
     ///
     /// # Returns
     ///
@@ -218,41 +838,17 @@ impl TlqClient {
     /// Returns [`TlqError::Connection`] for network issues, or [`TlqError::Timeout`]
     /// if the server doesn't respond within 5 seconds.
     pub async fn health_check(&self) -> Result<bool> {
-        let mut stream = timeout(Duration::from_secs(5), TcpStream::connect(&self.base_url))
-            .await
-            .map_err(|_| TlqError::Timeout(5000))?
-            .map_err(|e| TlqError::Connection(e.to_string()))?;
-
-        let request = format!(
-            "GET /hello HTTP/1.1\r\n\
-             Host: {}\r\n\
-             Connection: close\r\n\
-             \r\n",
-            self.base_url
-        );
-
-        stream.write_all(request.as_bytes()).await?;
-        stream.flush().await?;
-
-        let mut response = Vec::new();
-        stream.read_to_end(&mut response).await?;
-
-        let response_str = String::from_utf8_lossy(&response);
-        Ok(response_str.contains("200 OK"))
+        let response = self.hello_with_timeout().await?;
+        Ok(response.contains("200 OK"))
     }
 
-    /// Adds a new message to the TLQ server.
-    ///
-    /// The message will be assigned a UUID v7 identifier and placed in the queue
-    /// with state [`MessageState::Ready`]. Messages have a maximum size limit of 64KB.
-    ///
-    /// # Arguments
-    ///
-    /// * `body` - The message content (any type that can be converted to String)
-    ///
-    /// # Returns
+    /// Performs a health check and returns what the server reports about itself.
     ///
-    /// Returns the created [`Message`] with its assigned ID and metadata.
+    /// Sends the same `/hello` request as [`health_check`](Self::health_check),
+    /// with the same timeout, and parses the response body into a
+    /// [`HelloInfo`]. A JSON object body fills in the server version and uptime
+    /// where present; any other body, such as the plain `"Hello World"`
+    /// greeting, becomes the `message`.
     ///
     /// # Examples
     ///
@@ -263,150 +859,186 @@ impl TlqClient {
     /// async fn main() -> Result<(), tlq_client::TlqError> {
     ///     let client = TlqClient::new("localhost", 1337)?;
     ///
-    ///     // Add a simple string message
-    ///     let message = client.add_message("Hello, World!").await?;
-    ///     println!("Created message {} with body: {}", message.id, message.body);
+    ///     let info = client.health_check_detailed().await?;
+    ///     println!("{} (version {:?})", info.message, info.version);
     ///
-    ///     // Add a formatted message
-    ///     let user_data = "important data";
-    ///     let message = client.add_message(format!("Processing: {}", user_data)).await?;
-    ///     
     ///     Ok(())
     /// }
     /// ```
     ///
     /// # Errors
     ///
-    /// * [`TlqError::MessageTooLarge`] if the message exceeds 64KB (65,536 bytes)
-    /// * [`TlqError::Connection`] for network connectivity issues
-    /// * [`TlqError::Timeout`] if the request times out
-    /// * [`TlqError::Server`] for server-side errors (4xx/5xx HTTP responses)
-    pub async fn add_message(&self, body: impl Into<String>) -> Result<Message> {
-        let body = body.into();
-
-        if body.len() > MAX_MESSAGE_SIZE {
-            return Err(TlqError::MessageTooLarge { size: body.len() });
-        }
-
-        let request = AddMessageRequest { body };
-        let message: Message = self.request("/add", &request).await?;
-        Ok(message)
+    /// * [`TlqError::Server`] if the server responds with an error status
+    /// * [`TlqError::Connection`] for network issues or a malformed response
+    /// * [`TlqError::Timeout`] if the server doesn't respond in time
+    pub async fn health_check_detailed(&self) -> Result<HelloInfo> {
+        let response = self.hello_with_timeout().await?;
+        let body = Self::parse_http_response(&response)?;
+        Ok(HelloInfo::from_body(body))
     }
 
-    /// Retrieves multiple messages from the TLQ server.
+    /// Returns the server's current time, taken from the HTTP `Date` header.
     ///
-    /// This method fetches up to `count` messages from the queue. Messages are returned
-    /// in the order they were added and their state is changed to [`MessageState::Processing`].
-    /// The server may return fewer messages than requested if there are not enough
-    /// messages in the queue.
-    ///
-    /// # Arguments
-    ///
-    /// * `count` - Maximum number of messages to retrieve (must be greater than 0)
-    ///
-    /// # Returns
+    /// `lock_until` and other timestamps are set by the server's clock, so
+    /// comparing them with the local clock is off by however much the two
+    /// clocks differ. This sends the same request as
+    /// [`health_check`](Self::health_check) and reads the time the server
+    /// reports. The header has one-second resolution.
     ///
-    /// Returns a vector of [`Message`] objects. The vector may be empty if no messages
-    /// are available in the queue.
+    /// Each response with a `Date` header also updates the client's clock
+    /// offset estimate, see [`estimated_server_time`](Self::estimated_server_time).
     ///
     /// # Examples
     ///
     /// ```no_run
     /// use tlq_client::TlqClient;
+    /// use std::time::SystemTime;
     ///
     /// #[tokio::main]
     /// async fn main() -> Result<(), tlq_client::TlqError> {
     ///     let client = TlqClient::new("localhost", 1337)?;
     ///
-    ///     // Get up to 5 messages from the queue
-    ///     let messages = client.get_messages(5).await?;
-    ///     
-    ///     for message in messages {
-    ///         println!("Processing message {}: {}", message.id, message.body);
-    ///         
-    ///         // Process the message...
-    ///         
-    ///         // Delete when done
-    ///         client.delete_message(message.id).await?;
+    ///     let server_time = client.server_time().await?;
+    ///     match server_time.duration_since(SystemTime::now()) {
+    ///         Ok(ahead) => println!("Server clock is {:?} ahead", ahead),
+    ///         Err(e) => println!("Server clock is {:?} behind", e.duration()),
     ///     }
-    ///     
+    ///
     ///     Ok(())
     /// }
     /// ```
     ///
     /// # Errors
     ///
-    /// * [`TlqError::Validation`] if count is 0
-    /// * [`TlqError::Connection`] for network connectivity issues  
+    /// * [`TlqError::Unsupported`] if the response has no valid `Date` header
+    /// * [`TlqError::Connection`] for network connectivity issues
     /// * [`TlqError::Timeout`] if the request times out
-    /// * [`TlqError::Server`] for server-side errors (4xx/5xx HTTP responses)
-    pub async fn get_messages(&self, count: u32) -> Result<Vec<Message>> {
-        if count == 0 {
-            return Err(TlqError::Validation(
-                "Count must be greater than 0".to_string(),
-            ));
-        }
+    pub async fn server_time(&self) -> Result<SystemTime> {
+        let response = self.hello_with_timeout().await?;
 
-        let request = GetMessagesRequest { count };
-        let messages: Vec<Message> = self.request("/get", &request).await?;
-        Ok(messages)
+        response_date(&response).ok_or_else(|| {
+            TlqError::Unsupported("Server response has no valid Date header".to_string())
+        })
     }
 
-    /// Retrieves a single message from the TLQ server.
-    ///
-    /// This is a convenience method equivalent to calling [`get_messages(1)`](Self::get_messages)
-    /// and taking the first result. If no messages are available, returns `None`.
+    /// Returns the local time adjusted by the server's measured clock offset.
     ///
-    /// # Returns
+    /// The offset is measured from the `Date` header of the most recent
+    /// response that carried one, so it comes for free with normal requests.
+    /// Use the result instead of `SystemTime::now()` when comparing against
+    /// server timestamps such as [`lock_until`](Message::lock_until), so a
+    /// consumer with a fast clock doesn't treat a lock as expired early.
     ///
-    /// * `Ok(Some(message))` if a message was retrieved
-    /// * `Ok(None)` if no messages are available in the queue
-    /// * `Err` for connection or server errors
+    /// Returns `None` until a response with a `Date` header has been received.
+    /// Because the header has one-second resolution, the estimate can lag the
+    /// server by up to a second.
     ///
     /// # Examples
     ///
     /// ```no_run
     /// use tlq_client::TlqClient;
+    /// use std::time::SystemTime;
     ///
     /// #[tokio::main]
     /// async fn main() -> Result<(), tlq_client::TlqError> {
     ///     let client = TlqClient::new("localhost", 1337)?;
+    ///     let messages = client.get_messages(10).await?;
+    ///
+    ///     let now = client.estimated_server_time().unwrap_or_else(SystemTime::now);
+    ///     println!("{} messages fetched at server time {:?}", messages.len(), now);
     ///
-    ///     // Get a single message
-    ///     match client.get_message().await? {
-    ///         Some(message) => {
-    ///             println!("Got message: {}", message.body);
-    ///             client.delete_message(message.id).await?;
-    ///         }
-    ///         None => println!("No messages available"),
-    ///     }
-    ///     
     ///     Ok(())
     /// }
     /// ```
-    ///
-    /// # Errors
-    ///
-    /// * [`TlqError::Connection`] for network connectivity issues
-    /// * [`TlqError::Timeout`] if the request times out  
-    /// * [`TlqError::Server`] for server-side errors (4xx/5xx HTTP responses)
-    pub async fn get_message(&self) -> Result<Option<Message>> {
-        let messages = self.get_messages(1).await?;
-        Ok(messages.into_iter().next())
+    pub fn estimated_server_time(&self) -> Option<SystemTime> {
+        let offset_ms = (*self.server_clock_offset.lock().unwrap())?;
+        let offset = Duration::from_millis(offset_ms.unsigned_abs());
+        let now = SystemTime::now();
+
+        if offset_ms >= 0 {
+            Some(now + offset)
+        } else {
+            Some(now - offset)
+        }
     }
 
-    /// Deletes a single message from the TLQ server.
+    // Sends `/hello` after the startup jitter, within the health check
+    // timeout, and returns the raw response.
+    async fn hello_with_timeout(&self) -> Result<String> {
+        let health_timeout = self
+            .config
+            .operation_timeouts
+            .health
+            .unwrap_or(HEALTH_CHECK_TIMEOUT);
+
+        self.wait_startup_jitter().await;
+        timeout(health_timeout, self.hello())
+            .await
+            .map_err(|_| TlqError::Timeout(health_timeout.as_millis() as u64))?
+    }
+
+    async fn hello(&self) -> Result<String> {
+        let mut stream = self.open().await?;
+
+        let request = format!(
+            "GET /hello HTTP/1.1\r\n\
+             Host: {}\r\n\
+             Connection: close\r\n\
+             {}\
+             \r\n",
+            self.base_url,
+            self.extra_headers()?
+        );
+
+        stream.write_all(request.as_bytes()).await?;
+        stream.flush().await?;
+
+        let response = read_response(&mut stream).await?;
+
+        let response_str = self.decode_response(&response)?.into_owned();
+        self.record_server_date(&response_str);
+        Ok(response_str)
+    }
+
+    // Decodes a raw response as UTF-8, replacing invalid sequences only when
+    // `lossy_response_decode` is enabled.
+    fn decode_response<'a>(&self, response: &'a [u8]) -> Result<Cow<'a, str>> {
+        if self.config.lossy_response_decode {
+            return Ok(String::from_utf8_lossy(response));
+        }
+        std::str::from_utf8(response)
+            .map(Cow::Borrowed)
+            .map_err(|_| TlqError::Connection("invalid UTF-8 in response".to_string()))
+    }
+
+    // Updates the server clock offset from the response's `Date` header, if any.
+    fn record_server_date(&self, response: &str) {
+        let Some(server_time) = response_date(response) else {
+            return;
+        };
+        let local_time = SystemTime::now();
+        let offset_ms = match server_time.duration_since(local_time) {
+            Ok(ahead) => ahead.as_millis() as i64,
+            Err(behind) => -(behind.duration().as_millis() as i64),
+        };
+        *self.server_clock_offset.lock().unwrap() = Some(offset_ms);
+    }
+
+    /// Adds a new message to the TLQ server.
     ///
-    /// This is a convenience method that calls [`delete_messages`](Self::delete_messages)
-    /// with a single message ID.
+    /// The message will be assigned a UUID v7 identifier and placed in the queue
+    /// with state [`MessageState::Ready`]. Messages have a maximum size limit of
+    /// [`max_message_size`](ConfigBuilder::max_message_size) (64KB by default), or the
+    /// server-reported limit when [`auto_max_size`](ConfigBuilder::auto_max_size) is
+    /// enabled.
     ///
     /// # Arguments
     ///
-    /// * `id` - The UUID of the message to delete
+    /// * `body` - The message content (any type that can be converted to String)
     ///
     /// # Returns
     ///
-    /// Returns a string indicating the result of the operation (typically "Success" or a count).
+    /// Returns the created [`Message`] with its assigned ID and metadata.
     ///
     /// # Examples
     ///
@@ -417,10 +1049,13 @@ impl TlqClient {
     /// async fn main() -> Result<(), tlq_client::TlqError> {
     ///     let client = TlqClient::new("localhost", 1337)?;
     ///
-    ///     if let Some(message) = client.get_message().await? {
-    ///         let result = client.delete_message(message.id).await?;
-    ///         println!("Delete result: {}", result);
-    ///     }
+    ///     // Add a simple string message
+    ///     let message = client.add_message("Hello, World!").await?;
+    ///     println!("Created message {} with body: {}", message.id, message.body);
+    ///
+    ///     // Add a formatted message
+    ///     let user_data = "important data";
+    ///     let message = client.add_message(format!("Processing: {}", user_data)).await?;
     ///     
     ///     Ok(())
     /// }
@@ -428,171 +1063,179 @@ impl TlqClient {
     ///
     /// # Errors
     ///
+    /// * [`TlqError::MessageTooLarge`] if the message exceeds the configured maximum size
     /// * [`TlqError::Connection`] for network connectivity issues
     /// * [`TlqError::Timeout`] if the request times out
     /// * [`TlqError::Server`] for server-side errors (4xx/5xx HTTP responses)
-    pub async fn delete_message(&self, id: Uuid) -> Result<String> {
-        self.delete_messages(&[id]).await
+    pub async fn add_message(&self, body: impl Into<String>) -> Result<Message> {
+        Ok(self.add_message_detailed(body).await?.value)
     }
 
-    /// Deletes multiple messages from the TLQ server.
-    ///
-    /// This method removes the specified messages from the queue permanently.
-    /// Messages can be in any state when deleted.
-    ///
-    /// # Arguments
-    ///
-    /// * `ids` - A slice of message UUIDs to delete (must not be empty)
-    ///
-    /// # Returns
-    ///
-    /// Returns a string indicating the number of messages deleted or "Success".
-    ///
-    /// # Examples
-    ///
-    /// ```no_run
-    /// use tlq_client::TlqClient;
-    ///
-    /// #[tokio::main]
-    /// async fn main() -> Result<(), tlq_client::TlqError> {
-    ///     let client = TlqClient::new("localhost", 1337)?;
-    ///
-    ///     let messages = client.get_messages(3).await?;
-    ///     if !messages.is_empty() {
-    ///         let ids: Vec<_> = messages.iter().map(|m| m.id).collect();
-    ///         let result = client.delete_messages(&ids).await?;
-    ///         println!("Deleted {} messages", result);
-    ///     }
-    ///     
-    ///     Ok(())
-    /// }
-    /// ```
+    /// Like [`add_message`](Self::add_message), but also returns the response
+    /// metadata (see [`ResponseMeta`]).
     ///
     /// # Errors
     ///
-    /// * [`TlqError::Validation`] if the `ids` slice is empty
-    /// * [`TlqError::Connection`] for network connectivity issues
-    /// * [`TlqError::Timeout`] if the request times out
-    /// * [`TlqError::Server`] for server-side errors (4xx/5xx HTTP responses)
-    pub async fn delete_messages(&self, ids: &[Uuid]) -> Result<String> {
-        if ids.is_empty() {
-            return Err(TlqError::Validation("No message IDs provided".to_string()));
+    /// Returns the same errors as [`add_message`](Self::add_message).
+    pub async fn add_message_detailed(&self, body: impl Into<String>) -> Result<Response<Message>> {
+        let body = body.into();
+
+        let size = self.message_size(&body)?;
+        let max = self.max_message_size().await?;
+        if size > max {
+            return Err(TlqError::MessageTooLarge {
+                size,
+                max,
+                index: None,
+            });
         }
 
-        let request = DeleteMessagesRequest { ids: ids.to_vec() };
-        let response: String = self.request("/delete", &request).await?;
-        Ok(response)
+        if self.config.raw_body {
+            return self
+                .request_parts_detailed("/add", RAW_CONTENT_TYPE, &[body.as_bytes()])
+                .await;
+        }
+
+        let request = AddMessageRequest { body };
+        self.request_detailed("/add", &request).await
     }
 
-    /// Retries a single failed message on the TLQ server.
+    /// Adds a message whose body is `value` serialized as JSON.
     ///
-    /// This is a convenience method that calls [`retry_messages`](Self::retry_messages)
-    /// with a single message ID. The message state will be changed from
-    /// [`MessageState::Failed`] back to [`MessageState::Ready`].
+    /// Saves serializing to a string before [`add_message`](Self::add_message);
+    /// read such messages back with [`get_messages_json`](Self::get_messages_json).
+    /// The size limit is checked against the serialized JSON.
     ///
     /// # Arguments
     ///
-    /// * `id` - The UUID of the message to retry
-    ///
-    /// # Returns
-    ///
-    /// Returns a string indicating the result of the operation (typically "Success" or a count).
+    /// * `value` - The value to send as the message body
     ///
     /// # Examples
     ///
     /// ```no_run
-    /// use tlq_client::{TlqClient, MessageState};
+    /// use serde::Serialize;
+    /// use tlq_client::TlqClient;
+    ///
+    /// #[derive(Serialize)]
+    /// struct Order {
+    ///     id: u64,
+    ///     total_cents: u64,
+    /// }
     ///
     /// #[tokio::main]
     /// async fn main() -> Result<(), tlq_client::TlqError> {
     ///     let client = TlqClient::new("localhost", 1337)?;
     ///
-    ///     // Find failed messages and retry them
-    ///     let messages = client.get_messages(10).await?;
-    ///     for message in messages {
-    ///         if message.state == MessageState::Failed {
-    ///             let result = client.retry_message(message.id).await?;
-    ///             println!("Retry result: {}", result);
-    ///         }
-    ///     }
-    ///     
+    ///     let order = Order { id: 42, total_cents: 1999 };
+    ///     let message = client.add_message_json(&order).await?;
+    ///     println!("Queued order as {}", message.id);
+    ///
     ///     Ok(())
     /// }
     /// ```
     ///
     /// # Errors
     ///
-    /// * [`TlqError::Connection`] for network connectivity issues
-    /// * [`TlqError::Timeout`] if the request times out
-    /// * [`TlqError::Server`] for server-side errors (4xx/5xx HTTP responses)
-    pub async fn retry_message(&self, id: Uuid) -> Result<String> {
-        self.retry_messages(&[id]).await
+    /// * [`TlqError::Serialization`] if `value` can't be serialized
+    /// * Otherwise the same errors as [`add_message`](Self::add_message)
+    pub async fn add_message_json<T>(&self, value: &T) -> Result<Message>
+    where
+        T: Serialize + ?Sized,
+    {
+        let body = serde_json::to_string(value)?;
+        self.add_message(body).await
     }
 
-    /// Retries multiple failed messages on the TLQ server.
-    ///
-    /// This method changes the state of the specified messages from [`MessageState::Failed`]
-    /// back to [`MessageState::Ready`], making them available for processing again.
-    /// The retry count for each message will be incremented.
-    ///
-    /// # Arguments
+    /// Adds a new message from a [`Bytes`](bytes::Bytes) buffer without copying it.
     ///
-    /// * `ids` - A slice of message UUIDs to retry (must not be empty)
+    /// Intended for forwarding payloads that already live in a `Bytes` buffer.
+    /// The buffer is written to the socket as-is, alongside the JSON framing,
+    /// using vectored I/O instead of being copied into an intermediate `String`.
+    /// Bodies containing characters that must be escaped in JSON (quotes,
+    /// backslashes and control characters) are escaped into a new buffer first.
     ///
-    /// # Returns
+    /// The size limit is checked against `body.len()`, just like
+    /// [`add_message`](Self::add_message).
     ///
-    /// Returns a string indicating the number of messages retried or "Success".
+    /// Requires the `bytes` feature.
     ///
     /// # Examples
     ///
     /// ```no_run
-    /// use tlq_client::{TlqClient, MessageState};
+    /// use bytes::Bytes;
+    /// use tlq_client::TlqClient;
     ///
     /// #[tokio::main]
     /// async fn main() -> Result<(), tlq_client::TlqError> {
     ///     let client = TlqClient::new("localhost", 1337)?;
     ///
-    ///     // Get all messages and retry the failed ones
-    ///     let messages = client.get_messages(100).await?;
-    ///     let failed_ids: Vec<_> = messages
-    ///         .iter()
-    ///         .filter(|m| m.state == MessageState::Failed)
-    ///         .map(|m| m.id)
-    ///         .collect();
+    ///     let payload = Bytes::from_static(b"forwarded payload");
+    ///     let message = client.add_bytes_zerocopy(payload).await?;
+    ///     println!("Forwarded as message {}", message.id);
     ///
-    ///     if !failed_ids.is_empty() {
-    ///         let result = client.retry_messages(&failed_ids).await?;
-    ///         println!("Retried {} failed messages", result);
-    ///     }
-    ///     
     ///     Ok(())
     /// }
     /// ```
     ///
     /// # Errors
     ///
-    /// * [`TlqError::Validation`] if the `ids` slice is empty
+    /// * [`TlqError::Validation`] if the body is not valid UTF-8
+    /// * [`TlqError::MessageTooLarge`] if the message exceeds the size limit
     /// * [`TlqError::Connection`] for network connectivity issues
     /// * [`TlqError::Timeout`] if the request times out
     /// * [`TlqError::Server`] for server-side errors (4xx/5xx HTTP responses)
-    pub async fn retry_messages(&self, ids: &[Uuid]) -> Result<String> {
-        if ids.is_empty() {
-            return Err(TlqError::Validation("No message IDs provided".to_string()));
+    #[cfg(feature = "bytes")]
+    pub async fn add_bytes_zerocopy(&self, body: bytes::Bytes) -> Result<Message> {
+        let text = std::str::from_utf8(&body)
+            .map_err(|_| TlqError::Validation("Message body is not valid UTF-8".to_string()))?;
+
+        let size = self.message_size(text)?;
+        let max = self.max_message_size().await?;
+        if size > max {
+            return Err(TlqError::MessageTooLarge {
+                size,
+                max,
+                index: None,
+            });
         }
 
-        let request = RetryMessagesRequest { ids: ids.to_vec() };
-        let response: String = self.request("/retry", &request).await?;
-        Ok(response)
+        if self.config.raw_body {
+            return self.request_parts("/add", RAW_CONTENT_TYPE, &[&body]).await;
+        }
+
+        let needs_escaping = body.iter().any(|&b| b == b'"' || b == b'\\' || b < 0x20);
+        if needs_escaping {
+            let request = AddMessageRequest {
+                body: text.to_string(),
+            };
+            return self.request("/add", &request).await;
+        }
+
+        self.request_parts("/add", JSON_CONTENT_TYPE, &[b"{\"body\":\"", &body, b"\"}"])
+            .await
     }
 
-    /// Removes all messages from the TLQ server queue.
+    /// Adds several messages to the TLQ server using batch requests.
     ///
-    /// This method permanently deletes all messages in the queue regardless of their state.
-    /// Use with caution as this operation cannot be undone.
+    /// Bodies are sent to the server's batch endpoint instead of one request
+    /// per message. To stay within the server's request size limit, they are
+    /// split across as many requests as needed so that no request body exceeds
+    /// [`max_request_bytes`](ConfigBuilder::max_request_bytes). Each body must
+    /// fit the message size limit, as with [`add_message`](Self::add_message).
+    ///
+    /// If the server has no batch endpoint, the bodies are added with one
+    /// `/add` request each instead, in order, pipelined over a single
+    /// connection. Bodies the server leaves unanswered, for example because it
+    /// closes the connection after each response, are added one by one with
+    /// [`add_message`](Self::add_message).
+    ///
+    /// # Arguments
+    ///
+    /// * `bodies` - The message contents (must not be empty)
     ///
     /// # Returns
     ///
-    /// Returns a string indicating the result of the operation (typically "Success").
+    /// Returns the created messages in the same order as `bodies`.
     ///
     /// # Examples
     ///
@@ -603,346 +1246,6904 @@ impl TlqClient {
     /// async fn main() -> Result<(), tlq_client::TlqError> {
     ///     let client = TlqClient::new("localhost", 1337)?;
     ///
-    ///     // Clear all messages from the queue
-    ///     let result = client.purge_queue().await?;
-    ///     println!("Purge result: {}", result);
-    ///     
+    ///     let messages = client.add_messages(["first", "second", "third"]).await?;
+    ///     assert_eq!(messages.len(), 3);
+    ///
     ///     Ok(())
     /// }
     /// ```
     ///
     /// # Errors
     ///
+    /// * [`TlqError::Validation`] if `bodies` is empty
+    /// * [`TlqError::MessageTooLarge`] if any body exceeds the size limit, with
+    ///   `index` set to the position of the first such body
     /// * [`TlqError::Connection`] for network connectivity issues
-    /// * [`TlqError::Timeout`] if the request times out
+    /// * [`TlqError::Timeout`] if a request times out
     /// * [`TlqError::Server`] for server-side errors (4xx/5xx HTTP responses)
-    pub async fn purge_queue(&self) -> Result<String> {
-        let response: String = self.request("/purge", &serde_json::json!({})).await?;
-        Ok(response)
-    }
+    ///
+    /// Size checks happen before anything is sent. If a later request fails,
+    /// the messages added by earlier requests stay in the queue.
+    pub async fn add_messages<I, S>(&self, bodies: I) -> Result<Vec<Message>>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let bodies: Vec<String> = bodies.into_iter().map(Into::into).collect();
+        if bodies.is_empty() {
+            return Err(TlqError::Validation(
+                "No message bodies provided".to_string(),
+            ));
+        }
 
-    // Helper function to parse HTTP response - extracted for testing
-    fn parse_http_response(response: &str) -> Result<&str> {
-        if let Some(body_start) = response.find("\r\n\r\n") {
-            let headers = &response[..body_start];
-            let body = &response[body_start + 4..];
+        let max_size = self.max_message_size().await?;
+        for (index, body) in bodies.iter().enumerate() {
+            let size = self.message_size(body)?;
+            if size > max_size {
+                return Err(TlqError::MessageTooLarge {
+                    size,
+                    max: max_size,
+                    index: Some(index),
+                });
+            }
+        }
 
-            if let Some(status_line) = headers.lines().next() {
-                let parts: Vec<&str> = status_line.split_whitespace().collect();
-                if parts.len() >= 2 {
-                    if let Ok(status_code) = parts[1].parse::<u16>() {
-                        if status_code >= 400 {
-                            return Err(TlqError::Server {
-                                status: status_code,
-                                message: body.to_string(),
-                            });
-                        }
-                    }
+        let mut messages = Vec::with_capacity(bodies.len());
+        for chunk in self.split_by_request_size(&bodies)? {
+            let request = AddMessagesRequest { bodies: chunk };
+            match self
+                .request::<_, Vec<Message>>("/add_batch", &request)
+                .await
+            {
+                Ok(added) => messages.extend(added),
+                // Servers without a batch endpoint get one add per body
+                Err(TlqError::Server { status: 404, .. }) if messages.is_empty() => {
+                    return self.add_pipelined(&bodies).await;
                 }
+                Err(e) => return Err(e),
             }
+        }
+        Ok(messages)
+    }
 
-            Ok(body)
+    // Adds `bodies` with one `/add` request each, pipelined over a single
+    // connection. Bodies left unanswered because the server closed the
+    // connection early, or failed with an error a retry would cover, are
+    // added one by one with `add_message` instead.
+    async fn add_pipelined(&self, bodies: &[String]) -> Result<Vec<Message>> {
+        let content_type = if self.config.raw_body {
+            RAW_CONTENT_TYPE
         } else {
-            Err(TlqError::Connection("Invalid HTTP response".to_string()))
+            JSON_CONTENT_TYPE
+        };
+        let requests = bodies
+            .iter()
+            .map(|body| {
+                let request = if self.config.raw_body {
+                    body.as_bytes().to_vec()
+                } else {
+                    serde_json::to_vec(&AddMessageRequest { body: body.clone() })?
+                };
+                self.check_request_size(&[&request])?;
+                Ok(request)
+            })
+            .collect::<Result<Vec<_>>>()?;
+        self.wait_startup_jitter().await;
+
+        let mut messages = Vec::with_capacity(bodies.len());
+        let result = traced(
+            "/add",
+            self.pipeline("/add", content_type, &requests, &mut messages),
+        )
+        .await;
+        match result {
+            Err(err)
+                if !err.is_retryable()
+                    || (matches!(err, TlqError::Timeout(_)) && !self.config.retry_on_timeout) =>
+            {
+                return Err(err)
+            }
+            _ => {}
         }
+
+        for body in &bodies[messages.len()..] {
+            messages.push(self.add_message(body.as_str()).await?);
+        }
+        Ok(messages)
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    // Sends `requests` to `endpoint` over one connection and collects the
+    // responses, in order, into `values`. Requests are written while responses
+    // are read, so neither side stalls on a full socket buffer. Stops early,
+    // without an error, after a response that closes the connection. Each
+    // response must arrive within the endpoint's timeout.
+    async fn pipeline<R>(
+        &self,
+        endpoint: &str,
+        content_type: &str,
+        requests: &[Vec<u8>],
+        values: &mut Vec<R>,
+    ) -> Result<()>
+    where
+        R: DeserializeOwned,
+    {
+        let _in_flight = InFlightGuard::new(&self.in_flight);
+        let request_timeout = self.config.timeout_for(endpoint);
+        let timed_out = || TlqError::Timeout(request_timeout.as_millis() as u64);
 
-    #[test]
-    fn test_parse_http_response_success() {
-        let response =
-            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\n\r\n{\"message\":\"success\"}";
+        let heads = requests
+            .iter()
+            .enumerate()
+            .map(|(i, request)| {
+                self.post_head(
+                    endpoint,
+                    content_type,
+                    request.len(),
+                    i + 1 == requests.len(),
+                )
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let mut parts: Vec<_> = heads
+            .iter()
+            .zip(requests)
+            .flat_map(|(head, request)| [IoSlice::new(head.as_bytes()), IoSlice::new(request)])
+            .collect();
+        let sent = heads.iter().map(String::len).sum::<usize>()
+            + requests.iter().map(Vec::len).sum::<usize>();
+
+        let stream = timeout(request_timeout, self.open())
+            .await
+            .map_err(|_| timed_out())??;
+        let (mut reader, mut writer) = tokio::io::split(stream);
+
+        // A failed write shows up as a missing response, unless the server
+        // closed the connection on purpose before reading everything
+        let write = async {
+            if write_all_vectored(&mut writer, &mut parts).await.is_ok()
+                && writer.flush().await.is_ok()
+            {
+                self.stats.record_sent(sent);
+            }
+            std::future::pending::<()>().await
+        };
+        let read = async {
+            let mut carry = Vec::new();
+            while values.len() < requests.len() {
+                let started = Instant::now();
+                let response = timeout(
+                    request_timeout,
+                    read_response_buffered(&mut reader, &mut carry),
+                )
+                .await
+                .map_err(|_| timed_out())
+                .and_then(|result| result);
+                let result = response.and_then(|response| {
+                    self.stats.record_received(response.len());
+                    let value = self.parse_response(endpoint, &response)?.value;
+                    let close = std::str::from_utf8(&response)
+                        .ok()
+                        .and_then(|response| response_header(response, "connection"))
+                        .is_some_and(|value| value.eq_ignore_ascii_case("close"));
+                    Ok((value, close))
+                });
+                self.stats.record_request(
+                    started.elapsed(),
+                    result.is_ok(),
+                    matches!(result, Err(TlqError::Timeout(_))),
+                );
+                let (value, close) = result?;
+                values.push(value);
+                if close {
+                    break;
+                }
+            }
+            Ok(())
+        };
+
+        // Writing stops once the reads are done, even if it is still blocked
+        tokio::select! {
+            result = read => result,
+            () = write => unreachable!(),
+        }
+    }
+
+    // Splits `bodies` into consecutive chunks whose batch request bodies stay
+    // within `max_request_bytes`. A body too large on its own gets its own chunk.
+    fn split_by_request_size<'a>(&self, bodies: &'a [String]) -> Result<Vec<&'a [String]>> {
+        // Size of `{"bodies":[]}`
+        const ENVELOPE: usize = 13;
+
+        let limit = self.config.max_request_bytes;
+        let mut chunks = Vec::new();
+        let mut start = 0;
+        let mut size = ENVELOPE;
+
+        for (i, body) in bodies.iter().enumerate() {
+            let encoded = serde_json::to_string(body)?.len();
+            let separator = usize::from(i > start);
+            if i > start && size + separator + encoded > limit {
+                chunks.push(&bodies[start..i]);
+                start = i;
+                size = ENVELOPE + encoded;
+            } else {
+                size += separator + encoded;
+            }
+        }
+        chunks.push(&bodies[start..]);
+
+        Ok(chunks)
+    }
+
+    /// Retrieves multiple messages from the TLQ server.
+    ///
+    /// This method fetches up to `count` messages from the queue. Messages are returned
+    /// in the order they were added and their state is changed to [`MessageState::Processing`].
+    /// The server may return fewer messages than requested if there are not enough
+    /// messages in the queue.
+    ///
+    /// # Arguments
+    ///
+    /// * `count` - Maximum number of messages to retrieve (must be greater than 0)
+    ///
+    /// # Returns
+    ///
+    /// Returns a vector of [`Message`] objects. The vector may be empty if no messages
+    /// are available in the queue.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tlq_client::TlqClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), tlq_client::TlqError> {
+    ///     let client = TlqClient::new("localhost", 1337)?;
+    ///
+    ///     // Get up to 5 messages from the queue
+    ///     let messages = client.get_messages(5).await?;
+    ///     
+    ///     for message in messages {
+    ///         println!("Processing message {}: {}", message.id, message.body);
+    ///         
+    ///         // Process the message...
+    ///         
+    ///         // Delete when done
+    ///         client.delete_message(message.id).await?;
+    ///     }
+    ///     
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// * [`TlqError::Validation`] if count is 0
+    /// * [`TlqError::Connection`] for network connectivity issues  
+    /// * [`TlqError::Timeout`] if the request times out
+    /// * [`TlqError::Server`] for server-side errors (4xx/5xx HTTP responses)
+    pub async fn get_messages(&self, count: u32) -> Result<Vec<Message>> {
+        Ok(self.get_messages_detailed(count).await?.value)
+    }
+
+    /// Like [`get_messages`](Self::get_messages), but also returns the response
+    /// metadata (see [`ResponseMeta`]).
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`get_messages`](Self::get_messages).
+    pub async fn get_messages_detailed(&self, count: u32) -> Result<Response<Vec<Message>>> {
+        if count == 0 {
+            return Err(TlqError::Validation(
+                "Count must be greater than 0".to_string(),
+            ));
+        }
+
+        let request = GetMessagesRequest {
+            count,
+            wait_ms: None,
+        };
+        let response: Response<MessagesResponse> = self.request_detailed("/get", &request).await?;
+        Ok(response.map(|MessagesResponse(messages)| messages))
+    }
+
+    /// Retrieves up to `count` messages, letting the server hold the request
+    /// open for up to `wait` until at least one message is available.
+    ///
+    /// Long polling avoids the latency of polling [`get_messages`](Self::get_messages)
+    /// in a sleep loop: the server answers as soon as a message arrives, or with
+    /// an empty vector once `wait` elapses. The wait is sent as `wait_ms`;
+    /// servers without long-poll support ignore it and answer right away.
+    ///
+    /// Instead of the configured request [`timeout`](ConfigBuilder::timeout),
+    /// this call times out after `wait` plus
+    /// [`network_slack`](ConfigBuilder::network_slack), so the long poll itself
+    /// doesn't time out. With the `tracing` feature, a warning is logged when
+    /// the configured timeout would have cut the poll short. The server may
+    /// hold the request for less than `wait` if that exceeds its own maximum
+    /// wait.
+    ///
+    /// # Arguments
+    ///
+    /// * `count` - Maximum number of messages to retrieve (must be greater than 0)
+    /// * `wait` - How long the server may wait for a message
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tlq_client::TlqClient;
+    /// use std::time::Duration;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), tlq_client::TlqError> {
+    ///     let client = TlqClient::new("localhost", 1337)?;
+    ///
+    ///     loop {
+    ///         for message in client.get_messages_wait(10, Duration::from_secs(5)).await? {
+    ///             println!("Processing {}", message.body);
+    ///             client.delete_message(message.id).await?;
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// * [`TlqError::Validation`] if count is 0
+    /// * [`TlqError::Connection`] for network connectivity issues
+    /// * [`TlqError::Timeout`] if the request times out
+    /// * [`TlqError::Server`] for server-side errors (4xx/5xx HTTP responses)
+    pub async fn get_messages_wait(&self, count: u32, wait: Duration) -> Result<Vec<Message>> {
+        if count == 0 {
+            return Err(TlqError::Validation(
+                "Count must be greater than 0".to_string(),
+            ));
+        }
+
+        let mut client = self.clone();
+        client.config.operation_timeouts.get = Some(self.long_poll_timeout(wait));
+        let request = GetMessagesRequest {
+            count,
+            wait_ms: Some(wait.as_millis() as u64),
+        };
+        let MessagesResponse(messages) = client.request("/get", &request).await?;
+        Ok(messages)
+    }
+
+    // The request timeout of a long poll that waits up to `wait`.
+    fn long_poll_timeout(&self, wait: Duration) -> Duration {
+        let timeout = wait.saturating_add(self.config.network_slack);
+        #[cfg(feature = "tracing")]
+        if self.config.timeout_for("/get") < timeout {
+            tracing::warn!(
+                configured = ?self.config.timeout_for("/get"),
+                long_poll = ?timeout,
+                "request timeout would cut the long poll short, extending it"
+            );
+        }
+        timeout
+    }
+
+    /// Retrieves up to `count` messages and parses each body as JSON into `T`.
+    ///
+    /// The counterpart of [`add_message_json`](Self::add_message_json). Each
+    /// message is returned together with its parsed body.
+    ///
+    /// If a body doesn't parse, [`TlqError::InvalidBody`] is returned with the
+    /// offending message, which stays locked so it can be deleted or failed.
+    /// The other messages of the same fetch are handed back to the queue with
+    /// [`retry_messages`](Self::retry_messages), which counts as a retry for
+    /// them. Fetch one message at a time where that matters.
+    ///
+    /// # Arguments
+    ///
+    /// * `count` - Maximum number of messages to retrieve (must be greater than 0)
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use serde::Deserialize;
+    /// use tlq_client::{TlqClient, TlqError};
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Order {
+    ///     id: u64,
+    ///     total_cents: u64,
+    /// }
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), TlqError> {
+    ///     let client = TlqClient::new("localhost", 1337)?;
+    ///
+    ///     match client.get_messages_json::<Order>(10).await {
+    ///         Ok(orders) => {
+    ///             for (message, order) in orders {
+    ///                 println!("Order {}: {} cents", order.id, order.total_cents);
+    ///                 client.delete_message(message.id).await?;
+    ///             }
+    ///         }
+    ///         Err(TlqError::InvalidBody { message, source }) => {
+    ///             eprintln!("Dropping malformed message {}: {}", message.id, source);
+    ///             client.delete_message(message.id).await?;
+    ///         }
+    ///         Err(e) => return Err(e),
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// * [`TlqError::InvalidBody`] if a message body doesn't parse as `T`
+    /// * Otherwise the same errors as [`get_messages`](Self::get_messages)
+    pub async fn get_messages_json<T>(&self, count: u32) -> Result<Vec<(Message, T)>>
+    where
+        T: DeserializeOwned,
+    {
+        let mut messages = self.get_messages(count).await?.into_iter();
+        let mut parsed = Vec::with_capacity(messages.len());
+
+        while let Some(message) = messages.next() {
+            match serde_json::from_str(&message.body) {
+                Ok(value) => parsed.push((message, value)),
+                Err(source) => {
+                    let ids: Vec<Uuid> = parsed
+                        .iter()
+                        .map(|(message, _)| message.id)
+                        .chain(messages.map(|message| message.id))
+                        .collect();
+                    if !ids.is_empty() {
+                        let _ = self.retry_messages(&ids).await;
+                    }
+                    return Err(TlqError::InvalidBody {
+                        message: Box::new(message),
+                        source,
+                    });
+                }
+            }
+        }
+        Ok(parsed)
+    }
+
+    /// Retrieves between `min` and `max` messages, waiting up to `wait` for at
+    /// least `min` to arrive.
+    ///
+    /// Meant for batch-oriented consumers that want a minimum batch size to
+    /// amortize per-batch work such as bulk database writes. Messages are
+    /// collected with [`get_messages_wait`](Self::get_messages_wait) long polls
+    /// for the missing messages, each waiting on the server for whatever is
+    /// left of `wait`, until there are at least `min`. Once `wait` has
+    /// elapsed, whatever has arrived is returned, which may be fewer than
+    /// `min` or none at all. At most `max` messages are returned.
+    ///
+    /// Messages collected early are already locked while the call waits for
+    /// more, so keep `wait` well below the server's lock timeout. If a fetch
+    /// fails, the error is returned and messages collected so far stay locked
+    /// until their lock expires.
+    ///
+    /// # Arguments
+    ///
+    /// * `min` - Number of messages to wait for (must be greater than 0)
+    /// * `max` - Maximum number of messages to return (must be at least `min`)
+    /// * `wait` - How long to wait for `min` messages
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tlq_client::TlqClient;
+    /// use std::time::Duration;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), tlq_client::TlqError> {
+    ///     let client = TlqClient::new("localhost", 1337)?;
+    ///
+    ///     let batch = client
+    ///         .get_messages_range(50, 200, Duration::from_secs(5))
+    ///         .await?;
+    ///     println!("Writing {} messages in one transaction", batch.len());
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// * [`TlqError::Validation`] if `min` is 0 or greater than `max`
+    /// * [`TlqError::Connection`] for network connectivity issues
+    /// * [`TlqError::Timeout`] if a request times out
+    /// * [`TlqError::Server`] for server-side errors (4xx/5xx HTTP responses)
+    pub async fn get_messages_range(
+        &self,
+        min: u32,
+        max: u32,
+        wait: Duration,
+    ) -> Result<Vec<Message>> {
+        if min == 0 {
+            return Err(TlqError::Validation(
+                "Minimum count must be greater than 0".to_string(),
+            ));
+        }
+        if min > max {
+            return Err(TlqError::Validation(format!(
+                "Minimum count {} exceeds maximum count {}",
+                min, max
+            )));
+        }
+
+        let deadline = tokio::time::Instant::now() + wait;
+        let mut messages = Vec::new();
+
+        loop {
+            // Rounded down to whole milliseconds, as sent to the server, so a
+            // sub-millisecond remainder ends with one last poll that doesn't wait
+            let remaining = deadline.saturating_duration_since(tokio::time::Instant::now());
+            let remaining = Duration::from_millis(remaining.as_millis() as u64);
+            let count = max - messages.len() as u32;
+            messages.extend(self.get_messages_wait(count, remaining).await?);
+            if messages.len() >= min as usize || remaining.is_zero() {
+                return Ok(messages);
+            }
+        }
+    }
+
+    /// Retrieves a single message from the TLQ server.
+    ///
+    /// This is a convenience method equivalent to calling [`get_messages(1)`](Self::get_messages)
+    /// and taking the first result. If no messages are available, returns `None`.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Some(message))` if a message was retrieved
+    /// * `Ok(None)` if no messages are available in the queue
+    /// * `Err` for connection or server errors
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tlq_client::TlqClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), tlq_client::TlqError> {
+    ///     let client = TlqClient::new("localhost", 1337)?;
+    ///
+    ///     // Get a single message
+    ///     match client.get_message().await? {
+    ///         Some(message) => {
+    ///             println!("Got message: {}", message.body);
+    ///             client.delete_message(message.id).await?;
+    ///         }
+    ///         None => println!("No messages available"),
+    ///     }
+    ///     
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// * [`TlqError::Connection`] for network connectivity issues
+    /// * [`TlqError::Timeout`] if the request times out  
+    /// * [`TlqError::Server`] for server-side errors (4xx/5xx HTTP responses)
+    pub async fn get_message(&self) -> Result<Option<Message>> {
+        let messages = self.get_messages(1).await?;
+        Ok(messages.into_iter().next())
+    }
+
+    /// Retrieves up to `count` messages as a [`Batch`] for grouped acknowledgement.
+    ///
+    /// The batch tracks which messages were acknowledged (to be deleted) or
+    /// negatively acknowledged (to be retried) and commits all decisions with at
+    /// most one delete and one retry request. See [`Batch`] for details.
+    ///
+    /// # Arguments
+    ///
+    /// * `count` - Maximum number of messages to retrieve (must be greater than 0)
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tlq_client::TlqClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), tlq_client::TlqError> {
+    ///     let client = TlqClient::new("localhost", 1337)?;
+    ///
+    ///     let mut batch = client.get_batch(10).await?;
+    ///     let failed: Vec<_> = batch
+    ///         .messages()
+    ///         .iter()
+    ///         .filter(|m| m.body.contains("error"))
+    ///         .map(|m| m.id)
+    ///         .collect();
+    ///
+    ///     batch.ack_all();
+    ///     for id in failed {
+    ///         batch.nack(id);
+    ///     }
+    ///     batch.commit().await?;
+    ///     
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Same as [`get_messages`](Self::get_messages).
+    pub async fn get_batch(&self, count: u32) -> Result<Batch<'_>> {
+        let messages = self.get_messages(count).await?;
+        Ok(Batch::new(self, messages))
+    }
+
+    /// Returns a [`Producer`] that holds adds back while the queue is near capacity.
+    ///
+    /// Use it instead of [`add_message`](Self::add_message) when producers can
+    /// outpace consumers, so a bounded queue isn't driven into rejecting adds.
+    /// See [`Producer`] for how the queue depth is checked.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::time::Duration;
+    /// use tlq_client::{ProducerOptions, TlqClient};
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), tlq_client::TlqError> {
+    ///     let client = TlqClient::new("localhost", 1337)?;
+    ///
+    ///     let mut producer = client.producer(ProducerOptions {
+    ///         high_water: 5_000,
+    ///         poll_interval: Duration::from_secs(1),
+    ///         ..ProducerOptions::default()
+    ///     });
+    ///     producer.add_message("order created").await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn producer(&self, options: ProducerOptions) -> Producer<'_> {
+        Producer::new(self, options)
+    }
+
+    /// Returns up to `count` messages from the head of the queue without claiming them.
+    ///
+    /// Unlike [`get_messages`](Self::get_messages), peeking leaves messages in
+    /// their current state: they are not locked, moved to
+    /// [`MessageState::Processing`], or hidden from other consumers. Messages are
+    /// returned in queue (FIFO) order.
+    ///
+    /// # Arguments
+    ///
+    /// * `count` - Maximum number of messages to return (must be greater than 0)
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tlq_client::TlqClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), tlq_client::TlqError> {
+    ///     let client = TlqClient::new("localhost", 1337)?;
+    ///
+    ///     for message in client.peek_messages(5).await? {
+    ///         println!("Next up: {}", message.body);
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// * [`TlqError::Validation`] if count is 0
+    /// * [`TlqError::Server`] if the server does not support peeking; there is
+    ///   deliberately no fallback to [`get_messages`](Self::get_messages), which
+    ///   would claim the messages
+    /// * [`TlqError::Connection`] for network connectivity issues
+    /// * [`TlqError::Timeout`] if the request times out
+    pub async fn peek_messages(&self, count: u32) -> Result<Vec<Message>> {
+        if count == 0 {
+            return Err(TlqError::Validation(
+                "Count must be greater than 0".to_string(),
+            ));
+        }
+
+        let request = GetMessagesRequest {
+            count,
+            wait_ms: None,
+        };
+        let MessagesResponse(messages) = self.request("/peek", &request).await?;
+        Ok(messages)
+    }
+
+    /// Returns the next message in `state` that the server would deliver, without claiming it.
+    ///
+    /// The server picks the head message among those in `state`, following its
+    /// own delivery ordering for that state, and returns it without locking it
+    /// or changing any other message. Useful for admin "next up" views.
+    ///
+    /// If the server doesn't support this, the first 100 messages are inspected
+    /// with [`peek_messages`](Self::peek_messages) instead and the first one in
+    /// `state` is returned; a matching message further back isn't found then.
+    ///
+    /// # Arguments
+    ///
+    /// * `state` - The state the message must be in
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Some(message))` with the next message in `state`
+    /// * `Ok(None)` if no message is in `state`
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tlq_client::{MessageState, TlqClient};
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), tlq_client::TlqError> {
+    ///     let client = TlqClient::new("localhost", 1337)?;
+    ///
+    ///     if let Some(message) = client.peek_next(MessageState::Ready).await? {
+    ///         println!("Next up: {}", message.body);
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// * [`TlqError::Connection`] for network connectivity issues
+    /// * [`TlqError::Timeout`] if the request times out
+    /// * [`TlqError::Server`] for server-side errors (4xx/5xx HTTP responses)
+    pub async fn peek_next(&self, state: MessageState) -> Result<Option<Message>> {
+        let request = PeekNextRequest {
+            state: state.clone(),
+        };
+
+        match self.request("/peek_next", &request).await {
+            Err(TlqError::Server { status: 404, .. }) => Ok(self
+                .peek_messages(PEEK_NEXT_FALLBACK_COUNT)
+                .await?
+                .into_iter()
+                .find(|message| message.state == state)),
+            result => result,
+        }
+    }
+
+    /// Returns the failed messages among the first `count` messages in the queue.
+    ///
+    /// The queue is inspected with [`peek_messages`](Self::peek_messages), so no
+    /// message is claimed or changes state; the result keeps only messages in the
+    /// [`MessageState::Failed`] state. Each message's
+    /// [`failure_reason`](Message::failure_reason) is set when the server records
+    /// one. Useful for building dead letter inspection tools.
+    ///
+    /// # Arguments
+    ///
+    /// * `count` - Number of messages to inspect (must be greater than 0)
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tlq_client::TlqClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), tlq_client::TlqError> {
+    ///     let client = TlqClient::new("localhost", 1337)?;
+    ///
+    ///     for message in client.get_failed(100).await? {
+    ///         let reason = message.failure_reason.as_deref().unwrap_or("unknown");
+    ///         println!("{} failed: {}", message.id, reason);
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`peek_messages`](Self::peek_messages).
+    pub async fn get_failed(&self, count: u32) -> Result<Vec<Message>> {
+        let mut messages = self.peek_messages(count).await?;
+        messages.retain(|message| message.state == MessageState::Failed);
+        Ok(messages)
+    }
+
+    /// Returns the age of the oldest message at the head of the queue.
+    ///
+    /// The age is measured from the creation time embedded in the message's
+    /// UUID v7 identifier (see [`Message::created_at`]) to now, which makes it a
+    /// simple consumer lag metric: it grows while consumers fall behind. The
+    /// queue is inspected with [`peek_messages`](Self::peek_messages), so no
+    /// message is claimed.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Some(age))` for the message at the head of the queue
+    /// * `Ok(None)` if the queue is empty or the head message has no timestamp
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tlq_client::TlqClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), tlq_client::TlqError> {
+    ///     let client = TlqClient::new("localhost", 1337)?;
+    ///
+    ///     match client.consumer_lag().await? {
+    ///         Some(lag) => println!("Oldest message is {:?} old", lag),
+    ///         None => println!("Queue is empty"),
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`peek_messages`](Self::peek_messages).
+    pub async fn consumer_lag(&self) -> Result<Option<Duration>> {
+        let messages = self.peek_messages(1).await?;
+
+        Ok(messages
+            .first()
+            .and_then(Message::created_at)
+            .map(|created_at| created_at.elapsed().unwrap_or(Duration::ZERO)))
+    }
+
+    /// Deletes a single message from the TLQ server.
+    ///
+    /// This is a convenience method that calls [`delete_messages`](Self::delete_messages)
+    /// with a single message ID.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The UUID of the message to delete
+    ///
+    /// # Returns
+    ///
+    /// Returns a string indicating the result of the operation (typically "Success" or a count).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tlq_client::TlqClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), tlq_client::TlqError> {
+    ///     let client = TlqClient::new("localhost", 1337)?;
+    ///
+    ///     if let Some(message) = client.get_message().await? {
+    ///         let result = client.delete_message(message.id).await?;
+    ///         println!("Delete result: {}", result);
+    ///     }
+    ///     
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// * [`TlqError::Connection`] for network connectivity issues
+    /// * [`TlqError::Timeout`] if the request times out
+    /// * [`TlqError::Server`] for server-side errors (4xx/5xx HTTP responses)
+    pub async fn delete_message(&self, id: Uuid) -> Result<String> {
+        self.delete_messages(&[id]).await
+    }
+
+    /// Deletes multiple messages from the TLQ server.
+    ///
+    /// This method removes the specified messages from the queue permanently.
+    /// Messages can be in any state when deleted.
+    ///
+    /// # Arguments
+    ///
+    /// * `ids` - A slice of message UUIDs to delete (must not be empty)
+    ///
+    /// # Returns
+    ///
+    /// Returns a string indicating the number of messages deleted or "Success".
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tlq_client::TlqClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), tlq_client::TlqError> {
+    ///     let client = TlqClient::new("localhost", 1337)?;
+    ///
+    ///     let messages = client.get_messages(3).await?;
+    ///     if !messages.is_empty() {
+    ///         let ids: Vec<_> = messages.iter().map(|m| m.id).collect();
+    ///         let result = client.delete_messages(&ids).await?;
+    ///         println!("Deleted {} messages", result);
+    ///     }
+    ///     
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// * [`TlqError::Validation`] if the `ids` slice is empty
+    /// * [`TlqError::Connection`] for network connectivity issues
+    /// * [`TlqError::Timeout`] if the request times out
+    /// * [`TlqError::Server`] for server-side errors (4xx/5xx HTTP responses)
+    pub async fn delete_messages(&self, ids: &[Uuid]) -> Result<String> {
+        Ok(self.delete_messages_detailed(ids).await?.value)
+    }
+
+    /// Like [`delete_messages`](Self::delete_messages), but also returns the
+    /// response metadata (see [`ResponseMeta`]).
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`delete_messages`](Self::delete_messages).
+    pub async fn delete_messages_detailed(&self, ids: &[Uuid]) -> Result<Response<String>> {
+        if ids.is_empty() {
+            return Err(TlqError::Validation("No message IDs provided".to_string()));
+        }
+
+        let request = DeleteMessagesRequest { ids: ids.to_vec() };
+        self.request_detailed("/delete", &request).await
+    }
+
+    /// Deletes messages in chunks, with control over partial failures.
+    ///
+    /// Like [`delete_messages`](Self::delete_messages), but splits `ids` into
+    /// requests of at most [`chunk_size`](BatchOptions::chunk_size) IDs. With
+    /// [`fail_fast`](BatchOptions::fail_fast), the first failed chunk stops the
+    /// operation and its error is returned; otherwise the remaining chunks are
+    /// still sent and failures are collected in the [`BatchOutcome`]. Either
+    /// way, chunks sent before a failure stay deleted.
+    ///
+    /// # Arguments
+    ///
+    /// * `ids` - The UUIDs of the messages to delete (must not be empty)
+    /// * `options` - Chunking and failure handling
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tlq_client::{BatchOptions, TlqClient};
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), tlq_client::TlqError> {
+    ///     let client = TlqClient::new("localhost", 1337)?;
+    ///     let ids: Vec<_> = client.get_messages(100).await?.iter().map(|m| m.id).collect();
+    ///
+    ///     let options = BatchOptions {
+    ///         chunk_size: Some(25),
+    ///         fail_fast: false,
+    ///     };
+    ///     let outcome = client.delete_messages_with(&ids, &options).await?;
+    ///     for failure in &outcome.failures {
+    ///         eprintln!("{} deletes failed: {}", failure.ids.len(), failure.error);
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// * [`TlqError::Validation`] if `ids` is empty or `chunk_size` is zero
+    /// * The error of the first failed chunk when `fail_fast` is enabled
+    pub async fn delete_messages_with(
+        &self,
+        ids: &[Uuid],
+        options: &BatchOptions,
+    ) -> Result<BatchOutcome> {
+        self.in_chunks(ids, options, |chunk| self.delete_messages(chunk))
+            .await
+    }
+
+    /// Deletes a single message and returns its final state, e.g. for audit logging.
+    ///
+    /// The server deletes the message and returns a snapshot of it in the same
+    /// request, which avoids fetching the message separately before deleting it.
+    /// If the server doesn't support returning deleted messages, this falls back
+    /// to [`delete_message`](Self::delete_message) and returns `None` after the
+    /// delete succeeds. A 404 only triggers the fallback when the route itself
+    /// is missing; the client checks this once and caches the answer, so an
+    /// unknown id on a supporting server returns `None` without a second request.
+    ///
+    /// Note that `None` is also returned when the message was already gone, for
+    /// example because another consumer deleted it or its lock expired and it was
+    /// removed in the meantime. A `None` result therefore doesn't tell you whether
+    /// this call deleted anything.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The UUID of the message to delete
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Some(message))` with the message as it was when deleted
+    /// * `Ok(None)` if the message didn't exist or the server can't return it
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tlq_client::TlqClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), tlq_client::TlqError> {
+    ///     let client = TlqClient::new("localhost", 1337)?;
+    ///
+    ///     if let Some(message) = client.get_message().await? {
+    ///         if let Some(deleted) = client.delete_message_returning(message.id).await? {
+    ///             println!("audit: deleted {} ({})", deleted.id, deleted.body);
+    ///         }
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// * [`TlqError::Connection`] for network connectivity issues
+    /// * [`TlqError::Timeout`] if the request times out
+    /// * [`TlqError::Server`] for server-side errors (4xx/5xx HTTP responses)
+    pub async fn delete_message_returning(&self, id: Uuid) -> Result<Option<Message>> {
+        let request = DeleteReturningRequest { id };
+
+        match self.request("/delete_returning", &request).await {
+            Err(TlqError::Server { status: 404, .. }) => {
+                if !self.has_route("/delete_returning").await? {
+                    self.delete_message(id).await?;
+                }
+                Ok(None)
+            }
+            Ok(message) => {
+                self.route_answered("/delete_returning");
+                Ok(message)
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Deletes a message only if its retry count still matches `expected_retry_count`.
+    ///
+    /// This is an optimistic-concurrency delete for at-least-once consumers. If
+    /// the message was redelivered after you fetched it, its
+    /// [`retry_count`](Message::retry_count) has changed and another consumer may
+    /// be working on it; the server then leaves it in place and this method
+    /// returns `false`. The comparison and deletion happen atomically on the
+    /// server.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The UUID of the message to delete
+    /// * `expected_retry_count` - The retry count observed when the message was fetched
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(true)` if the message was deleted
+    /// * `Ok(false)` if its retry count changed, so it was left in place
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tlq_client::TlqClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), tlq_client::TlqError> {
+    ///     let client = TlqClient::new("localhost", 1337)?;
+    ///
+    ///     if let Some(message) = client.get_message().await? {
+    ///         // Process the message...
+    ///         if !client.delete_if_unchanged(message.id, message.retry_count).await? {
+    ///             println!("Message {} was redelivered meanwhile", message.id);
+    ///         }
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// * [`TlqError::Unsupported`] if the server has no compare-and-delete support
+    /// * [`TlqError::Server`] with status 404 if no message has this id
+    /// * [`TlqError::Connection`] for network connectivity issues
+    /// * [`TlqError::Timeout`] if the request times out
+    /// * [`TlqError::Server`] for other server-side errors
+    pub async fn delete_if_unchanged(&self, id: Uuid, expected_retry_count: u32) -> Result<bool> {
+        let request = DeleteIfUnchangedRequest {
+            id,
+            expected_retry_count,
+        };
+
+        match self.request("/delete_if", &request).await {
+            Err(err @ TlqError::Server { status: 404, .. }) => {
+                if self.has_route("/delete_if").await? {
+                    Err(err)
+                } else {
+                    Err(TlqError::Unsupported(
+                        "Server does not support delete_if_unchanged".to_string(),
+                    ))
+                }
+            }
+            Ok(deleted) => {
+                self.route_answered("/delete_if");
+                Ok(deleted)
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Moves messages to another queue on the same server, keeping their IDs.
+    ///
+    /// Unlike consuming and re-adding, the server relocates the messages in one
+    /// step: they keep their IDs and can't be lost in between. Useful for
+    /// rebalancing and for requeueing messages from a dead letter queue.
+    ///
+    /// # Arguments
+    ///
+    /// * `ids` - The UUIDs of the messages to move (must not be empty)
+    /// * `to_queue` - The name of the destination queue (must not be empty)
+    ///
+    /// # Returns
+    ///
+    /// Returns the number of messages moved. IDs unknown to the server are
+    /// skipped, so this can be lower than `ids.len()`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tlq_client::TlqClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), tlq_client::TlqError> {
+    ///     let client = TlqClient::new("localhost", 1337)?;
+    ///
+    ///     let failed = client.get_failed(100).await?;
+    ///     let ids: Vec<_> = failed.iter().map(|m| m.id).collect();
+    ///     if !ids.is_empty() {
+    ///         let moved = client.move_messages(&ids, "dead-letters").await?;
+    ///         println!("Moved {} messages", moved);
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// * [`TlqError::Validation`] if `ids` or `to_queue` is empty
+    /// * [`TlqError::Unsupported`] if the server doesn't support multiple queues
+    /// * [`TlqError::Connection`] for network connectivity issues
+    /// * [`TlqError::Timeout`] if the request times out
+    /// * [`TlqError::Server`] for other server-side errors
+    pub async fn move_messages(&self, ids: &[Uuid], to_queue: &str) -> Result<u64> {
+        if ids.is_empty() {
+            return Err(TlqError::Validation("No message IDs provided".to_string()));
+        }
+        if to_queue.is_empty() {
+            return Err(TlqError::Validation(
+                "Destination queue name must not be empty".to_string(),
+            ));
+        }
+
+        let request = MoveMessagesRequest {
+            ids: ids.to_vec(),
+            to_queue: to_queue.to_string(),
+        };
+
+        match self.request("/move", &request).await {
+            Err(TlqError::Server { status: 404, .. }) => Err(TlqError::Unsupported(
+                "Server does not support moving messages between queues".to_string(),
+            )),
+            result => result,
+        }
+    }
+
+    /// Lists the queues hosted by the server.
+    ///
+    /// Each [`QueueInfo`] carries the queue name and, if the server reports
+    /// them, its message counts. Useful for admin tooling and for discovering
+    /// queues to consume from with [`with_queue`](Self::with_queue).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tlq_client::TlqClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), tlq_client::TlqError> {
+    ///     let client = TlqClient::new("localhost", 1337)?;
+    ///
+    ///     for queue in client.list_queues().await? {
+    ///         match queue.stats {
+    ///             Some(stats) => println!("{}: {} messages", queue.name, stats.total()),
+    ///             None => println!("{}", queue.name),
+    ///         }
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// * [`TlqError::Unsupported`] if the server doesn't support multiple queues
+    /// * [`TlqError::Connection`] for network connectivity issues
+    /// * [`TlqError::Timeout`] if the request times out
+    /// * [`TlqError::Server`] for other server-side errors
+    pub async fn list_queues(&self) -> Result<Vec<QueueInfo>> {
+        match self.request("/queues", &serde_json::json!({})).await {
+            Err(TlqError::Server { status: 404, .. }) => Err(TlqError::Unsupported(
+                "Server does not support listing queues".to_string(),
+            )),
+            result => result,
+        }
+    }
+
+    /// Returns the number of messages in the queue by state, without
+    /// retrieving any of them.
+    ///
+    /// Useful for autoscaling on queue depth and for alerting on a growing
+    /// number of failed messages. The counts are a snapshot and may be out of
+    /// date as soon as they are returned. Not to be confused with
+    /// [`stats`](Self::stats), which counts this client's own requests.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tlq_client::TlqClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), tlq_client::TlqError> {
+    ///     let client = TlqClient::new("localhost", 1337)?;
+    ///
+    ///     let stats = client.queue_stats().await?;
+    ///     println!("{} ready, {} failed", stats.ready, stats.failed);
+    ///     if stats.failed > 100 {
+    ///         eprintln!("Failed messages are piling up");
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// * [`TlqError::Unsupported`] if the server doesn't report queue statistics
+    /// * [`TlqError::Connection`] for network connectivity issues
+    /// * [`TlqError::Timeout`] if the request times out
+    /// * [`TlqError::Server`] for other server-side errors
+    pub async fn queue_stats(&self) -> Result<QueueStats> {
+        match self.request("/stats", &serde_json::json!({})).await {
+            Err(TlqError::Server { status: 404, .. }) => Err(TlqError::Unsupported(
+                "Server does not report queue statistics".to_string(),
+            )),
+            result => result,
+        }
+    }
+
+    /// Creates a named queue on the server.
+    ///
+    /// Creating a queue that already exists is not an error: the existing
+    /// queue is left unchanged (including its options) and `false` is returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The queue name (must not be empty)
+    /// * `options` - Settings for the new queue; see [`QueueOptions`]
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(true)` if the queue was created
+    /// * `Ok(false)` if a queue with this name already existed
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tlq_client::{QueueOptions, TlqClient};
+    /// use std::time::Duration;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), tlq_client::TlqError> {
+    ///     let client = TlqClient::new("localhost", 1337)?;
+    ///
+    ///     let options = QueueOptions {
+    ///         lock_duration: Some(Duration::from_secs(30)),
+    ///         ..QueueOptions::default()
+    ///     };
+    ///     client.create_queue("orders", options).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// * [`TlqError::Validation`] if `name` is empty
+    /// * [`TlqError::Unsupported`] if the server doesn't support queue management
+    /// * [`TlqError::Connection`] for network connectivity issues
+    /// * [`TlqError::Timeout`] if the request times out
+    /// * [`TlqError::Server`] for other server-side errors
+    pub async fn create_queue(&self, name: &str, options: QueueOptions) -> Result<bool> {
+        if name.is_empty() {
+            return Err(TlqError::Validation(
+                "Queue name must not be empty".to_string(),
+            ));
+        }
+
+        let request = CreateQueueRequest {
+            name: name.to_string(),
+            max_size: options.max_size,
+            lock_duration_ms: options.lock_duration.map(|d| d.as_millis() as u64),
+            ttl_ms: options.ttl.map(|d| d.as_millis() as u64),
+        };
+
+        match self.request("/queues/create", &request).await {
+            Err(TlqError::Server { status: 409, .. }) => Ok(false),
+            Err(TlqError::Server { status: 404, .. }) => Err(TlqError::Unsupported(
+                "Server does not support queue management".to_string(),
+            )),
+            result => result,
+        }
+    }
+
+    /// Deletes a named queue from the server.
+    ///
+    /// A queue that still holds messages is only deleted when `force` is
+    /// `true`, in which case its messages are discarded.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The queue name (must not be empty)
+    /// * `force` - Delete the queue even if it still holds messages
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(true)` if the queue was deleted
+    /// * `Ok(false)` if no queue with this name existed
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tlq_client::TlqClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), tlq_client::TlqError> {
+    ///     let client = TlqClient::new("localhost", 1337)?;
+    ///
+    ///     client.delete_queue("orders", false).await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// * [`TlqError::Validation`] if `name` is empty, or the queue is not empty
+    ///   and `force` is `false`
+    /// * [`TlqError::Unsupported`] if the server doesn't support queue management
+    /// * [`TlqError::Connection`] for network connectivity issues
+    /// * [`TlqError::Timeout`] if the request times out
+    /// * [`TlqError::Server`] for other server-side errors
+    pub async fn delete_queue(&self, name: &str, force: bool) -> Result<bool> {
+        if name.is_empty() {
+            return Err(TlqError::Validation(
+                "Queue name must not be empty".to_string(),
+            ));
+        }
+
+        let request = DeleteQueueRequest {
+            name: name.to_string(),
+            force,
+        };
+
+        match self.request("/queues/delete", &request).await {
+            Err(TlqError::Server { status: 409, .. }) => Err(TlqError::Validation(format!(
+                "Queue '{}' is not empty; use force to delete it",
+                name
+            ))),
+            Err(TlqError::Server { status: 404, .. }) => Err(TlqError::Unsupported(
+                "Server does not support queue management".to_string(),
+            )),
+            result => result,
+        }
+    }
+
+    /// Retries a single failed message on the TLQ server.
+    ///
+    /// This is a convenience method that calls [`retry_messages`](Self::retry_messages)
+    /// with a single message ID. The message state will be changed from
+    /// [`MessageState::Failed`] back to [`MessageState::Ready`].
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The UUID of the message to retry
+    ///
+    /// # Returns
+    ///
+    /// Returns a string indicating the result of the operation (typically "Success" or a count).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tlq_client::{TlqClient, MessageState};
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), tlq_client::TlqError> {
+    ///     let client = TlqClient::new("localhost", 1337)?;
+    ///
+    ///     // Find failed messages and retry them
+    ///     let messages = client.get_messages(10).await?;
+    ///     for message in messages {
+    ///         if message.state == MessageState::Failed {
+    ///             let result = client.retry_message(message.id).await?;
+    ///             println!("Retry result: {}", result);
+    ///         }
+    ///     }
+    ///     
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// * [`TlqError::Connection`] for network connectivity issues
+    /// * [`TlqError::Timeout`] if the request times out
+    /// * [`TlqError::Server`] for server-side errors (4xx/5xx HTTP responses)
+    pub async fn retry_message(&self, id: Uuid) -> Result<String> {
+        self.retry_messages(&[id]).await
+    }
+
+    /// Retries a message whose processing failed, or deletes it once it has
+    /// used up `max_attempts`.
+    ///
+    /// This is client-side poison-message handling for custom consumer loops:
+    /// a message that keeps failing is dropped instead of being redelivered
+    /// forever. The message's [`retry_count`](Message::retry_count) counts the
+    /// earlier attempts, so the attempt that just failed is number
+    /// `retry_count + 1`. If that reaches `max_attempts`, the message is
+    /// deleted with [`delete_message`](Self::delete_message); otherwise it is
+    /// requeued with [`retry_message`](Self::retry_message).
+    ///
+    /// The decision uses the `retry_count` of the snapshot you pass in, so
+    /// pass the message as it was fetched.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - The message whose processing failed
+    /// * `max_attempts` - Total processing attempts allowed, including the first
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tlq_client::{FailureDisposition, TlqClient};
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), tlq_client::TlqError> {
+    ///     let client = TlqClient::new("localhost", 1337)?;
+    ///
+    ///     if let Some(message) = client.get_message().await? {
+    ///         if message.body.is_empty() {
+    ///             let disposition = client.handle_failure(&message, 5).await?;
+    ///             if disposition == FailureDisposition::Deleted {
+    ///                 eprintln!("Dropped poison message {}", message.id);
+    ///             }
+    ///         }
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// * [`TlqError::Connection`] for network connectivity issues
+    /// * [`TlqError::Timeout`] if the request times out
+    /// * [`TlqError::Server`] for server-side errors (4xx/5xx HTTP responses)
+    pub async fn handle_failure(
+        &self,
+        message: &Message,
+        max_attempts: u32,
+    ) -> Result<FailureDisposition> {
+        if message.retry_count.saturating_add(1) >= max_attempts {
+            self.delete_message(message.id).await?;
+            Ok(FailureDisposition::Deleted)
+        } else {
+            self.retry_message(message.id).await?;
+            Ok(FailureDisposition::Retried)
+        }
+    }
+
+    /// Marks a message as failed and records why.
+    ///
+    /// Moves the message from [`MessageState::Processing`] to
+    /// [`MessageState::Failed`] and stores `reason` as its
+    /// [`failure_reason`](Message::failure_reason), so whoever inspects failed
+    /// messages later can see what went wrong. The retry count is left alone;
+    /// it is incremented when the message is requeued with
+    /// [`retry_message`](Self::retry_message).
+    ///
+    /// If the server doesn't support recording failures, this falls back to
+    /// [`retry_message`](Self::retry_message), which requeues the message
+    /// right away, and returns `None`. The reason isn't stored anywhere in that
+    /// case, so log it yourself if you need it.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The UUID of the message that failed
+    /// * `reason` - Why processing failed
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Some(message))` with the message in its `Failed` state
+    /// * `Ok(None)` if the server can't record failures and the message was retried
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tlq_client::TlqClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), tlq_client::TlqError> {
+    ///     let client = TlqClient::new("localhost", 1337)?;
+    ///
+    ///     if let Some(message) = client.get_message().await? {
+    ///         if message.body.is_empty() {
+    ///             client.fail_message(message.id, "empty body").await?;
+    ///         }
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// * [`TlqError::Connection`] for network connectivity issues
+    /// * [`TlqError::Timeout`] if the request times out
+    /// * [`TlqError::Server`] for server-side errors (4xx/5xx HTTP responses)
+    pub async fn fail_message(
+        &self,
+        id: Uuid,
+        reason: impl Into<String>,
+    ) -> Result<Option<Message>> {
+        let request = FailRequest {
+            id,
+            reason: reason.into(),
+        };
+
+        match self.request("/fail", &request).await {
+            Ok(message) => Ok(Some(message)),
+            Err(TlqError::Server { status: 404, .. }) => {
+                self.retry_message(id).await?;
+                Ok(None)
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Retries multiple failed messages on the TLQ server.
+    ///
+    /// This method changes the state of the specified messages from [`MessageState::Failed`]
+    /// back to [`MessageState::Ready`], making them available for processing again.
+    /// The retry count for each message will be incremented.
+    ///
+    /// # Arguments
+    ///
+    /// * `ids` - A slice of message UUIDs to retry (must not be empty)
+    ///
+    /// # Returns
+    ///
+    /// Returns a string indicating the number of messages retried or "Success".
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tlq_client::{TlqClient, MessageState};
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), tlq_client::TlqError> {
+    ///     let client = TlqClient::new("localhost", 1337)?;
+    ///
+    ///     // Get all messages and retry the failed ones
+    ///     let messages = client.get_messages(100).await?;
+    ///     let failed_ids: Vec<_> = messages
+    ///         .iter()
+    ///         .filter(|m| m.state == MessageState::Failed)
+    ///         .map(|m| m.id)
+    ///         .collect();
+    ///
+    ///     if !failed_ids.is_empty() {
+    ///         let result = client.retry_messages(&failed_ids).await?;
+    ///         println!("Retried {} failed messages", result);
+    ///     }
+    ///     
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// * [`TlqError::Validation`] if the `ids` slice is empty
+    /// * [`TlqError::Connection`] for network connectivity issues
+    /// * [`TlqError::Timeout`] if the request times out
+    /// * [`TlqError::Server`] for server-side errors (4xx/5xx HTTP responses)
+    pub async fn retry_messages(&self, ids: &[Uuid]) -> Result<String> {
+        Ok(self.retry_messages_detailed(ids).await?.value)
+    }
+
+    /// Like [`retry_messages`](Self::retry_messages), but also returns the
+    /// response metadata (see [`ResponseMeta`]).
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`retry_messages`](Self::retry_messages).
+    pub async fn retry_messages_detailed(&self, ids: &[Uuid]) -> Result<Response<String>> {
+        if ids.is_empty() {
+            return Err(TlqError::Validation("No message IDs provided".to_string()));
+        }
+
+        let request = RetryMessagesRequest { ids: ids.to_vec() };
+        self.request_detailed("/retry", &request).await
+    }
+
+    /// Retries messages in chunks, with control over partial failures.
+    ///
+    /// Like [`retry_messages`](Self::retry_messages), but split into chunks as
+    /// described for [`delete_messages_with`](Self::delete_messages_with).
+    ///
+    /// # Arguments
+    ///
+    /// * `ids` - The UUIDs of the messages to retry (must not be empty)
+    /// * `options` - Chunking and failure handling
+    ///
+    /// # Errors
+    ///
+    /// * [`TlqError::Validation`] if `ids` is empty or `chunk_size` is zero
+    /// * The error of the first failed chunk when `fail_fast` is enabled
+    pub async fn retry_messages_with(
+        &self,
+        ids: &[Uuid],
+        options: &BatchOptions,
+    ) -> Result<BatchOutcome> {
+        self.in_chunks(ids, options, |chunk| self.retry_messages(chunk))
+            .await
+    }
+
+    /// Retries the failed messages that match a predicate.
+    ///
+    /// The first `max` messages in the queue are inspected with
+    /// [`get_failed`](Self::get_failed), which peeks without claiming anything,
+    /// so `Ready` messages are never touched. `predicate` is applied to each
+    /// failed message, and all matches are retried with one
+    /// [`retry_messages`](Self::retry_messages) call.
+    ///
+    /// The predicate runs client-side on snapshots. A message can change
+    /// between the peek and the retry, for example when another operator
+    /// retries or deletes it first, so the server acts on its state at retry
+    /// time rather than the state the predicate saw.
+    ///
+    /// # Arguments
+    ///
+    /// * `max` - Number of messages to inspect (must be greater than 0)
+    /// * `predicate` - Selects which failed messages to retry
+    ///
+    /// # Returns
+    ///
+    /// Returns the number of messages that matched and were sent for retry.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tlq_client::TlqClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), tlq_client::TlqError> {
+    ///     let client = TlqClient::new("localhost", 1337)?;
+    ///
+    ///     let retried = client.retry_where(1000, |m| m.retry_count < 5).await?;
+    ///     println!("Retried {} messages", retried);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`peek_messages`](Self::peek_messages) and
+    /// [`retry_messages`](Self::retry_messages).
+    pub async fn retry_where<P>(&self, max: u32, predicate: P) -> Result<u64>
+    where
+        P: Fn(&Message) -> bool,
+    {
+        let ids: Vec<_> = self
+            .get_failed(max)
+            .await?
+            .iter()
+            .filter(|message| predicate(message))
+            .map(|message| message.id)
+            .collect();
+
+        if ids.is_empty() {
+            return Ok(0);
+        }
+
+        self.retry_messages(&ids).await?;
+        Ok(ids.len() as u64)
+    }
+
+    /// Removes all messages from the TLQ server queue.
+    ///
+    /// This method permanently deletes all messages in the queue regardless of their state.
+    /// Use with caution as this operation cannot be undone. When
+    /// [`require_purge_confirmation`](ConfigBuilder::require_purge_confirmation)
+    /// is enabled, use [`purge_queue_confirmed`](Self::purge_queue_confirmed)
+    /// instead.
+    ///
+    /// # Returns
+    ///
+    /// Returns a string indicating the result of the operation (typically "Success").
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tlq_client::TlqClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), tlq_client::TlqError> {
+    ///     let client = TlqClient::new("localhost", 1337)?;
+    ///
+    ///     // Clear all messages from the queue
+    ///     let result = client.purge_queue().await?;
+    ///     println!("Purge result: {}", result);
+    ///     
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// * [`TlqError::Validation`] if purges require confirmation
+    /// * [`TlqError::Connection`] for network connectivity issues
+    /// * [`TlqError::Timeout`] if the request times out
+    /// * [`TlqError::Server`] for server-side errors (4xx/5xx HTTP responses)
+    pub async fn purge_queue(&self) -> Result<String> {
+        Ok(self.purge_queue_detailed().await?.value)
+    }
+
+    /// Like [`purge_queue`](Self::purge_queue), but also returns the response
+    /// metadata (see [`ResponseMeta`]).
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`purge_queue`](Self::purge_queue).
+    pub async fn purge_queue_detailed(&self) -> Result<Response<String>> {
+        if self.config.require_purge_confirmation {
+            return Err(TlqError::Validation(
+                "purge requires confirmation".to_string(),
+            ));
+        }
+        self.request_detailed("/purge", &serde_json::json!({}))
+            .await
+    }
+
+    /// Removes all messages from the queue after checking that `token` names it.
+    ///
+    /// The token must be the configured host, followed by `/` and the queue
+    /// name when a named queue is targeted (see [`ConfigBuilder::queue`]), for
+    /// example `"queue.prod.example.com"` or `"queue.prod.example.com/orders"`.
+    /// Making callers spell out what they purge catches code pointed at the
+    /// wrong environment. This works whether or not
+    /// [`require_purge_confirmation`](ConfigBuilder::require_purge_confirmation)
+    /// is enabled.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The host (and queue) being purged
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tlq_client::{ConfigBuilder, TlqClient};
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), tlq_client::TlqError> {
+    ///     let client = TlqClient::with_config(
+    ///         ConfigBuilder::new()
+    ///             .host("queue.staging.example.com")
+    ///             .require_purge_confirmation(true)
+    ///             .build(),
+    ///     );
+    ///
+    ///     client.purge_queue_confirmed("queue.staging.example.com").await?;
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// * [`TlqError::Validation`] if `token` doesn't match the purge target
+    /// * Otherwise the same errors as [`purge_queue`](Self::purge_queue)
+    pub async fn purge_queue_confirmed(&self, token: &str) -> Result<String> {
+        let target = if self.config.queue.is_empty() {
+            self.config.host.clone()
+        } else {
+            format!("{}/{}", self.config.host, self.config.queue)
+        };
+        if token != target {
+            return Err(TlqError::Validation(format!(
+                "purge confirmation '{}' does not match '{}'",
+                token, target
+            )));
+        }
+
+        self.request("/purge", &serde_json::json!({})).await
+    }
+
+    /// Adds a request message and waits for a correlated reply message.
+    ///
+    /// This implements the RPC-over-queue pattern: the request is enqueued with
+    /// [`add_message`](Self::add_message), then the queue is polled until a message
+    /// accepted by `reply_matcher` arrives. The matching reply is deleted from the
+    /// queue and returned.
+    ///
+    /// # Matching Contract
+    ///
+    /// TLQ messages carry no attributes, so correlation is left to the body.
+    /// `reply_matcher` is called as `reply_matcher(&request, &candidate)` for every
+    /// message fetched while waiting; the usual convention is for the responder to
+    /// embed `request.id` in the reply body. The first candidate for which the
+    /// matcher returns `true` is the reply. Every other fetched message (including
+    /// the request itself, if it is fetched back) is handed back to the queue with
+    /// [`retry_messages`](Self::retry_messages), which increments its retry count.
+    /// The reply is deleted before the others are handed back, and handing them
+    /// back is best effort: if it fails, they return to the queue once their lock
+    /// expires.
+    ///
+    /// `wait` bounds the whole call, including each poll of a slow server.
+    ///
+    /// # Arguments
+    ///
+    /// * `body` - The request message content
+    /// * `reply_matcher` - Predicate recognising the reply for the request
+    /// * `wait` - Maximum time to wait for the reply
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tlq_client::TlqClient;
+    /// use std::time::Duration;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), tlq_client::TlqError> {
+    ///     let client = TlqClient::new("localhost", 1337)?;
+    ///
+    ///     let reply = client
+    ///         .request_reply(
+    ///             "resize image 42",
+    ///             |request, candidate| candidate.body == format!("reply:{}", request.id),
+    ///             Duration::from_secs(5),
+    ///         )
+    ///         .await?;
+    ///     println!("Got reply: {}", reply.body);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// * [`TlqError::Timeout`] if no matching reply arrives within `wait`
+    /// * [`TlqError::MessageTooLarge`] if the request exceeds the configured maximum size
+    /// * [`TlqError::Connection`] for network connectivity issues
+    /// * [`TlqError::Server`] for server-side errors (4xx/5xx HTTP responses)
+    pub async fn request_reply<F>(
+        &self,
+        body: impl Into<String>,
+        reply_matcher: F,
+        wait: Duration,
+    ) -> Result<Message>
+    where
+        F: Fn(&Message, &Message) -> bool,
+    {
+        let deadline = tokio::time::Instant::now() + wait;
+        let request = self.add_message(body).await?;
+
+        let timed_out = || TlqError::Timeout(wait.as_millis() as u64);
+
+        loop {
+            // Each poll is bounded by the deadline, so a slow server can't
+            // stretch the call past `wait`
+            let remaining = deadline.saturating_duration_since(tokio::time::Instant::now());
+            let messages = timeout(remaining, self.get_messages(REPLY_POLL_BATCH))
+                .await
+                .map_err(|_| timed_out())??;
+            let (replies, others): (Vec<_>, Vec<_>) = messages
+                .into_iter()
+                .partition(|candidate| reply_matcher(&request, candidate));
+
+            let mut replies = replies.into_iter();
+            let reply = replies.next();
+            let release: Vec<Uuid> = others
+                .iter()
+                .chain(replies.as_slice())
+                .map(|m| m.id)
+                .collect();
+
+            // The reply is deleted first, so it can't be left locked if
+            // releasing the others fails
+            let deleted = match &reply {
+                Some(reply) => Some(self.delete_message(reply.id).await),
+                None => None,
+            };
+            // Best effort: unreleased messages return to the queue once their
+            // lock expires
+            if !release.is_empty() {
+                let _ = self.retry_messages(&release).await;
+            }
+
+            if let (Some(reply), Some(deleted)) = (reply, deleted) {
+                deleted?;
+                return Ok(reply);
+            }
+
+            let now = tokio::time::Instant::now();
+            if now >= deadline {
+                return Err(timed_out());
+            }
+            tokio::time::sleep(REPLY_POLL_INTERVAL.min(deadline - now)).await;
+        }
+    }
+
+    /// Spawns a background task that forwards queue messages into a channel.
+    ///
+    /// The task repeatedly fetches up to `batch` messages with
+    /// [`get_messages`](Self::get_messages) and sends them to `tx`, bridging TLQ
+    /// into channel-based pipelines. Before each fetch it reserves channel
+    /// capacity for the whole batch, so fetching pauses while the channel is
+    /// full (back-pressure) and no message is claimed that can't be delivered.
+    /// `batch` is clamped to between 1 and the channel's capacity. When the queue
+    /// is empty or a request fails, the task waits briefly before polling again.
+    ///
+    /// The task stops once the receiver is dropped. Messages fetched but not yet
+    /// sent at that point are handed back to the queue with
+    /// [`retry_messages`](Self::retry_messages); messages already sitting in the
+    /// channel buffer are dropped with it and stay in the `Processing` state
+    /// until their lock expires. Abort the returned handle (or a handle obtained
+    /// from [`JoinHandle::abort_handle`]) to cancel the task early.
+    ///
+    /// Must be called from within a Tokio runtime.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tlq_client::TlqClient;
+    /// use tokio::sync::mpsc;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), tlq_client::TlqError> {
+    ///     let client = TlqClient::new("localhost", 1337)?;
+    ///     let (tx, mut rx) = mpsc::channel(100);
+    ///
+    ///     let consumer = client.spawn_consumer(10, tx);
+    ///
+    ///     while let Some(message) = rx.recv().await {
+    ///         println!("Processing: {}", message.body);
+    ///         client.delete_message(message.id).await?;
+    ///     }
+    ///
+    ///     consumer.abort();
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn spawn_consumer(&self, batch: u32, tx: mpsc::Sender<Message>) -> JoinHandle<()> {
+        let client = self.clone();
+        let batch = (batch as usize).clamp(1, tx.max_capacity());
+
+        tokio::spawn(async move {
+            loop {
+                let Ok(mut permits) = tx.reserve_many(batch).await else {
+                    return;
+                };
+
+                let messages = match client.get_messages(batch as u32).await {
+                    Ok(messages) if !messages.is_empty() => messages,
+                    _ => {
+                        tokio::time::sleep(CONSUMER_POLL_INTERVAL).await;
+                        continue;
+                    }
+                };
+
+                if tx.is_closed() {
+                    let ids: Vec<Uuid> = messages.iter().map(|m| m.id).collect();
+                    let _ = client.retry_messages(&ids).await;
+                    return;
+                }
+
+                for message in messages {
+                    if let Some(permit) = permits.next() {
+                        permit.send(message);
+                    }
+                }
+            }
+        })
+    }
+
+    /// Runs a processing loop that fetches messages and acknowledges them
+    /// based on the handler's result.
+    ///
+    /// Encodes the recommended processing pattern: messages are fetched up to
+    /// [`batch_size`](ConsumeOptions::batch_size) at a time and passed to
+    /// `handler` one by one. When the handler returns `Ok(())` the message is
+    /// deleted. When it returns `Err(_)`, the message is retried while its
+    /// `retry_count` is below
+    /// [`max_message_retries`](ConsumeOptions::max_message_retries); after that
+    /// it is moved to the [`dead_letter_queue`](ConsumeOptions::dead_letter_queue)
+    /// with [`move_messages`](Self::move_messages), or deleted if none is set.
+    /// While the queue is empty the loop waits
+    /// [`poll_interval`](ConsumeOptions::poll_interval) between polls. With an
+    /// [`ack_batch_size`](ConsumeOptions::ack_batch_size) above 1, deletes of
+    /// handled messages are buffered and sent in batches.
+    /// [`max_in_flight`](ConsumeOptions::max_in_flight) bounds how many fetched
+    /// messages may be awaiting acknowledgement at once.
+    ///
+    /// With [`strict_ordering`](ConsumeOptions::strict_ordering), a failing
+    /// message blocks the consumer instead: no new messages are fetched and the
+    /// rest of the batch waits while the handler is called again with the same
+    /// message, after the client's retry backoff, until it succeeds or has been
+    /// retried `max_message_retries` times in total. Only then is it given up and
+    /// the next message handled. This preserves queue order for consumers such
+    /// as event sourcing or change data capture, but one failing message stalls
+    /// all processing for its retries and their delays, so throughput drops to
+    /// that of a single in-order worker. The message stays claimed meanwhile, so
+    /// keep the retry delays well within the server's lock duration.
+    ///
+    /// Cancelling [`shutdown`](ConsumeOptions::shutdown) stops the loop
+    /// gracefully: messages already fetched are still handled and acknowledged,
+    /// buffered acknowledgements are flushed, then `consume` returns `Ok(())`.
+    /// A wait on an empty queue ends right away.
+    /// In strict ordering mode, a message waiting to be retried is released
+    /// together with the rest of its batch instead.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tlq_client::{CancellationToken, ConsumeOptions, TlqClient};
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), tlq_client::TlqError> {
+    ///     let client = TlqClient::new("localhost", 1337)?;
+    ///     let shutdown = CancellationToken::new();
+    ///
+    ///     let options = ConsumeOptions {
+    ///         batch_size: 10,
+    ///         shutdown: shutdown.clone(),
+    ///         ..ConsumeOptions::default()
+    ///     };
+    ///     tokio::spawn(async move {
+    ///         let _ = tokio::signal::ctrl_c().await;
+    ///         shutdown.cancel();
+    ///     });
+    ///
+    ///     client
+    ///         .consume(options, |message| async move {
+    ///             if message.body.is_empty() {
+    ///                 return Err("empty body");
+    ///             }
+    ///             println!("Processing: {}", message.body);
+    ///             Ok(())
+    ///         })
+    ///         .await
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Stops at the first failed request, after the client's own retries:
+    ///
+    /// * [`TlqError::Connection`] for network connectivity issues
+    /// * [`TlqError::Timeout`] if a request times out
+    /// * [`TlqError::Server`] for server-side errors (4xx/5xx HTTP responses)
+    /// * [`TlqError::Unsupported`] if a dead letter queue is set and the server
+    ///   doesn't support multiple queues
+    pub async fn consume<F, Fut, E>(&self, options: ConsumeOptions, mut handler: F) -> Result<()>
+    where
+        F: FnMut(Message) -> Fut,
+        Fut: std::future::Future<Output = std::result::Result<(), E>>,
+    {
+        let ConsumeOptions {
+            batch_size,
+            max_message_retries,
+            poll_interval,
+            dead_letter_queue,
+            shutdown,
+            ack_batch_size,
+            ack_linger,
+            max_in_flight,
+            strict_ordering,
+        } = options;
+
+        let in_flight = Arc::new(Semaphore::new(
+            max_in_flight
+                .unwrap_or(Semaphore::MAX_PERMITS)
+                .clamp(1, Semaphore::MAX_PERMITS),
+        ));
+        let mut acks = PendingAcks::new(ack_batch_size, ack_linger);
+        while !shutdown.is_cancelled() {
+            // Buffered acknowledgements holding every permit would stall the loop
+            if in_flight.available_permits() == 0 {
+                self.flush_acks(&mut acks).await?;
+            }
+            let count = batch_size
+                .max(1)
+                .min(u32::try_from(in_flight.available_permits()).unwrap_or(u32::MAX));
+            let mut permits: Vec<_> = (0..count)
+                .map_while(|_| in_flight.clone().try_acquire_owned().ok())
+                .collect();
+            let messages = self.get_messages(count).await?;
+            // Permits for messages the server didn't return are given back
+            permits.truncate(messages.len());
+            if messages.is_empty() {
+                // No more acknowledgements to batch with while the queue is empty
+                self.flush_acks(&mut acks).await?;
+                tokio::select! {
+                    _ = shutdown.cancelled() => break,
+                    _ = tokio::time::sleep(poll_interval) => continue,
+                }
+            }
+
+            let mut pending = messages.into_iter().zip(permits);
+            while let Some((message, permit)) = pending.next() {
+                let (id, retry_count) = (message.id, message.retry_count);
+                let handled = if strict_ordering {
+                    self.handle_in_order(message, &mut handler, max_message_retries, &shutdown)
+                        .await
+                } else {
+                    Some(handler(message).await.is_ok())
+                };
+                match handled {
+                    // Shut down while blocked: hand the batch back in order
+                    None => {
+                        let ids: Vec<Uuid> = std::iter::once(id)
+                            .chain(pending.map(|(m, _)| m.id))
+                            .collect();
+                        self.retry_messages(&ids).await?;
+                        return self.flush_acks(&mut acks).await;
+                    }
+                    Some(true) => acks.push(id, permit),
+                    Some(false) if !strict_ordering && retry_count < max_message_retries => {
+                        self.retry_message(id).await?;
+                    }
+                    Some(false) => {
+                        if let Some(queue) = &dead_letter_queue {
+                            self.move_messages(&[id], queue).await?;
+                        } else {
+                            self.delete_message(id).await?;
+                        }
+                    }
+                }
+                if acks.is_due() {
+                    self.flush_acks(&mut acks).await?;
+                }
+            }
+        }
+        self.flush_acks(&mut acks).await
+    }
+
+    // Deletes the acknowledgements buffered by `consume`, if there are any.
+    async fn flush_acks(&self, acks: &mut PendingAcks) -> Result<()> {
+        // The in-flight permits are released once the delete went through
+        let (ids, _permits) = acks.take();
+        if !ids.is_empty() {
+            self.delete_messages(&ids).await?;
+        }
+        Ok(())
+    }
+
+    // Calls `handler` with `message` until it succeeds, retrying in place after
+    // the retry backoff while the message's retries stay within
+    // `max_message_retries`. Returns whether the handler succeeded, or `None`
+    // if `shutdown` was cancelled during a backoff.
+    async fn handle_in_order<F, Fut, E>(
+        &self,
+        message: Message,
+        handler: &mut F,
+        max_message_retries: u32,
+        shutdown: &CancellationToken,
+    ) -> Option<bool>
+    where
+        F: FnMut(Message) -> Fut,
+        Fut: std::future::Future<Output = std::result::Result<(), E>>,
+    {
+        let mut retries = message.retry_count;
+        let mut attempt = 0;
+        loop {
+            if handler(message.clone()).await.is_ok() {
+                return Some(true);
+            }
+            if retries >= max_message_retries {
+                return Some(false);
+            }
+            tokio::select! {
+                _ = shutdown.cancelled() => return None,
+                _ = tokio::time::sleep(self.retry_strategy.jittered_delay(attempt)) => {}
+            }
+            retries += 1;
+            attempt += 1;
+        }
+    }
+
+    /// Returns a [`Stream`](futures_core::Stream) of queue messages, fetched
+    /// `batch_size` at a time.
+    ///
+    /// The next batch is fetched only when the previous one has been consumed,
+    /// so back-pressure from the consumer limits how many messages are claimed.
+    /// `batch_size` is raised to at least 1. See [`MessageStream`] for how
+    /// empty queues and errors are handled.
+    ///
+    /// Requires the `stream` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use futures_util::StreamExt;
+    /// use tlq_client::TlqClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), tlq_client::TlqError> {
+    ///     let client = TlqClient::new("localhost", 1337)?;
+    ///
+    ///     client
+    ///         .messages(10)
+    ///         .take(100)
+    ///         .for_each_concurrent(4, |message| async {
+    ///             if let Ok(message) = message {
+    ///                 let _ = client.delete_message(message.id).await;
+    ///             }
+    ///         })
+    ///         .await;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(feature = "stream")]
+    pub fn messages(&self, batch_size: u32) -> MessageStream {
+        MessageStream::new(self.clone(), batch_size)
+    }
+
+    // Fetches up to `count` messages for `MessageStream`. With
+    // `incremental_parse`, a JSON array response is returned unparsed, right
+    // after its opening `[`, for the messages to be parsed as they arrive;
+    // other responses are read and parsed whole. The incremental request isn't
+    // retried.
+    #[cfg(feature = "stream")]
+    pub(crate) async fn get_message_body(&self, count: u32) -> Result<MessageBody> {
+        if !self.config.incremental_parse {
+            return Ok(MessageBody::Parsed(self.get_messages(count).await?));
+        }
+
+        let request = serde_json::to_vec(&GetMessagesRequest {
+            count,
+            wait_ms: None,
+        })?;
+        self.check_request_size(&[&request])?;
+        self.wait_startup_jitter().await;
+
+        let request_timeout = self.config.timeout_for("/get");
+        let started = Instant::now();
+        let result = traced("/get", async {
+            timeout(request_timeout, self.open_message_body(&request))
+                .await
+                .map_err(|_| TlqError::Timeout(request_timeout.as_millis() as u64))
+                .and_then(|result| result)
+        })
+        .await;
+
+        self.stats.record_request(
+            started.elapsed(),
+            result.is_ok(),
+            matches!(result, Err(TlqError::Timeout(_))),
+        );
+        result
+    }
+
+    // Sends a `/get` and reads its response up to the first byte of the body.
+    #[cfg(feature = "stream")]
+    async fn open_message_body(&self, request: &[u8]) -> Result<MessageBody> {
+        let _in_flight = InFlightGuard::new(&self.in_flight);
+        let head = self.post_head("/get", JSON_CONTENT_TYPE, request.len(), true)?;
+
+        let mut stream = self.open().await?;
+        let mut parts = [IoSlice::new(head.as_bytes()), IoSlice::new(request)];
+        write_all_vectored(&mut stream, &mut parts).await?;
+        stream.flush().await?;
+        self.stats.record_sent(head.len() + request.len());
+
+        let mut response: Vec<u8> = Vec::new();
+        let mut chunk = [0u8; 8192];
+        let body_start = loop {
+            if let Some(header_end) = response.windows(4).position(|w| w == b"\r\n\r\n") {
+                let body_start = header_end + 4;
+                let first = response[body_start..]
+                    .iter()
+                    .position(|b| !b.is_ascii_whitespace());
+                if let Some(first) = first {
+                    break Some((body_start, body_start + first));
+                }
+            }
+            let n = stream.read(&mut chunk).await?;
+            if n == 0 {
+                break None;
+            }
+            self.stats.record_received(n);
+            response.extend_from_slice(&chunk[..n]);
+        };
+
+        // Only successful JSON arrays are parsed as they arrive
+        let headers = body_start.and_then(|(body_start, first)| {
+            let headers = std::str::from_utf8(&response[..body_start]).ok()?;
+            let incremental = response_status(headers).is_some_and(|s| (200..300).contains(&s))
+                && !is_ndjson(headers)
+                && response[first] == b'['
+                && !self.config.lossy_response_decode;
+            incremental.then_some((headers, body_start, first))
+        });
+        let Some((headers, body_start, first)) = headers else {
+            let read = response.len();
+            let response = read_response_buffered(&mut stream, &mut response).await?;
+            self.stats
+                .record_received(response.len().saturating_sub(read));
+            let MessagesResponse(messages) = self.parse_response("/get", &response)?.value;
+            return Ok(MessageBody::Parsed(messages));
+        };
+
+        self.record_server_date(headers);
+        #[cfg(any(feature = "tracing", feature = "otel"))]
+        if let Some(status) = response_status(headers) {
+            #[cfg(feature = "tracing")]
+            tracing::Span::current().record("status", status);
+            #[cfg(feature = "otel")]
+            crate::otel::record_status(status);
+        }
+        let remaining = response_header(headers, "content-length")
+            .map(|value| {
+                value
+                    .parse::<usize>()
+                    .map_err(|_| TlqError::Connection(format!("Invalid Content-Length: {}", value)))
+            })
+            .transpose()?
+            .map(|length| length.saturating_sub(response.len() - body_start));
+        response.drain(..=first);
+
+        Ok(MessageBody::Reading(BodyReader::new(
+            stream,
+            response,
+            remaining,
+            self.config.timeout_for("/get"),
+            self.stats.clone(),
+        )))
+    }
+
+    /// Runs an async operation with the client's configured retry policy.
+    ///
+    /// The operation is retried with the same `max_retries`, `retry_delay` and
+    /// [`backoff`](ConfigBuilder::backoff) settings used for the client's own
+    /// requests, which makes it a building block for TLQ-adjacent operations
+    /// that should back off the same way.
+    ///
+    /// Only errors for which [`TlqError::is_retryable`] returns `true`
+    /// (connection, timeout and I/O errors) are retried; any other error is
+    /// returned immediately. Because classification relies on [`TlqError`], the
+    /// operation must return this crate's [`Result`]: map your own errors to a
+    /// `TlqError` variant that reflects whether they are transient.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tlq_client::TlqClient;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), tlq_client::TlqError> {
+    ///     let client = TlqClient::new("localhost", 1337)?;
+    ///
+    ///     let count = client
+    ///         .retry(|| async {
+    ///             let messages = client.get_messages(10).await?;
+    ///             Ok(messages.len())
+    ///         })
+    ///         .await?;
+    ///     println!("Fetched {} messages", count);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns the first non-retryable error, or
+    /// [`TlqError::MaxRetriesExceeded`] wrapping the last error once the retry
+    /// limit is reached.
+    pub async fn retry<T, F, Fut>(&self, operation: F) -> Result<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: std::future::Future<Output = Result<T>>,
+    {
+        self.run_with_retries(operation, TlqError::is_retryable)
+            .await
+    }
+
+    /// Returns the number of requests currently in their retry phase.
+    ///
+    /// Counts requests of this client and all its clones that failed at least
+    /// once and are backing off or retrying. Useful as a gauge during incidents,
+    /// especially together with
+    /// [`max_concurrent_retries`](ConfigBuilder::max_concurrent_retries).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tlq_client::TlqClient;
+    ///
+    /// let client = TlqClient::new("localhost", 1337).unwrap();
+    /// assert_eq!(client.retries_in_flight(), 0);
+    /// ```
+    pub fn retries_in_flight(&self) -> usize {
+        self.retry_limit - self.retry_slots.available_permits()
+    }
+
+    /// Returns the number of requests currently in flight.
+    ///
+    /// Counts HTTP requests of this client and all its clones that have been
+    /// started and haven't completed yet, including each attempt of a retried
+    /// operation while it runs (but not the backoff between attempts). A
+    /// request stops counting when it completes, fails or is cancelled. A
+    /// steadily high count suggests the client, rather than the server, is the
+    /// bottleneck.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tlq_client::TlqClient;
+    ///
+    /// let client = TlqClient::new("localhost", 1337).unwrap();
+    /// assert_eq!(client.in_flight(), 0);
+    /// ```
+    pub fn in_flight(&self) -> usize {
+        self.in_flight.load(Ordering::Relaxed)
+    }
+
+    // Runs `operation` on chunks of `ids` as configured by `options`.
+    async fn in_chunks<'a, F, Fut>(
+        &self,
+        ids: &'a [Uuid],
+        options: &BatchOptions,
+        mut operation: F,
+    ) -> Result<BatchOutcome>
+    where
+        F: FnMut(&'a [Uuid]) -> Fut,
+        Fut: std::future::Future<Output = Result<String>>,
+    {
+        if ids.is_empty() {
+            return Err(TlqError::Validation("No message IDs provided".to_string()));
+        }
+        let chunk_size = match options.chunk_size {
+            Some(0) => {
+                return Err(TlqError::Validation(
+                    "Chunk size must be greater than 0".to_string(),
+                ))
+            }
+            Some(size) => size,
+            None => ids.len(),
+        };
+
+        let mut outcome = BatchOutcome::default();
+        for chunk in ids.chunks(chunk_size) {
+            match operation(chunk).await {
+                Ok(_) => outcome.processed += chunk.len(),
+                Err(error) if options.fail_fast => return Err(error),
+                Err(error) => outcome.failures.push(ChunkFailure {
+                    ids: chunk.to_vec(),
+                    error,
+                }),
+            }
+        }
+        Ok(outcome)
+    }
+
+    // Sleeps for the random startup delay before the client's first request.
+    // Concurrent first requests all wait for the same delay.
+    async fn wait_startup_jitter(&self) {
+        let jitter = self.config.startup_jitter;
+        if jitter.is_zero() {
+            return;
+        }
+        self.startup_jitter
+            .get_or_init(|| tokio::time::sleep(random_delay(jitter)))
+            .await;
+    }
+
+    // Checks that the server answers and primes the cached size limit.
+    async fn warm_up(&self) -> Result<()> {
+        if !self.health_check().await? {
+            return Err(TlqError::Connection(format!(
+                "Server at {} failed the health check",
+                self.base_url
+            )));
+        }
+        self.max_message_size().await?;
+        Ok(())
+    }
+
+    // The request path for `endpoint`, scoped to the configured queue.
+    fn request_target(&self, endpoint: &str) -> String {
+        if self.config.queue.is_empty() {
+            endpoint.to_string()
+        } else {
+            format!(
+                "{}?queue={}",
+                endpoint,
+                encode_query_value(&self.config.queue)
+            )
+        }
+    }
+
+    // Headers added to every request: the protocol version, plus optional
+    // headers derived from the configuration. Each is terminated by CRLF.
+    fn extra_headers(&self) -> Result<String> {
+        let mut headers = format!(
+            "{}: {}\r\n",
+            CLIENT_PROTOCOL_HEADER, CLIENT_PROTOCOL_VERSION
+        );
+        if !self.config.client_name.is_empty() {
+            check_header_value("X-Client-Name", &self.config.client_name)?;
+            headers.push_str(&format!("X-Client-Name: {}\r\n", self.config.client_name));
+        }
+        if let Some(token) = &self.config.auth_token {
+            check_header_value("Authorization", token)?;
+            headers.push_str(&format!("Authorization: Bearer {}\r\n", token));
+        }
+        for (name, value) in &self.config.headers {
+            if self.config.auth_token.is_some() && name.eq_ignore_ascii_case("authorization") {
+                continue;
+            }
+            check_header_name(name)?;
+            check_header_value(name, value)?;
+            headers.push_str(&format!("{}: {}\r\n", name, value));
+        }
+        // Trace context headers set by hand take precedence
+        #[cfg(feature = "otel")]
+        if !self.config.headers.iter().any(|(name, _)| {
+            name.eq_ignore_ascii_case("traceparent") || name.eq_ignore_ascii_case("tracestate")
+        }) {
+            headers.push_str(&crate::otel::trace_context_headers());
+        }
+        Ok(headers)
+    }
+
+    // Whether the server has `endpoint`, to tell a 404 for a missing route from
+    // a 404 for an unknown message. A route that has answered is known to
+    // exist; otherwise it is probed with an empty request, which a server with
+    // the route rejects as malformed rather than with a 404. The answer is
+    // cached for the lifetime of the client (and its clones).
+    async fn has_route(&self, endpoint: &'static str) -> Result<bool> {
+        if let Some(&known) = self.routes.lock().unwrap().get(endpoint) {
+            return Ok(known);
+        }
+
+        let exists = match self
+            .single_request::<_, serde_json::Value>(endpoint, &serde_json::json!({}))
+            .await
+        {
+            Err(TlqError::Server { status: 404, .. }) => false,
+            Ok(_) | Err(TlqError::Server { .. } | TlqError::Serialization(_)) => true,
+            Err(err) => return Err(err),
+        };
+        self.routes.lock().unwrap().insert(endpoint, exists);
+        Ok(exists)
+    }
+
+    // Records that `endpoint` answered, so a later 404 from it means the
+    // message is unknown rather than the route missing.
+    fn route_answered(&self, endpoint: &'static str) {
+        self.routes.lock().unwrap().insert(endpoint, true);
+    }
+
+    // The message size limit: the server-reported limit when `auto_max_size` is
+    // enabled (fetched once and cached), otherwise the configured one. Servers
+    // without an `/info` endpoint or without a reported limit fall back to the
+    // configured limit. Any other server error falls back for this call only,
+    // leaving the cache empty so the next call asks `/info` again.
+    async fn max_message_size(&self) -> Result<usize> {
+        let fallback = self.config.max_message_size;
+        if !self.config.auto_max_size {
+            return Ok(fallback);
+        }
+
+        let result = self
+            .server_max_size
+            .get_or_try_init(|| async {
+                match self
+                    .single_request::<_, ServerInfoResponse>("/info", &serde_json::json!({}))
+                    .await
+                {
+                    Ok(info) => Ok(info.max_message_size.unwrap_or(fallback)),
+                    Err(TlqError::Server { status: 404, .. } | TlqError::Serialization(_)) => {
+                        Ok(fallback)
+                    }
+                    Err(e) => Err(e),
+                }
+            })
+            .await;
+
+        match result {
+            Ok(&size) => Ok(size),
+            Err(TlqError::Server { .. }) => Ok(fallback),
+            Err(e) => Err(e),
+        }
+    }
+
+    // Size of a message body as counted against the limit: the raw length, or
+    // the JSON-encoded length (without the surrounding quotes) when configured.
+    fn message_size(&self, body: &str) -> Result<usize> {
+        if self.config.size_counts_encoding {
+            Ok(serde_json::to_string(body)?.len() - 2)
+        } else {
+            Ok(body.len())
+        }
+    }
+
+    // Helper function to parse HTTP response - extracted for testing
+    fn parse_http_response(response: &str) -> Result<&str> {
+        if let Some(body_start) = response.find("\r\n\r\n") {
+            let headers = &response[..body_start];
+            let body = &response[body_start + 4..];
+
+            if let Some(status_line) = headers.lines().next() {
+                let parts: Vec<&str> = status_line.split_whitespace().collect();
+                if parts.len() >= 2 {
+                    if let Ok(status_code) = parts[1].parse::<u16>() {
+                        if let Some(server) = protocol_mismatch(status_code, response) {
+                            return Err(TlqError::ProtocolMismatch {
+                                client: CLIENT_PROTOCOL_VERSION.to_string(),
+                                server,
+                            });
+                        }
+                        if matches!(status_code, 429 | 503) {
+                            if let Some(retry_after) = response_retry_after(response) {
+                                return Err(TlqError::Throttled {
+                                    status: status_code,
+                                    message: body.to_string(),
+                                    retry_after,
+                                });
+                            }
+                        }
+                        if status_code >= 400 {
+                            return Err(TlqError::Server {
+                                status: status_code,
+                                message: body.to_string(),
+                            });
+                        }
+                    }
+                }
+            }
+
+            Ok(body)
+        } else {
+            Err(TlqError::Connection("Invalid HTTP response".to_string()))
+        }
+    }
+}
+
+// Runs `request` in a `tlq_request` span for `endpoint`, recording the
+// elapsed time and how the request ended. This is the one instrumentation
+// point for requests; `attempt` is only recorded by operations that retry.
+// With the `otel` feature, the request also gets an OpenTelemetry span.
+#[cfg(feature = "tracing")]
+#[tracing::instrument(
+    name = "tlq_request",
+    level = "debug",
+    skip_all,
+    fields(
+        endpoint = %endpoint,
+        attempt = tracing::field::Empty,
+        status = tracing::field::Empty,
+        elapsed_ms = tracing::field::Empty,
+    )
+)]
+async fn traced<T>(
+    endpoint: &str,
+    request: impl std::future::Future<Output = Result<T>>,
+) -> Result<T> {
+    #[cfg(feature = "otel")]
+    let request = crate::otel::in_span(endpoint, request);
+    let started = Instant::now();
+    let result = request.await;
+    tracing::Span::current().record("elapsed_ms", started.elapsed().as_millis() as u64);
+    match &result {
+        Ok(_) => tracing::debug!("request completed"),
+        Err(err) => tracing::debug!(error = %err, "request failed"),
+    }
+    result
+}
+
+#[cfg(not(feature = "tracing"))]
+async fn traced<T>(
+    endpoint: &str,
+    request: impl std::future::Future<Output = Result<T>>,
+) -> Result<T> {
+    #[cfg(feature = "otel")]
+    let request = crate::otel::in_span(endpoint, request);
+    #[cfg(not(feature = "otel"))]
+    let _ = endpoint;
+    request.await
+}
+
+// The status code from the status line of a raw HTTP response.
+#[cfg(any(feature = "tracing", feature = "stream", feature = "otel"))]
+fn response_status(response: &str) -> Option<u16> {
+    response.split_whitespace().nth(1)?.parse().ok()
+}
+
+// The TLS connector for `config`, if TLS is enabled. Without a custom
+// `tls_config`, the server certificate is verified against the Mozilla roots.
+#[cfg(feature = "tls")]
+fn tls_connector(config: &Config) -> Option<TlsConnector> {
+    if !config.tls {
+        return None;
+    }
+
+    let mut tls_config = config.tls_config.clone().unwrap_or_else(|| {
+        let roots = rustls::RootCertStore {
+            roots: webpki_roots::TLS_SERVER_ROOTS.to_vec(),
+        };
+        let tls_config = rustls::ClientConfig::builder_with_provider(Arc::new(
+            rustls::crypto::ring::default_provider(),
+        ))
+        .with_safe_default_protocol_versions()
+        .expect("the ring provider supports the default protocol versions")
+        .with_root_certificates(roots)
+        .with_no_client_auth();
+        Arc::new(tls_config)
+    });
+    if config.tls_insecure_skip_verify {
+        #[cfg(feature = "tracing")]
+        tracing::warn!(
+            host = %config.host,
+            "TLS certificate verification is disabled (tls_insecure_skip_verify); \
+             never use this in production"
+        );
+        let mut insecure = (*tls_config).clone();
+        let provider = insecure.crypto_provider().clone();
+        insecure
+            .dangerous()
+            .set_certificate_verifier(Arc::new(AcceptAnyCertificate(provider)));
+        tls_config = Arc::new(insecure);
+    }
+    Some(TlsConnector::from(tls_config))
+}
+
+// Accepts any server certificate, for `tls_insecure_skip_verify`. Handshake
+// signatures are still verified with the provider's algorithms.
+#[cfg(feature = "tls")]
+#[derive(Debug)]
+struct AcceptAnyCertificate(Arc<rustls::crypto::CryptoProvider>);
+
+#[cfg(feature = "tls")]
+impl rustls::client::danger::ServerCertVerifier for AcceptAnyCertificate {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &rustls::pki_types::CertificateDer<'_>,
+        _intermediates: &[rustls::pki_types::CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: rustls::pki_types::UnixTime,
+    ) -> std::result::Result<rustls::client::danger::ServerCertVerified, rustls::Error> {
+        Ok(rustls::client::danger::ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &rustls::pki_types::CertificateDer<'_>,
+        dss: &rustls::DigitallySignedStruct,
+    ) -> std::result::Result<rustls::client::danger::HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls12_signature(
+            message,
+            cert,
+            dss,
+            &self.0.signature_verification_algorithms,
+        )
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &rustls::pki_types::CertificateDer<'_>,
+        dss: &rustls::DigitallySignedStruct,
+    ) -> std::result::Result<rustls::client::danger::HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls13_signature(
+            message,
+            cert,
+            dss,
+            &self.0.signature_verification_algorithms,
+        )
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<rustls::SignatureScheme> {
+        self.0.signature_verification_algorithms.supported_schemes()
+    }
+}
+
+// Counts a request as in flight until dropped, so the count stays accurate when
+// a request fails or its future is cancelled.
+struct InFlightGuard<'a>(&'a AtomicUsize);
+
+impl<'a> InFlightGuard<'a> {
+    fn new(counter: &'a AtomicUsize) -> Self {
+        counter.fetch_add(1, Ordering::Relaxed);
+        Self(counter)
+    }
+}
+
+impl Drop for InFlightGuard<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+// Reads one HTTP response. With a `Content-Length` header, reads exactly the
+// header block plus that many body bytes, so the server may keep the socket
+// open; without one, the body is delimited by the server closing the connection.
+async fn read_response<S>(stream: &mut S) -> Result<Vec<u8>>
+where
+    S: AsyncRead + Unpin,
+{
+    read_response_buffered(stream, &mut Vec::new()).await
+}
+
+// Like `read_response`, for several responses on one connection: starts with
+// the bytes in `carry` and leaves any bytes read past the response there.
+async fn read_response_buffered<S>(stream: &mut S, carry: &mut Vec<u8>) -> Result<Vec<u8>>
+where
+    S: AsyncRead + Unpin,
+{
+    let mut response = std::mem::take(carry);
+    let mut chunk = [0u8; 8192];
+
+    let header_end = loop {
+        if let Some(pos) = response.windows(4).position(|w| w == b"\r\n\r\n") {
+            break pos;
+        }
+        let n = stream.read(&mut chunk).await?;
+        if n == 0 {
+            // Incomplete headers; left to the response parser to reject
+            return Ok(response);
+        }
+        response.extend_from_slice(&chunk[..n]);
+    };
+
+    let headers = String::from_utf8_lossy(&response[..header_end]);
+    let content_length = headers
+        .lines()
+        .skip(1)
+        .filter_map(|line| line.split_once(':'))
+        .find(|(name, _)| name.trim().eq_ignore_ascii_case("content-length"))
+        .map(|(_, value)| {
+            value.trim().parse::<usize>().map_err(|_| {
+                TlqError::Connection(format!("Invalid Content-Length: {}", value.trim()))
+            })
+        })
+        .transpose()?;
+
+    let Some(content_length) = content_length else {
+        stream.read_to_end(&mut response).await?;
+        return Ok(response);
+    };
+
+    let total = header_end + 4 + content_length;
+    while response.len() < total {
+        let n = stream.read(&mut chunk).await?;
+        if n == 0 {
+            return Err(TlqError::Connection(format!(
+                "Connection closed after {} of {} body bytes",
+                response.len() - header_end - 4,
+                content_length
+            )));
+        }
+        response.extend_from_slice(&chunk[..n]);
+    }
+    *carry = response.split_off(total);
+    Ok(response)
+}
+
+// Detects a server rejecting the client's protocol version: a 426 (Upgrade
+// Required), or a 400 naming a server protocol other than ours. Returns the
+// server's protocol version, if it reported one.
+fn protocol_mismatch(status: u16, response: &str) -> Option<Option<String>> {
+    let server = response_header(response, SERVER_PROTOCOL_HEADER);
+    let mismatch = match status {
+        426 => true,
+        400 => server.is_some_and(|version| version != CLIENT_PROTOCOL_VERSION),
+        _ => false,
+    };
+    mismatch.then(|| server.map(str::to_string))
+}
+
+// The time in the `Date` header of a raw HTTP response, if present and valid.
+fn response_date(response: &str) -> Option<SystemTime> {
+    response_header(response, "date").and_then(parse_http_date)
+}
+
+// The delay in the `Retry-After` header of a raw HTTP response, given either
+// in seconds or as an HTTP date. A date is measured from the response's `Date`
+// header when present, so clock skew between client and server cancels out.
+fn response_retry_after(response: &str) -> Option<Duration> {
+    let value = response_header(response, "retry-after")?;
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let at = parse_http_date(value)?;
+    let now = response_date(response).unwrap_or_else(SystemTime::now);
+    Some(at.duration_since(now).unwrap_or(Duration::ZERO))
+}
+
+// The value of header `name` (case-insensitive) in a raw HTTP response.
+fn response_header<'a>(response: &'a str, name: &str) -> Option<&'a str> {
+    let headers = &response[..response.find("\r\n\r\n")?];
+    headers
+        .lines()
+        .skip(1)
+        .filter_map(|line| line.split_once(':'))
+        .find(|(key, _)| key.trim().eq_ignore_ascii_case(name))
+        .map(|(_, value)| value.trim())
+}
+
+// Whether the response declares a newline-delimited JSON body.
+fn is_ndjson(response: &str) -> bool {
+    response_header(response, "content-type").is_some_and(|value| {
+        value
+            .split(';')
+            .next()
+            .is_some_and(|media_type| media_type.trim().eq_ignore_ascii_case(NDJSON_CONTENT_TYPE))
+    })
+}
+
+// Rewrites a newline-delimited JSON body as a JSON array of its lines.
+fn ndjson_to_array(body: &str) -> String {
+    let lines: Vec<_> = body
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect();
+    format!("[{}]", lines.join(","))
+}
+
+// Parses an RFC 9110 IMF-fixdate such as "Sun, 06 Nov 1994 08:49:37 GMT".
+fn parse_http_date(value: &str) -> Option<SystemTime> {
+    const MONTHS: [&str; 12] = [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ];
+
+    let (_, rest) = value.split_once(", ")?;
+    let mut parts = rest.split(' ');
+    let day: i64 = parts.next()?.parse().ok()?;
+    let month_name = parts.next()?;
+    let month = MONTHS.iter().position(|m| *m == month_name)? as i64 + 1;
+    let year: i64 = parts.next()?.parse().ok()?;
+    let mut clock = parts
+        .next()?
+        .split(':')
+        .map(|part| part.parse::<u64>().ok());
+    let (hour, minute, second) = (clock.next()??, clock.next()??, clock.next()??);
+    if parts.next()? != "GMT" || !(1..=31).contains(&day) || hour > 23 || minute > 59 || second > 60
+    {
+        return None;
+    }
+
+    // Days since the Unix epoch for a proleptic Gregorian date
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let year_of_era = y - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = u64::try_from(era * 146_097 + day_of_era - 719_468).ok()?;
+
+    Some(UNIX_EPOCH + Duration::from_secs(days * 86_400 + hour * 3600 + minute * 60 + second))
+}
+
+// Rejects header names that aren't HTTP tokens, and headers the client sets
+// itself, where a second copy would make the request ambiguous.
+fn check_header_name(name: &str) -> Result<()> {
+    const MANAGED: [&str; 5] = [
+        "host",
+        "content-type",
+        "content-length",
+        "connection",
+        "transfer-encoding",
+    ];
+
+    let is_token = !name.is_empty()
+        && name
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&b));
+    if !is_token {
+        return Err(TlqError::Validation(format!(
+            "Invalid header name: {:?}",
+            name
+        )));
+    }
+    if MANAGED
+        .iter()
+        .any(|managed| name.eq_ignore_ascii_case(managed))
+    {
+        return Err(TlqError::Validation(format!(
+            "Header {} is set by the client and can't be overridden",
+            name
+        )));
+    }
+    Ok(())
+}
+
+// Rejects line breaks, which would let a value inject further headers. The
+// value itself is left out of the error, since it may be a credential.
+fn check_header_value(name: &str, value: &str) -> Result<()> {
+    if value.contains(['\r', '\n']) {
+        return Err(TlqError::Validation(format!(
+            "Invalid value for header {}: line breaks are not allowed",
+            name
+        )));
+    }
+    Ok(())
+}
+
+// Joins host and port for connecting and the `Host` header, bracketing IPv6
+// literals (`[::1]:1337`) so the port separator stays unambiguous.
+fn host_port(host: &str, port: u16) -> String {
+    if host.parse::<Ipv6Addr>().is_ok() {
+        format!("[{}]:{}", host, port)
+    } else {
+        format!("{}:{}", host, port)
+    }
+}
+
+// Percent-encodes everything except RFC 3986 unreserved characters.
+fn encode_query_value(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~') {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    encoded
+}
+
+// Writes all slices using vectored I/O, so a request can be sent from several
+// buffers without first copying them into one.
+async fn write_all_vectored<S>(stream: &mut S, mut parts: &mut [IoSlice<'_>]) -> Result<()>
+where
+    S: AsyncWrite + Unpin,
+{
+    IoSlice::advance_slices(&mut parts, 0);
+    while !parts.is_empty() {
+        let written = stream.write_vectored(parts).await?;
+        if written == 0 {
+            return Err(std::io::Error::from(std::io::ErrorKind::WriteZero).into());
+        }
+        IoSlice::advance_slices(&mut parts, written);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAX_MESSAGE_SIZE: usize = 65536;
+
+    #[test]
+    fn test_parse_http_response_success() {
+        let response =
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\n\r\n{\"message\":\"success\"}";
+
+        let result = TlqClient::parse_http_response(response);
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), "{\"message\":\"success\"}");
+    }
+
+    #[test]
+    fn test_parse_http_response_server_error() {
+        let response = "HTTP/1.1 500 Internal Server Error\r\nContent-Type: text/plain\r\n\r\nInternal server error occurred";
+
+        let result = TlqClient::parse_http_response(response);
+        match result {
+            Err(TlqError::Server { status, message }) => {
+                assert_eq!(status, 500);
+                assert_eq!(message, "Internal server error occurred");
+            }
+            _ => panic!("Expected server error"),
+        }
+    }
+
+    #[test]
+    fn test_parse_http_response_client_error() {
+        let response = "HTTP/1.1 400 Bad Request\r\nContent-Type: text/plain\r\n\r\nBad request";
+
+        let result = TlqClient::parse_http_response(response);
+        match result {
+            Err(TlqError::Server { status, message }) => {
+                assert_eq!(status, 400);
+                assert_eq!(message, "Bad request");
+            }
+            _ => panic!("Expected client error"),
+        }
+    }
+
+    #[test]
+    fn test_parse_http_response_protocol_mismatch() {
+        let response = "HTTP/1.1 400 Bad Request\r\nX-TLQ-Server-Protocol: 2\r\n\r\nunknown field";
+        match TlqClient::parse_http_response(response) {
+            Err(TlqError::ProtocolMismatch { client, server }) => {
+                assert_eq!(client, CLIENT_PROTOCOL_VERSION);
+                assert_eq!(server.as_deref(), Some("2"));
+            }
+            other => panic!("Expected ProtocolMismatch error, got {:?}", other),
+        }
+
+        let response = "HTTP/1.1 426 Upgrade Required\r\n\r\n";
+        assert!(matches!(
+            TlqClient::parse_http_response(response),
+            Err(TlqError::ProtocolMismatch { server: None, .. })
+        ));
+
+        // A 400 from a server on the same protocol is an ordinary error
+        let response = format!(
+            "HTTP/1.1 400 Bad Request\r\nX-TLQ-Server-Protocol: {}\r\n\r\nbad",
+            CLIENT_PROTOCOL_VERSION
+        );
+        assert!(matches!(
+            TlqClient::parse_http_response(&response),
+            Err(TlqError::Server { status: 400, .. })
+        ));
+    }
+
+    #[test]
+    fn test_parse_http_response_retry_after() {
+        let response = "HTTP/1.1 429 Too Many Requests\r\nRetry-After: 7\r\n\r\nslow down";
+        match TlqClient::parse_http_response(response) {
+            Err(TlqError::Throttled {
+                status,
+                message,
+                retry_after,
+            }) => {
+                assert_eq!(status, 429);
+                assert_eq!(message, "slow down");
+                assert_eq!(retry_after, Duration::from_secs(7));
+            }
+            other => panic!("Expected Throttled error, got {:?}", other),
+        }
+
+        let response = "HTTP/1.1 503 Service Unavailable\r\n\
+            Date: Sun, 06 Nov 1994 08:49:37 GMT\r\n\
+            Retry-After: Sun, 06 Nov 1994 08:51:37 GMT\r\n\r\n";
+        assert_eq!(
+            TlqClient::parse_http_response(response)
+                .unwrap_err()
+                .retry_after(),
+            Some(Duration::from_secs(120))
+        );
+
+        // A date in the past means retry right away
+        let response =
+            "HTTP/1.1 503 Service Unavailable\r\nRetry-After: Sun, 06 Nov 1994 08:49:37 GMT\r\n\r\n";
+        assert_eq!(
+            TlqClient::parse_http_response(response)
+                .unwrap_err()
+                .retry_after(),
+            Some(Duration::ZERO)
+        );
+
+        // Without a usable Retry-After, or on other statuses, it's a plain server error
+        for response in [
+            "HTTP/1.1 503 Service Unavailable\r\n\r\n",
+            "HTTP/1.1 429 Too Many Requests\r\nRetry-After: soon\r\n\r\n",
+            "HTTP/1.1 500 Internal Server Error\r\nRetry-After: 7\r\n\r\n",
+        ] {
+            assert!(matches!(
+                TlqClient::parse_http_response(response),
+                Err(TlqError::Server { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn test_parse_http_response_no_headers_separator() {
+        let response =
+            "HTTP/1.1 200 OK\nContent-Type: application/json\n{\"incomplete\":\"response\"}";
+
+        let result = TlqClient::parse_http_response(response);
+        match result {
+            Err(TlqError::Connection(msg)) => {
+                assert_eq!(msg, "Invalid HTTP response");
+            }
+            _ => panic!("Expected connection error"),
+        }
+    }
+
+    #[test]
+    fn test_parse_http_response_malformed_status_line() {
+        let response = "INVALID_STATUS_LINE\r\n\r\n{\"data\":\"test\"}";
+
+        let result = TlqClient::parse_http_response(response);
+        // Should still succeed because we only check if parts.len() >= 2 and parse fails gracefully
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), "{\"data\":\"test\"}");
+    }
+
+    #[test]
+    fn test_parse_http_response_empty_body() {
+        let response = "HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n";
+
+        let result = TlqClient::parse_http_response(response);
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), "");
+    }
+
+    #[test]
+    fn test_parse_http_response_with_extra_headers() {
+        let response = "HTTP/1.1 201 Created\r\nContent-Type: application/json\r\nServer: TLQ/1.0\r\nConnection: close\r\n\r\n{\"id\":\"123\",\"status\":\"created\"}";
+
+        let result = TlqClient::parse_http_response(response);
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), "{\"id\":\"123\",\"status\":\"created\"}");
+    }
+
+    #[test]
+    fn test_parse_http_response_status_code_edge_cases() {
+        // Test various status codes around the 400 boundary
+
+        // 399 should be success (< 400)
+        let response_399 = "HTTP/1.1 399 Custom Success\r\n\r\n{\"ok\":true}";
+        let result = TlqClient::parse_http_response(response_399);
+        assert!(result.is_ok());
+
+        // 400 should be error (>= 400)
+        let response_400 = "HTTP/1.1 400 Bad Request\r\n\r\nBad request";
+        let result = TlqClient::parse_http_response(response_400);
+        assert!(matches!(result, Err(TlqError::Server { status: 400, .. })));
+
+        // 599 should be error
+        let response_599 = "HTTP/1.1 599 Custom Error\r\n\r\nCustom error";
+        let result = TlqClient::parse_http_response(response_599);
+        assert!(matches!(result, Err(TlqError::Server { status: 599, .. })));
+    }
+
+    #[test]
+    fn test_max_message_size_default() {
+        assert_eq!(Config::default().max_message_size, MAX_MESSAGE_SIZE);
+    }
+
+    #[test]
+    fn test_client_creation() {
+        let client = TlqClient::new("test-host", 9999);
+        assert!(client.is_ok());
+
+        let client = client.unwrap();
+        assert_eq!(client.base_url, "test-host:9999");
+    }
+
+    #[test]
+    fn test_client_from_url() {
+        let client = TlqClient::from_url("tlq://queue.example.com:8080?max_retries=5").unwrap();
+        assert_eq!(client.base_url, "queue.example.com:8080");
+        assert_eq!(client.config.max_retries, 5);
+
+        assert!(matches!(
+            TlqClient::from_url("queue.example.com:8080"),
+            Err(TlqError::Validation(_))
+        ));
+    }
+
+    #[test]
+    fn test_base_url_brackets_ipv6_hosts() {
+        assert_eq!(TlqClient::new("::1", 1337).unwrap().base_url, "[::1]:1337");
+        assert_eq!(
+            TlqClient::new("2001:db8:85a3::8a2e:370:7334", 8080)
+                .unwrap()
+                .base_url,
+            "[2001:db8:85a3::8a2e:370:7334]:8080"
+        );
+        assert_eq!(
+            TlqClient::new("127.0.0.1", 1337).unwrap().base_url,
+            "127.0.0.1:1337"
+        );
+        assert_eq!(
+            TlqClient::new("queue.example.com", 1337).unwrap().base_url,
+            "queue.example.com:1337"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_ipv6_host_header() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        use tokio::net::TcpListener;
+
+        let Ok(listener) = TcpListener::bind("[::1]:0").await else {
+            // No IPv6 loopback in this environment
+            return;
+        };
+        let port = listener.local_addr().unwrap().port();
+        let server = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut request = vec![0; 4096];
+            let n = stream.read(&mut request).await.unwrap();
+            stream
+                .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 5\r\n\r\nHello")
+                .await
+                .unwrap();
+            String::from_utf8_lossy(&request[..n]).into_owned()
+        });
+
+        let client = TlqClient::new("::1", port).unwrap();
+        assert!(client.health_check().await.unwrap());
+        let request = server.await.unwrap();
+        assert!(request.contains(&format!("Host: [::1]:{}\r\n", port)));
+    }
+
+    #[test]
+    fn test_client_with_config() {
+        let config = Config {
+            host: "custom-host".to_string(),
+            port: 8080,
+            timeout: Duration::from_secs(10),
+            max_retries: 5,
+            retry_delay: Duration::from_millis(200),
+            ..Config::default()
+        };
+
+        let client = TlqClient::with_config(config);
+        assert_eq!(client.base_url, "custom-host:8080");
+        assert_eq!(client.config.max_retries, 5);
+        assert_eq!(client.config.timeout, Duration::from_secs(10));
+    }
+
+    #[test]
+    fn test_message_size_validation() {
+        let _client = TlqClient::new("localhost", 1337).unwrap();
+
+        // Test exact limit
+        let message_at_limit = "x".repeat(MAX_MESSAGE_SIZE);
+        let result = std::panic::catch_unwind(|| {
+            // We can't actually test async methods in sync tests without tokio,
+            // but we can verify the constant is correct
+            assert_eq!(message_at_limit.len(), MAX_MESSAGE_SIZE);
+        });
+        assert!(result.is_ok());
+
+        // Test over limit
+        let message_over_limit = "x".repeat(MAX_MESSAGE_SIZE + 1);
+        assert_eq!(message_over_limit.len(), MAX_MESSAGE_SIZE + 1);
+    }
+
+    #[tokio::test]
+    async fn test_add_message_size_validation() {
+        let client = TlqClient::new("localhost", 1337).unwrap();
+
+        // Test message at exact size limit (should be rejected because it's over the limit)
+        let large_message = "x".repeat(MAX_MESSAGE_SIZE + 1);
+        let result = client.add_message(large_message).await;
+
+        match result {
+            Err(TlqError::MessageTooLarge { size, .. }) => {
+                assert_eq!(size, MAX_MESSAGE_SIZE + 1);
+            }
+            _ => panic!("Expected MessageTooLarge error"),
+        }
+
+        // Test empty message (should be valid)
+        let empty_message = "";
+        // We can't actually test without a server, but we can verify it passes size validation
+        assert!(empty_message.len() <= MAX_MESSAGE_SIZE);
+
+        // Test message exactly at limit (should be valid)
+        let max_message = "x".repeat(MAX_MESSAGE_SIZE);
+        // Size check should pass
+        assert_eq!(max_message.len(), MAX_MESSAGE_SIZE);
+    }
+
+    #[tokio::test]
+    async fn test_get_messages_validation() {
+        let client = TlqClient::new("localhost", 1337).unwrap();
+
+        // Test zero count (should be rejected)
+        let result = client.get_messages(0).await;
+        match result {
+            Err(TlqError::Validation(msg)) => {
+                assert_eq!(msg, "Count must be greater than 0");
+            }
+            _ => panic!("Expected validation error for zero count"),
+        }
+
+        // Test valid counts - these should pass without validation errors
+        let _ = client.get_messages(1).await; // Should be valid
+        let _ = client.get_messages(100).await; // Should be valid
+        let _ = client.get_messages(u32::MAX).await; // Should be valid
+    }
+
+    #[tokio::test]
+    async fn test_delete_messages_validation() {
+        let client = TlqClient::new("localhost", 1337).unwrap();
+
+        // Test empty IDs array
+        let result = client.delete_messages(&[]).await;
+        match result {
+            Err(TlqError::Validation(msg)) => {
+                assert_eq!(msg, "No message IDs provided");
+            }
+            _ => panic!("Expected validation error for empty IDs"),
+        }
+
+        // Test delete_message (single ID) - should not have validation issue
+        use uuid::Uuid;
+        let test_id = Uuid::now_v7();
+        // We can't test the actual call without a server, but we can verify
+        // it would call delete_messages with a single-item array
+        assert!(!vec![test_id].is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_retry_messages_validation() {
+        let client = TlqClient::new("localhost", 1337).unwrap();
+
+        // Test empty IDs array
+        let result = client.retry_messages(&[]).await;
+        match result {
+            Err(TlqError::Validation(msg)) => {
+                assert_eq!(msg, "No message IDs provided");
+            }
+            _ => panic!("Expected validation error for empty IDs"),
+        }
+
+        // Test retry_message (single ID) - should not have validation issue
+        use uuid::Uuid;
+        let test_id = Uuid::now_v7();
+        // We can't test the actual call without a server, but we can verify
+        // it would call retry_messages with a single-item array
+        assert!(!vec![test_id].is_empty());
+    }
+
+    #[test]
+    fn test_client_builder_edge_cases() {
+        // Test builder with minimum values
+        let config = TlqClient::builder()
+            .host("")
+            .port(0)
+            .timeout_ms(0)
+            .max_retries(0)
+            .retry_delay_ms(0)
+            .build();
+
+        let client = TlqClient::with_config(config);
+        assert_eq!(client.base_url, ":0");
+        assert_eq!(client.config.max_retries, 0);
+        assert_eq!(client.config.timeout, Duration::from_millis(0));
+
+        // Test builder with maximum reasonable values
+        let config = TlqClient::builder()
+            .host("very-long-hostname-that-might-be-used-in-some-environments")
+            .port(65535)
+            .timeout_ms(600000) // 10 minutes
+            .max_retries(100)
+            .retry_delay_ms(10000) // 10 seconds
+            .build();
+
+        let client = TlqClient::with_config(config);
+        assert!(client.base_url.contains("very-long-hostname"));
+        assert_eq!(client.config.max_retries, 100);
+        assert_eq!(client.config.timeout, Duration::from_secs(600));
+    }
+
+    #[test]
+    fn test_config_validation() {
+        use crate::config::ConfigBuilder;
+        use std::time::Duration;
+
+        // Test various duration configurations
+        let config1 = ConfigBuilder::new()
+            .timeout(Duration::from_nanos(1))
+            .build();
+        assert_eq!(config1.timeout, Duration::from_nanos(1));
+
+        let config2 = ConfigBuilder::new()
+            .retry_delay(Duration::from_secs(3600)) // 1 hour
+            .build();
+        assert_eq!(config2.retry_delay, Duration::from_secs(3600));
+
+        // Test edge case ports
+        let config3 = ConfigBuilder::new().port(1).build();
+        assert_eq!(config3.port, 1);
+
+        let config4 = ConfigBuilder::new().port(65535).build();
+        assert_eq!(config4.port, 65535);
+
+        // Test very high retry counts
+        let config5 = ConfigBuilder::new().max_retries(1000).build();
+        assert_eq!(config5.max_retries, 1000);
+    }
+
+    #[tokio::test]
+    async fn test_request_reply_returns_matching_reply() {
+        use crate::test_support::{message_json, mock_server};
+        use std::sync::atomic::{AtomicU32, Ordering};
+
+        let request_id = Uuid::now_v7();
+        let unrelated_id = Uuid::now_v7();
+        let reply_id = Uuid::now_v7();
+        let polls = AtomicU32::new(0);
+
+        let (port, captured) = mock_server(move |path, _| match path {
+            "/add" => (200, message_json(request_id, "ping", "Ready")),
+            "/get" if polls.fetch_add(1, Ordering::SeqCst) == 0 => (
+                200,
+                format!("[{}]", message_json(unrelated_id, "other", "Processing")),
+            ),
+            "/get" => (
+                200,
+                format!(
+                    "[{}]",
+                    message_json(reply_id, &format!("reply:{}", request_id), "Processing")
+                ),
+            ),
+            _ => (200, "\"Success\"".to_string()),
+        })
+        .await;
+
+        let client = TlqClient::new("127.0.0.1", port).unwrap();
+        let reply = client
+            .request_reply(
+                "ping",
+                |request, candidate| candidate.body == format!("reply:{}", request.id),
+                Duration::from_secs(5),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(reply.id, reply_id);
+
+        let requests = captured.lock().unwrap();
+        let retried: Vec<_> = requests.iter().filter(|r| r.path == "/retry").collect();
+        assert_eq!(retried.len(), 1);
+        assert!(retried[0].body.contains(&unrelated_id.to_string()));
+        let deleted: Vec<_> = requests.iter().filter(|r| r.path == "/delete").collect();
+        assert_eq!(deleted.len(), 1);
+        assert!(deleted[0].body.contains(&reply_id.to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_request_reply_times_out() {
+        use crate::test_support::{message_json, mock_server};
+
+        let request_id = Uuid::now_v7();
+        let (port, _) = mock_server(move |path, _| match path {
+            "/add" => (200, message_json(request_id, "ping", "Ready")),
+            _ => (200, "[]".to_string()),
+        })
+        .await;
+
+        let client = TlqClient::new("127.0.0.1", port).unwrap();
+        let result = client
+            .request_reply("ping", |_, _| true, Duration::from_millis(250))
+            .await;
+
+        assert!(matches!(result, Err(TlqError::Timeout(250))));
+    }
+
+    #[tokio::test]
+    async fn test_request_reply_bounds_slow_polls() {
+        use crate::test_support::{http_response, message_json};
+        use tokio::io::AsyncWriteExt;
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            // Answer the add, then leave every poll hanging
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut buf = [0u8; 1024];
+            let _ = stream.read(&mut buf).await;
+            let add = message_json(Uuid::now_v7(), "ping", "Ready");
+            let _ = stream.write_all(http_response(200, &add).as_bytes()).await;
+
+            let mut held = Vec::new();
+            while let Ok((stream, _)) = listener.accept().await {
+                held.push(stream);
+            }
+        });
+
+        let client = TlqClient::new("127.0.0.1", port).unwrap();
+        let start = std::time::Instant::now();
+        let result = client
+            .request_reply("ping", |_, _| true, Duration::from_millis(200))
+            .await;
+
+        assert!(matches!(result, Err(TlqError::Timeout(200))));
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+
+    #[tokio::test]
+    async fn test_request_reply_deletes_reply_when_release_fails() {
+        use crate::test_support::{message_json, mock_server};
+
+        let request_id = Uuid::now_v7();
+        let unrelated_id = Uuid::now_v7();
+        let reply_id = Uuid::now_v7();
+        let (port, captured) = mock_server(move |path, _| match path {
+            "/add" => (200, message_json(request_id, "ping", "Ready")),
+            "/get" => (
+                200,
+                format!(
+                    "[{},{}]",
+                    message_json(unrelated_id, "other", "Processing"),
+                    message_json(reply_id, "pong", "Processing")
+                ),
+            ),
+            "/retry" => (400, "Bad Request".to_string()),
+            _ => (200, "\"Success\"".to_string()),
+        })
+        .await;
+
+        let client = TlqClient::new("127.0.0.1", port).unwrap();
+        let reply = client
+            .request_reply(
+                "ping",
+                |_, candidate| candidate.body == "pong",
+                Duration::from_secs(5),
+            )
+            .await
+            .unwrap();
+        assert_eq!(reply.id, reply_id);
+
+        let requests = captured.lock().unwrap();
+        let paths: Vec<_> = requests.iter().map(|r| r.path.as_str()).collect();
+        assert_eq!(paths, ["/add", "/get", "/delete", "/retry"]);
+        assert!(requests[2].body.contains(&reply_id.to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_ip_version_filters_resolved_addresses() {
+        use crate::test_support::mock_server;
+
+        let (port, _) = mock_server(|_, _| (200, "\"Success\"".to_string())).await;
+
+        let v4_client = TlqClient::with_config(
+            ConfigBuilder::new()
+                .host("127.0.0.1")
+                .port(port)
+                .ip_version(IpPreference::V4Only)
+                .build(),
+        );
+        assert_eq!(v4_client.purge_queue().await.unwrap(), "Success");
+
+        let v6_client = TlqClient::with_config(
+            ConfigBuilder::new()
+                .host("127.0.0.1")
+                .port(port)
+                .ip_version(IpPreference::V6Only)
+                .max_retries(0)
+                .build(),
+        );
+        match v6_client.purge_queue().await {
+            Err(TlqError::Connection(msg)) => assert_eq!(
+                msg,
+                format!("No IPv6 address resolved for 127.0.0.1:{}", port)
+            ),
+            other => panic!("Expected connection error, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_connect_retries_recover_from_refused_connect() {
+        use crate::test_support::serve;
+
+        // Reserve a port, then release it so the first connect is refused.
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+
+        let delay = Duration::from_millis(500);
+        let client = TlqClient::with_config(
+            ConfigBuilder::new()
+                .host("127.0.0.1")
+                .port(port)
+                .max_retries(0)
+                .connect_retries(1)
+                .connect_retry_delay(delay)
+                .build(),
+        );
+
+        // Listen well after the first connect was refused, but before the retry
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            let listener = tokio::net::TcpListener::bind(("127.0.0.1", port))
+                .await
+                .unwrap();
+            serve(listener, |_, _| (200, "\"Success\"".to_string()));
+        });
+
+        let started = Instant::now();
+        assert_eq!(client.purge_queue().await.unwrap(), "Success");
+        assert!(started.elapsed() >= delay);
+    }
+
+    #[tokio::test]
+    async fn test_connect_retries_disabled_by_default() {
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+
+        let client = TlqClient::with_config(
+            ConfigBuilder::new()
+                .host("127.0.0.1")
+                .port(port)
+                .max_retries(0)
+                .build(),
+        );
+
+        assert_eq!(client.config.connect_retries, 0);
+        assert!(matches!(
+            client.purge_queue().await,
+            Err(TlqError::Connection(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_size_counts_encoding() {
+        // 40,000 quotes fit the raw limit but double to 80,000 bytes when escaped
+        let quoted = "\"".repeat(40_000);
+
+        let raw_client = TlqClient::new("localhost", 1337).unwrap();
+        assert_eq!(raw_client.message_size(&quoted).unwrap(), 40_000);
+
+        let encoded_client = TlqClient::with_config(
+            ConfigBuilder::new()
+                .size_counts_encoding(true)
+                .max_retries(0)
+                .build(),
+        );
+        assert_eq!(encoded_client.message_size(&quoted).unwrap(), 80_000);
+        assert_eq!(encoded_client.message_size("plain").unwrap(), 5);
+
+        match encoded_client.add_message(quoted).await {
+            Err(TlqError::MessageTooLarge { size, .. }) => assert_eq!(size, 80_000),
+            other => panic!("Expected MessageTooLarge error, got {:?}", other),
+        }
+    }
+
+    #[cfg(feature = "bytes")]
+    #[tokio::test]
+    async fn test_add_bytes_zerocopy() {
+        use crate::test_support::{message_json, mock_server};
+        use bytes::Bytes;
+
+        let (port, captured) = mock_server(|_, body| {
+            let request: serde_json::Value = serde_json::from_str(body).unwrap();
+            let body = request["body"].as_str().unwrap();
+            (200, message_json(Uuid::now_v7(), body, "Ready"))
+        })
+        .await;
+        let client = TlqClient::new("127.0.0.1", port).unwrap();
+
+        let message = client
+            .add_bytes_zerocopy(Bytes::from_static(b"plain payload"))
+            .await
+            .unwrap();
+        assert_eq!(message.body, "plain payload");
+
+        let message = client
+            .add_bytes_zerocopy(Bytes::from_static(b"needs \"escaping\"\n"))
+            .await
+            .unwrap();
+        assert_eq!(message.body, "needs \"escaping\"\n");
+        assert_eq!(message.into_body_bytes(), "needs \"escaping\"\n");
+
+        let requests = captured.lock().unwrap();
+        assert_eq!(requests[0].body, r#"{"body":"plain payload"}"#);
+        assert_eq!(
+            requests[0].header("Content-Length"),
+            Some(requests[0].body.len().to_string().as_str())
+        );
+    }
+
+    #[cfg(feature = "bytes")]
+    #[tokio::test]
+    async fn test_add_bytes_zerocopy_validation() {
+        use bytes::Bytes;
+
+        let client = TlqClient::new("localhost", 1337).unwrap();
+
+        match client
+            .add_bytes_zerocopy(Bytes::from(vec![b'x'; MAX_MESSAGE_SIZE + 1]))
+            .await
+        {
+            Err(TlqError::MessageTooLarge { size, .. }) => assert_eq!(size, MAX_MESSAGE_SIZE + 1),
+            other => panic!("Expected MessageTooLarge error, got {:?}", other),
+        }
+
+        match client
+            .add_bytes_zerocopy(Bytes::from_static(&[0xff, 0xfe]))
+            .await
+        {
+            Err(TlqError::Validation(_)) => {}
+            other => panic!("Expected Validation error, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_peek_messages() {
+        use crate::test_support::{message_json, mock_server};
+
+        let id = Uuid::now_v7();
+        let (port, captured) =
+            mock_server(move |_, _| (200, format!("[{}]", message_json(id, "head", "Ready"))))
+                .await;
+        let client = TlqClient::new("127.0.0.1", port).unwrap();
+
+        let messages = client.peek_messages(1).await.unwrap();
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].id, id);
+        assert_eq!(messages[0].state, MessageState::Ready);
+
+        let requests = captured.lock().unwrap();
+        assert_eq!(requests[0].path, "/peek");
+        assert_eq!(requests[0].body, r#"{"count":1}"#);
+    }
+
+    #[tokio::test]
+    async fn test_peek_messages_unsupported() {
+        use crate::test_support::mock_server;
+
+        let (port, captured) = mock_server(|_, _| (404, "Not Found".to_string())).await;
+        let client = TlqClient::new("127.0.0.1", port).unwrap();
+
+        assert!(matches!(
+            client.peek_messages(5).await,
+            Err(TlqError::Server { status: 404, .. })
+        ));
+        let requests = captured.lock().unwrap();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].path, "/peek");
+    }
+
+    #[tokio::test]
+    async fn test_peek_messages_zero_count() {
+        let client = TlqClient::new("localhost", 1337).unwrap();
+
+        match client.peek_messages(0).await {
+            Err(TlqError::Validation(msg)) => assert_eq!(msg, "Count must be greater than 0"),
+            other => panic!("Expected Validation error, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_get_failed_filters_failed_messages() {
+        use crate::test_support::{message_json, mock_server};
+
+        let failed = Uuid::now_v7();
+        let (port, captured) = mock_server(move |_, _| {
+            let messages = [
+                message_json(Uuid::now_v7(), "ready", "Ready"),
+                message_json(failed, "broken", "Failed"),
+                message_json(Uuid::now_v7(), "busy", "Processing"),
+            ];
+            (200, format!("[{}]", messages.join(",")))
+        })
+        .await;
+        let client = TlqClient::new("127.0.0.1", port).unwrap();
+
+        let messages = client.get_failed(3).await.unwrap();
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].id, failed);
+        assert_eq!(captured.lock().unwrap()[0].path, "/peek");
+    }
+
+    #[tokio::test]
+    async fn test_consumer_lag() {
+        use crate::test_support::{message_json, mock_server};
+        use uuid::{NoContext, Timestamp};
+
+        let created = std::time::SystemTime::now() - Duration::from_secs(60);
+        let since_epoch = created.duration_since(std::time::UNIX_EPOCH).unwrap();
+        let id = Uuid::new_v7(Timestamp::from_unix(
+            NoContext,
+            since_epoch.as_secs(),
+            since_epoch.subsec_nanos(),
+        ));
+        let (port, _) =
+            mock_server(move |_, _| (200, format!("[{}]", message_json(id, "old", "Ready")))).await;
+        let lag = TlqClient::new("127.0.0.1", port)
+            .unwrap()
+            .consumer_lag()
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(lag >= Duration::from_secs(60) && lag < Duration::from_secs(70));
+
+        let (port, _) = mock_server(|_, _| (200, "[]".to_string())).await;
+        let client = TlqClient::new("127.0.0.1", port).unwrap();
+        assert_eq!(client.consumer_lag().await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_retry_only_retries_retryable_errors() {
+        use std::sync::atomic::{AtomicU32, Ordering};
+
+        let client = TlqClient::with_config(
+            ConfigBuilder::new()
+                .max_retries(2)
+                .retry_delay(Duration::from_millis(1))
+                .build(),
+        );
+
+        let calls = AtomicU32::new(0);
+        let result = client
+            .retry(|| async {
+                calls.fetch_add(1, Ordering::SeqCst);
+                Err::<(), _>(TlqError::Timeout(10))
+            })
+            .await;
+        assert!(matches!(
+            result,
+            Err(TlqError::MaxRetriesExceeded { max_retries: 2, source })
+                if matches!(*source, TlqError::Timeout(10))
+        ));
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+
+        let calls = AtomicU32::new(0);
+        let result = client
+            .retry(|| async {
+                calls.fetch_add(1, Ordering::SeqCst);
+                Err::<(), _>(TlqError::Validation("bad input".to_string()))
+            })
+            .await;
+        assert!(matches!(result, Err(TlqError::Validation(_))));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        let calls = AtomicU32::new(0);
+        let result = client
+            .retry(|| async {
+                if calls.fetch_add(1, Ordering::SeqCst) == 0 {
+                    Err(TlqError::Connection("refused".to_string()))
+                } else {
+                    Ok("recovered")
+                }
+            })
+            .await;
+        assert_eq!(result.unwrap(), "recovered");
+    }
+
+    #[tokio::test]
+    async fn test_spawn_consumer_forwards_messages() {
+        use crate::test_support::{message_json, mock_server};
+
+        let (port, _) = mock_server(|path, _| match path {
+            "/get" => (
+                200,
+                format!("[{}]", message_json(Uuid::now_v7(), "work", "Processing")),
+            ),
+            _ => (200, "\"Success\"".to_string()),
+        })
+        .await;
+        let client = TlqClient::new("127.0.0.1", port).unwrap();
+
+        let (tx, mut rx) = mpsc::channel(4);
+        let consumer = client.spawn_consumer(10, tx);
+
+        for _ in 0..3 {
+            let message = timeout(Duration::from_secs(5), rx.recv())
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(message.body, "work");
+        }
+
+        drop(rx);
+        timeout(Duration::from_secs(5), consumer)
+            .await
+            .unwrap()
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_spawn_consumer_requeues_when_receiver_drops() {
+        use crate::test_support::{message_json, mock_server};
+        use std::sync::Mutex;
+
+        let id = Uuid::now_v7();
+        let (tx, rx) = mpsc::channel::<Message>(1);
+        let receiver = Mutex::new(Some(rx));
+
+        // The receiver is dropped while the fetch is in flight.
+        let (port, captured) = mock_server(move |path, _| match path {
+            "/get" => {
+                receiver.lock().unwrap().take();
+                (200, format!("[{}]", message_json(id, "work", "Processing")))
+            }
+            _ => (200, "\"Success\"".to_string()),
+        })
+        .await;
+        let client = TlqClient::new("127.0.0.1", port).unwrap();
+
+        let consumer = client.spawn_consumer(1, tx);
+        timeout(Duration::from_secs(5), consumer)
+            .await
+            .unwrap()
+            .unwrap();
+
+        let requests = captured.lock().unwrap();
+        let paths: Vec<_> = requests.iter().map(|r| r.path.as_str()).collect();
+        assert_eq!(paths, vec!["/get", "/retry"]);
+        assert!(requests[1].body.contains(&id.to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_new_warmed() {
+        use crate::test_support::mock_server;
+
+        let (port, captured) = mock_server(|_, _| (200, "\"Hello World\"".to_string())).await;
+        TlqClient::new_warmed("127.0.0.1", port).await.unwrap();
+        assert_eq!(captured.lock().unwrap()[0].path, "/hello");
+
+        let (port, _) = mock_server(|_, _| (503, "unavailable".to_string())).await;
+        match TlqClient::new_warmed("127.0.0.1", port).await {
+            Err(TlqError::Connection(msg)) => assert!(msg.contains("health check")),
+            other => panic!("Expected Connection error, got {:?}", other.err()),
+        }
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        drop(listener);
+        assert!(matches!(
+            TlqClient::new_warmed("127.0.0.1", port).await,
+            Err(TlqError::Connection(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_delete_if_unchanged() {
+        use crate::test_support::mock_server;
+
+        let (port, captured) = mock_server(|_, body| {
+            let request: serde_json::Value = serde_json::from_str(body).unwrap();
+            let unchanged = request["expected_retry_count"] == 2;
+            (200, unchanged.to_string())
+        })
+        .await;
+        let client = TlqClient::new("127.0.0.1", port).unwrap();
+        let id = Uuid::now_v7();
+
+        assert!(client.delete_if_unchanged(id, 2).await.unwrap());
+        assert!(!client.delete_if_unchanged(id, 1).await.unwrap());
+
+        let requests = captured.lock().unwrap();
+        assert_eq!(requests[0].path, "/delete_if");
+        assert!(requests[0].body.contains(&id.to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_delete_message_returning() {
+        use crate::test_support::{message_json, mock_server};
+
+        let id = Uuid::now_v7();
+        let snapshot = message_json(id, "audit me", "Processing");
+        let (port, captured) = mock_server(move |_, body| {
+            if body.contains(&id.to_string()) {
+                (200, snapshot.clone())
+            } else {
+                (200, "null".to_string())
+            }
+        })
+        .await;
+        let client = TlqClient::new("127.0.0.1", port).unwrap();
+
+        let deleted = client.delete_message_returning(id).await.unwrap().unwrap();
+        assert_eq!(deleted.id, id);
+        assert_eq!(deleted.body, "audit me");
+        assert!(client
+            .delete_message_returning(Uuid::now_v7())
+            .await
+            .unwrap()
+            .is_none());
+
+        let requests = captured.lock().unwrap();
+        assert_eq!(requests[0].path, "/delete_returning");
+    }
+
+    #[tokio::test]
+    async fn test_delete_message_returning_falls_back_to_delete() {
+        use crate::test_support::mock_server;
+
+        let (port, captured) = mock_server(|path, _| match path {
+            "/delete" => (200, "\"Success\"".to_string()),
+            _ => (404, "Not Found".to_string()),
+        })
+        .await;
+        let client = TlqClient::with_config(
+            ConfigBuilder::new()
+                .host("127.0.0.1")
+                .port(port)
+                .max_retries(0)
+                .build(),
+        );
+        let id = Uuid::now_v7();
+
+        assert!(client.delete_message_returning(id).await.unwrap().is_none());
+
+        let requests = captured.lock().unwrap();
+        let paths: Vec<_> = requests.iter().map(|r| r.path.as_str()).collect();
+        assert_eq!(paths, ["/delete_returning", "/delete_returning", "/delete"]);
+        assert_eq!(requests[1].body, "{}");
+        assert!(requests[2].body.contains(&id.to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_delete_message_returning_unknown_id() {
+        use crate::test_support::mock_server;
+
+        // A server with the route: malformed requests get 422, unknown ids 404
+        let (port, captured) = mock_server(|_, body| match body {
+            "{}" => (422, "missing field `id`".to_string()),
+            _ => (404, "Message not found".to_string()),
+        })
+        .await;
+        let client = TlqClient::with_config(
+            ConfigBuilder::new()
+                .host("127.0.0.1")
+                .port(port)
+                .max_retries(0)
+                .build(),
+        );
+
+        assert!(client
+            .delete_message_returning(Uuid::now_v7())
+            .await
+            .unwrap()
+            .is_none());
+        assert!(client
+            .delete_message_returning(Uuid::now_v7())
+            .await
+            .unwrap()
+            .is_none());
+
+        // One probe, and no fallback to /delete
+        let requests = captured.lock().unwrap();
+        let paths: Vec<_> = requests.iter().map(|r| r.path.as_str()).collect();
+        assert_eq!(paths, ["/delete_returning"; 3]);
+        assert_eq!(requests[1].body, "{}");
+    }
+
+    #[tokio::test]
+    async fn test_delete_if_unchanged_unsupported() {
+        use crate::test_support::mock_server;
+
+        let (port, _) = mock_server(|_, _| (404, "Not Found".to_string())).await;
+        let client = TlqClient::with_config(
+            ConfigBuilder::new()
+                .host("127.0.0.1")
+                .port(port)
+                .max_retries(0)
+                .build(),
+        );
+
+        match client.delete_if_unchanged(Uuid::now_v7(), 0).await {
+            Err(TlqError::Unsupported(_)) => {}
+            other => panic!("Expected Unsupported error, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_delete_if_unchanged_unknown_id() {
+        use crate::test_support::mock_server;
+
+        // A server with the route: malformed requests get 422, unknown ids 404
+        let (port, captured) = mock_server(|_, body| match body {
+            "{}" => (422, "missing field `id`".to_string()),
+            _ => (404, "Message not found".to_string()),
+        })
+        .await;
+        let client = TlqClient::with_config(
+            ConfigBuilder::new()
+                .host("127.0.0.1")
+                .port(port)
+                .max_retries(0)
+                .build(),
+        );
+
+        for _ in 0..2 {
+            match client.delete_if_unchanged(Uuid::now_v7(), 0).await {
+                Err(TlqError::Server { status: 404, .. }) => {}
+                other => panic!("Expected a 404 Server error, got {:?}", other),
+            }
+        }
+
+        // The route is probed once, then cached
+        let requests = captured.lock().unwrap();
+        let probes = requests.iter().filter(|r| r.body == "{}").count();
+        assert_eq!(requests.len(), 3);
+        assert_eq!(probes, 1);
+    }
+
+    #[tokio::test]
+    async fn test_max_concurrent_retries_fails_fast() {
+        use std::sync::atomic::{AtomicU32, Ordering};
+
+        let client = TlqClient::with_config(
+            ConfigBuilder::new()
+                .max_retries(3)
+                .retry_delay(Duration::from_millis(200))
+                .max_concurrent_retries(1)
+                .build(),
+        );
+
+        let retrying = client.clone();
+        let first = tokio::spawn(async move {
+            retrying
+                .retry(|| async { Err::<(), _>(TlqError::Timeout(1)) })
+                .await
+        });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(client.retries_in_flight(), 1);
+
+        let calls = AtomicU32::new(0);
+        let result = client
+            .retry(|| async {
+                calls.fetch_add(1, Ordering::SeqCst);
+                Err::<(), _>(TlqError::Connection("refused".to_string()))
+            })
+            .await;
+        assert!(matches!(result, Err(TlqError::Connection(_))));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        first.abort();
+        let _ = first.await;
+        assert_eq!(client.retries_in_flight(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_max_retry_duration_bounds_failing_request() {
+        // Accepts connections (via the backlog) but never responds
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let config = ConfigBuilder::new()
+            .host("127.0.0.1")
+            .port(port)
+            .timeout_ms(50)
+            .max_retries(2)
+            .retry_delay_ms(20)
+            .build();
+        let bound = config.max_retry_duration();
+        assert_eq!(bound, Duration::from_millis(210)); // 3 × 50ms + 20ms + 40ms
+
+        let client = TlqClient::with_config(config);
+        let start = tokio::time::Instant::now();
+        assert!(matches!(
+            client.get_messages(1).await,
+            Err(TlqError::MaxRetriesExceeded { max_retries: 2, source })
+                if matches!(*source, TlqError::Timeout(50))
+        ));
+        let elapsed = start.elapsed();
+
+        assert!(elapsed >= bound);
+        assert!(elapsed < bound + Duration::from_millis(100));
+    }
+
+    #[tokio::test]
+    async fn test_move_messages() {
+        use crate::test_support::mock_server;
+
+        let (port, captured) = mock_server(|_, _| (200, "2".to_string())).await;
+        let client = TlqClient::new("127.0.0.1", port).unwrap();
+        let ids = [Uuid::now_v7(), Uuid::now_v7()];
+
+        assert_eq!(client.move_messages(&ids, "archive").await.unwrap(), 2);
+
+        let requests = captured.lock().unwrap();
+        assert_eq!(requests[0].path, "/move");
+        let body: serde_json::Value = serde_json::from_str(&requests[0].body).unwrap();
+        assert_eq!(body["to_queue"], "archive");
+        assert_eq!(body["ids"][1], ids[1].to_string());
+    }
+
+    #[tokio::test]
+    async fn test_move_messages_errors() {
+        use crate::test_support::mock_server;
+
+        let (port, _) = mock_server(|_, _| (404, "Not Found".to_string())).await;
+        let client = TlqClient::with_config(
+            ConfigBuilder::new()
+                .host("127.0.0.1")
+                .port(port)
+                .max_retries(0)
+                .build(),
+        );
+        let ids = [Uuid::now_v7()];
+
+        assert!(matches!(
+            client.move_messages(&ids, "archive").await,
+            Err(TlqError::Unsupported(_))
+        ));
+        assert!(matches!(
+            client.move_messages(&[], "archive").await,
+            Err(TlqError::Validation(_))
+        ));
+        assert!(matches!(
+            client.move_messages(&ids, "").await,
+            Err(TlqError::Validation(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_queue_is_sent_as_query_parameter() {
+        use crate::test_support::mock_server;
+
+        let (port, captured) = mock_server(|_, _| (200, "\"Success\"".to_string())).await;
+        let client = TlqClient::with_config(
+            ConfigBuilder::new()
+                .host("127.0.0.1")
+                .port(port)
+                .queue("orders")
+                .build(),
+        );
+
+        client.purge_queue().await.unwrap();
+        client
+            .with_queue("dead letters/eu")
+            .purge_queue()
+            .await
+            .unwrap();
+        client.with_queue("").purge_queue().await.unwrap();
+
+        let requests = captured.lock().unwrap();
+        assert_eq!(requests[0].path, "/purge?queue=orders");
+        assert_eq!(requests[1].path, "/purge?queue=dead%20letters%2Feu");
+        assert_eq!(requests[2].path, "/purge");
+    }
+
+    #[tokio::test]
+    async fn test_list_queues() {
+        use crate::test_support::mock_server;
+
+        let (port, captured) = mock_server(|path, _| match path {
+            "/queues" => (
+                200,
+                r#"[{"name":"orders","stats":{"ready":3}},{"name":"audit"}]"#.to_string(),
+            ),
+            _ => (404, "Not Found".to_string()),
+        })
+        .await;
+        let client = TlqClient::new("127.0.0.1", port).unwrap();
+
+        let queues = client.list_queues().await.unwrap();
+        let names: Vec<_> = queues.iter().map(|q| q.name.as_str()).collect();
+        assert_eq!(names, vec!["orders", "audit"]);
+        assert_eq!(queues[0].stats.unwrap().ready, 3);
+        assert_eq!(captured.lock().unwrap()[0].path, "/queues");
+
+        let (port, _) = mock_server(|_, _| (404, "Not Found".to_string())).await;
+        let client = TlqClient::with_config(
+            ConfigBuilder::new()
+                .host("127.0.0.1")
+                .port(port)
+                .max_retries(0)
+                .build(),
+        );
+        assert!(matches!(
+            client.list_queues().await,
+            Err(TlqError::Unsupported(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_create_queue() {
+        use crate::test_support::mock_server;
+
+        let (port, captured) = mock_server(|_, body| {
+            if body.contains("\"existing\"") {
+                (409, "Conflict".to_string())
+            } else {
+                (200, "true".to_string())
+            }
+        })
+        .await;
+        let client = TlqClient::new("127.0.0.1", port).unwrap();
+
+        let options = QueueOptions {
+            lock_duration: Some(Duration::from_secs(30)),
+            ..QueueOptions::default()
+        };
+        assert!(client.create_queue("orders", options).await.unwrap());
+        assert!(!client
+            .create_queue("existing", QueueOptions::default())
+            .await
+            .unwrap());
+
+        let requests = captured.lock().unwrap();
+        assert_eq!(requests[0].path, "/queues/create");
+        assert_eq!(
+            requests[0].body,
+            r#"{"name":"orders","lock_duration_ms":30000}"#
+        );
+    }
+
+    #[tokio::test]
+    async fn test_delete_queue() {
+        use crate::test_support::mock_server;
+
+        let (port, captured) = mock_server(|_, body| {
+            let request: serde_json::Value = serde_json::from_str(body).unwrap();
+            match (
+                request["name"].as_str().unwrap(),
+                request["force"].as_bool(),
+            ) {
+                ("busy", Some(false)) => (409, "Conflict".to_string()),
+                ("missing", _) => (200, "false".to_string()),
+                _ => (200, "true".to_string()),
+            }
+        })
+        .await;
+        let client = TlqClient::with_config(
+            ConfigBuilder::new()
+                .host("127.0.0.1")
+                .port(port)
+                .max_retries(0)
+                .build(),
+        );
+
+        assert!(matches!(
+            client.delete_queue("busy", false).await,
+            Err(TlqError::Validation(_))
+        ));
+        assert!(client.delete_queue("busy", true).await.unwrap());
+        assert!(!client.delete_queue("missing", false).await.unwrap());
+        assert!(matches!(
+            client.delete_queue("", true).await,
+            Err(TlqError::Validation(_))
+        ));
+        assert_eq!(captured.lock().unwrap()[0].path, "/queues/delete");
+    }
+
+    #[tokio::test]
+    async fn test_add_messages_splits_by_request_size() {
+        use crate::test_support::{message_json, mock_server};
+
+        let (port, captured) = mock_server(|_, body| {
+            let request: serde_json::Value = serde_json::from_str(body).unwrap();
+            let messages: Vec<_> = request["bodies"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|b| message_json(Uuid::now_v7(), b.as_str().unwrap(), "Ready"))
+                .collect();
+            (200, format!("[{}]", messages.join(",")))
+        })
+        .await;
+        let client = TlqClient::with_config(
+            ConfigBuilder::new()
+                .host("127.0.0.1")
+                .port(port)
+                .max_request_bytes(10_000)
+                .build(),
+        );
+
+        // 20 bodies of ~3KB: at most 3 fit in a 10KB request
+        let bodies: Vec<String> = (0..20)
+            .map(|i| format!("{:02}{}", i, "x".repeat(3_000)))
+            .collect();
+        let messages = client.add_messages(bodies.clone()).await.unwrap();
+
+        let received: Vec<_> = messages.iter().map(|m| m.body.clone()).collect();
+        assert_eq!(received, bodies);
+
+        let requests = captured.lock().unwrap();
+        assert_eq!(requests.len(), 7);
+        for request in requests.iter() {
+            assert_eq!(request.path, "/add_batch");
+            assert!(request.body.len() <= 10_000);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_add_messages_validation() {
+        let client = TlqClient::new("localhost", 1337).unwrap();
+
+        assert!(matches!(
+            client.add_messages(Vec::<String>::new()).await,
+            Err(TlqError::Validation(_))
+        ));
+        match client
+            .add_messages(["ok".to_string(), "x".repeat(MAX_MESSAGE_SIZE + 1)])
+            .await
+        {
+            Err(TlqError::MessageTooLarge { size, max, index }) => {
+                assert_eq!(size, MAX_MESSAGE_SIZE + 1);
+                assert_eq!(max, MAX_MESSAGE_SIZE);
+                assert_eq!(index, Some(1));
+            }
+            other => panic!("Expected MessageTooLarge error, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_add_messages_falls_back_without_batch_endpoint() {
+        use crate::test_support::{message_json, mock_server};
+
+        let (port, captured) = mock_server(|path, body| match path {
+            "/add_batch" => (404, "Not Found".to_string()),
+            _ => {
+                let request: serde_json::Value = serde_json::from_str(body).unwrap();
+                let body = request["body"].as_str().unwrap();
+                (200, message_json(Uuid::now_v7(), body, "Ready"))
+            }
+        })
+        .await;
+        let client = TlqClient::with_config(
+            ConfigBuilder::new()
+                .host("127.0.0.1")
+                .port(port)
+                .max_retries(0)
+                .build(),
+        );
+
+        let messages = client.add_messages(["first", "second"]).await.unwrap();
+        let received: Vec<_> = messages.iter().map(|m| m.body.as_str()).collect();
+        assert_eq!(received, ["first", "second"]);
+
+        let paths: Vec<_> = captured
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|r| r.path.clone())
+            .collect();
+        assert_eq!(paths, ["/add_batch", "/add", "/add"]);
+    }
+
+    #[cfg(feature = "otel")]
+    #[tokio::test]
+    async fn test_otel_propagates_trace_context() {
+        use crate::test_support::{message_json, mock_server};
+        use opentelemetry::context::FutureExt;
+        use opentelemetry::trace::{
+            SpanContext, SpanId, TraceContextExt, TraceFlags, TraceId, TraceState,
+        };
+        use opentelemetry::{global, Context, KeyValue};
+        use opentelemetry_sdk::trace::{InMemorySpanExporterBuilder, SdkTracerProvider};
+
+        let exporter = InMemorySpanExporterBuilder::new().build();
+        let provider = SdkTracerProvider::builder()
+            .with_simple_exporter(exporter.clone())
+            .build();
+        global::set_tracer_provider(provider);
+
+        let (port, captured) =
+            mock_server(|_, _| (200, message_json(Uuid::now_v7(), "traced", "Ready"))).await;
+        let client = TlqClient::new("127.0.0.1", port).unwrap();
+
+        let trace_id = TraceId::from_hex("4bf92f3577b34da6a3ce929d0e0e4736").unwrap();
+        let parent = Context::new().with_remote_span_context(SpanContext::new(
+            trace_id,
+            SpanId::from_hex("00f067aa0ba902b7").unwrap(),
+            TraceFlags::SAMPLED,
+            true,
+            TraceState::from_key_value([("vendor", "value")]).unwrap(),
+        ));
+        client
+            .add_message("traced")
+            .with_context(parent)
+            .await
+            .unwrap();
+
+        let spans = exporter.get_finished_spans().unwrap();
+        assert_eq!(spans.len(), 1);
+        let span = &spans[0];
+        assert_eq!(span.name, "tlq_request");
+        assert_eq!(span.span_context.trace_id(), trace_id);
+        for attribute in [
+            KeyValue::new("tlq.endpoint", "/add"),
+            KeyValue::new("http.request.body.size", 17),
+            KeyValue::new("http.response.status_code", 200),
+        ] {
+            assert!(span.attributes.contains(&attribute), "{:?}", attribute);
+        }
+
+        // The server sees the client span as the parent of its own
+        let requests = captured.lock().unwrap();
+        assert_eq!(
+            requests[0].header("traceparent"),
+            Some(format!("00-{}-{}-01", trace_id, span.span_context.span_id()).as_str())
+        );
+        assert_eq!(requests[0].header("tracestate"), Some("vendor=value"));
+    }
+
+    #[cfg(feature = "otel")]
+    #[tokio::test]
+    async fn test_otel_sends_nothing_without_active_span() {
+        use crate::test_support::{message_json, mock_server};
+
+        let (port, captured) =
+            mock_server(|_, _| (200, message_json(Uuid::now_v7(), "plain", "Ready"))).await;
+        let client = TlqClient::new("127.0.0.1", port).unwrap();
+        client.add_message("plain").await.unwrap();
+
+        let requests = captured.lock().unwrap();
+        assert_eq!(requests[0].header("traceparent"), None);
+        assert_eq!(requests[0].header("tracestate"), None);
+    }
+
+    #[tokio::test]
+    async fn test_add_messages_fallback_uses_one_connection() {
+        use crate::test_support::{keep_alive_server, message_json};
+
+        let (port, captured, connections) = keep_alive_server(|path, body| match path {
+            "/add_batch" => (404, "Not Found".to_string()),
+            _ => {
+                let request: serde_json::Value = serde_json::from_str(body).unwrap();
+                let body = request["body"].as_str().unwrap();
+                (200, message_json(Uuid::now_v7(), body, "Ready"))
+            }
+        })
+        .await;
+        let client = TlqClient::new("127.0.0.1", port).unwrap();
+
+        let bodies: Vec<_> = (0..20).map(|i| format!("message {}", i)).collect();
+        let messages = client.add_messages(bodies.clone()).await.unwrap();
+        let received: Vec<_> = messages.iter().map(|m| m.body.clone()).collect();
+        assert_eq!(received, bodies);
+
+        // One connection for the batch attempt, one for all the adds
+        assert_eq!(connections.load(Ordering::SeqCst), 2);
+        let adds = captured
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|r| r.path == "/add")
+            .count();
+        assert_eq!(adds, 20);
+    }
+
+    #[test]
+    fn test_split_by_request_size_keeps_oversized_body_alone() {
+        let client = TlqClient::with_config(ConfigBuilder::new().max_request_bytes(30).build());
+        let bodies = vec![
+            "a".to_string(),
+            "b".to_string(),
+            "c".repeat(40),
+            "d".to_string(),
+        ];
+
+        let chunks = client.split_by_request_size(&bodies).unwrap();
+        let lengths: Vec<_> = chunks.iter().map(|c| c.len()).collect();
+        assert_eq!(lengths, vec![2, 1, 1]);
+    }
+
+    #[tokio::test]
+    async fn test_raw_body_sends_payload_unwrapped() {
+        use crate::test_support::{message_json, mock_server};
+
+        let (port, captured) =
+            mock_server(|_, body| (200, message_json(Uuid::now_v7(), body, "Ready"))).await;
+        let client = TlqClient::with_config(
+            ConfigBuilder::new()
+                .host("127.0.0.1")
+                .port(port)
+                .raw_body(true)
+                .build(),
+        );
+
+        let message = client.add_message("say \"hi\"").await.unwrap();
+        assert_eq!(message.body, "say \"hi\"");
+
+        let requests = captured.lock().unwrap();
+        assert_eq!(requests[0].body, "say \"hi\"");
+        assert_eq!(
+            requests[0].header("Content-Type"),
+            Some("text/plain; charset=utf-8")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_stats_count_requests_and_retries() {
+        use crate::test_support::{mock_server, DROP_CONNECTION};
+        use std::sync::atomic::{AtomicU32, Ordering};
+
+        let calls = Arc::new(AtomicU32::new(0));
+        let counter = calls.clone();
+        let (port, _) = mock_server(move |_, _| {
+            if counter.fetch_add(1, Ordering::SeqCst) == 0 {
+                (DROP_CONNECTION, String::new())
+            } else {
+                (200, "\"Success\"".to_string())
+            }
+        })
+        .await;
+        let client = TlqClient::with_config(
+            ConfigBuilder::new()
+                .host("127.0.0.1")
+                .port(port)
+                .retry_delay(Duration::from_millis(1))
+                .build(),
+        );
+
+        client
+            .with_queue("other")
+            .delete_message(Uuid::now_v7())
+            .await
+            .unwrap();
+
+        let stats = client.stats();
+        assert_eq!(stats.requests, 2);
+        assert_eq!(stats.successes, 1);
+        assert_eq!(stats.failures, 1);
+        assert_eq!(stats.retries, 1);
+        assert_eq!(stats.timeouts, 0);
+        assert!(stats.bytes_sent > 0);
+        assert!(stats.bytes_received > 0);
+
+        client.reset_stats();
+        assert_eq!(client.stats(), ClientStats::default());
+    }
+
+    #[tokio::test]
+    async fn test_startup_jitter_applies_once() {
+        use crate::test_support::mock_server;
+
+        let (port, captured) = mock_server(|_, _| (200, "\"Success\"".to_string())).await;
+        let client = TlqClient::with_config(
+            ConfigBuilder::new()
+                .host("127.0.0.1")
+                .port(port)
+                .startup_jitter(Duration::from_millis(20))
+                .build(),
+        );
+        assert!(!client.startup_jitter.initialized());
+
+        client.delete_message(Uuid::now_v7()).await.unwrap();
+        assert!(client.startup_jitter.initialized());
+        assert!(client.with_queue("other").startup_jitter.initialized());
+
+        client.delete_message(Uuid::now_v7()).await.unwrap();
+        assert_eq!(captured.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_parse_http_date() {
+        assert_eq!(
+            parse_http_date("Sun, 06 Nov 1994 08:49:37 GMT"),
+            Some(UNIX_EPOCH + Duration::from_secs(784_111_777))
+        );
+        assert_eq!(
+            parse_http_date("Thu, 29 Feb 2024 00:00:00 GMT"),
+            Some(UNIX_EPOCH + Duration::from_secs(1_709_164_800))
+        );
+        assert_eq!(parse_http_date("Sunday, 06-Nov-94 08:49:37 GMT"), None);
+        assert_eq!(parse_http_date("Sun, 06 Nov 1994 08:49:37 PST"), None);
+        assert_eq!(parse_http_date("Sun, 06 Foo 1994 08:49:37 GMT"), None);
+        assert_eq!(parse_http_date("garbage"), None);
+    }
+
+    #[tokio::test]
+    async fn test_read_response_uses_content_length() {
+        // Headers and the start of the body arrive in the same read.
+        let mut stream = tokio_test::io::Builder::new()
+            .read(b"HTTP/1.1 200 OK\r\nContent-Length: 13\r\n\r\n\"Hel")
+            .read(b"lo Wo")
+            .read(b"rld\"")
+            .build();
+        let response = read_response(&mut stream).await.unwrap();
+        assert!(response.ends_with(b"\r\n\r\n\"Hello World\""));
+
+        let mut stream = tokio_test::io::Builder::new()
+            .read(b"HTTP/1.1 200 OK\r\nContent-Length: 13\r\n\r\n\"Hel")
+            .build();
+        match read_response(&mut stream).await {
+            Err(TlqError::Connection(msg)) => assert!(msg.contains("after 4 of 13 body bytes")),
+            other => panic!("Expected Connection error, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_response_read_without_waiting_for_close() {
+        use tokio::io::AsyncWriteExt;
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut buf = [0u8; 1024];
+            let _ = stream.read(&mut buf).await;
+            let _ = stream
+                .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 9\r\n\r\n\"Success\"")
+                .await;
+            // Hold the connection open, as a keep-alive server would
+            tokio::time::sleep(Duration::from_secs(10)).await;
+        });
+
+        let client = TlqClient::with_config(
+            ConfigBuilder::new()
+                .host("127.0.0.1")
+                .port(port)
+                .timeout_ms(1000)
+                .max_retries(0)
+                .build(),
+        );
+        assert_eq!(client.purge_queue().await.unwrap(), "Success");
+    }
+
+    #[tokio::test]
+    async fn test_server_time_from_date_header() {
+        use tokio::io::AsyncWriteExt;
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let mut buf = [0u8; 1024];
+                let _ = stream.read(&mut buf).await;
+                let _ = stream
+                    .write_all(
+                        b"HTTP/1.1 200 OK\r\nDate: Sun, 06 Nov 1994 08:49:37 GMT\r\n\
+                          Content-Length: 9\r\nConnection: close\r\n\r\n\"Success\"",
+                    )
+                    .await;
+            }
+        });
+        let client = TlqClient::new("127.0.0.1", port).unwrap();
+        let server_time = UNIX_EPOCH + Duration::from_secs(784_111_777);
+
+        assert!(client.estimated_server_time().is_none());
+        assert_eq!(client.server_time().await.unwrap(), server_time);
+
+        // The server's clock is decades behind, so the estimate follows it
+        let estimate = client.estimated_server_time().unwrap();
+        let drift = estimate.duration_since(server_time).unwrap();
+        assert!(drift < Duration::from_secs(5));
+
+        client.delete_message(Uuid::now_v7()).await.unwrap();
+        assert!(client.estimated_server_time().unwrap() < SystemTime::now());
+    }
+
+    #[tokio::test]
+    async fn test_server_time_without_date_header() {
+        use crate::test_support::mock_server;
+
+        let (port, _) = mock_server(|_, _| (200, "Hello World".to_string())).await;
+        let client = TlqClient::new("127.0.0.1", port).unwrap();
+
+        assert!(matches!(
+            client.server_time().await,
+            Err(TlqError::Unsupported(_))
+        ));
+        assert!(client.estimated_server_time().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_invalid_utf8_response() {
+        use tokio::io::AsyncWriteExt;
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let mut buf = [0u8; 1024];
+                let _ = stream.read(&mut buf).await;
+                let _ = stream
+                    .write_all(b"HTTP/1.1 200 OK\r\nConnection: close\r\n\r\n\"Succ\xFF\"")
+                    .await;
+            }
+        });
+        let config = || {
+            ConfigBuilder::new()
+                .host("127.0.0.1")
+                .port(port)
+                .max_retries(0)
+        };
+
+        let strict = TlqClient::with_config(config().build());
+        match strict.delete_message(Uuid::now_v7()).await {
+            Err(TlqError::Connection(message)) => {
+                assert_eq!(message, "invalid UTF-8 in response")
+            }
+            other => panic!("Expected Connection error, got {:?}", other),
+        }
+
+        let lossy = TlqClient::with_config(config().lossy_response_decode(true).build());
+        assert_eq!(
+            lossy.delete_message(Uuid::now_v7()).await.unwrap(),
+            "Succ\u{FFFD}"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_retry_where() {
+        use crate::test_support::{message_json, mock_server};
+
+        let ready = Uuid::now_v7();
+        let young = Uuid::now_v7();
+        let old = Uuid::now_v7();
+        let peeked = format!(
+            "[{},{},{}]",
+            message_json(ready, "a", "Ready"),
+            message_json(young, "b", "Failed"),
+            message_json(old, "c", "Failed").replace("\"retry_count\":0", "\"retry_count\":7"),
+        );
+        let (port, captured) = mock_server(move |path, _| match path {
+            "/peek" => (200, peeked.clone()),
+            _ => (200, "\"Success\"".to_string()),
+        })
+        .await;
+        let client = TlqClient::new("127.0.0.1", port).unwrap();
+
+        assert_eq!(
+            client.retry_where(10, |m| m.retry_count < 5).await.unwrap(),
+            1
+        );
+        assert_eq!(client.retry_where(10, |_| false).await.unwrap(), 0);
+
+        let requests = captured.lock().unwrap();
+        let paths: Vec<_> = requests.iter().map(|r| r.path.as_str()).collect();
+        assert_eq!(paths, ["/peek", "/retry", "/peek"]);
+        assert!(requests[1].body.contains(&young.to_string()));
+        assert!(!requests[1].body.contains(&old.to_string()));
+        assert!(!requests[1].body.contains(&ready.to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_get_messages_accepts_wrapped_response() {
+        use crate::test_support::{message_json, mock_server};
+
+        let id = Uuid::now_v7();
+        let wrapped = format!(
+            r#"{{"messages":[{}]}}"#,
+            message_json(id, "wrapped", "Processing")
+        );
+        let (port, _) = mock_server(move |_, _| (200, wrapped.clone())).await;
+        let client = TlqClient::new("127.0.0.1", port).unwrap();
+
+        let messages = client.get_messages(1).await.unwrap();
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].id, id);
+        assert_eq!(client.peek_messages(1).await.unwrap()[0].body, "wrapped");
+    }
+
+    #[tokio::test]
+    async fn test_retry_on_timeout() {
+        // Accepts connections (via the backlog) but never responds
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let client = |retry_on_timeout| {
+            TlqClient::with_config(
+                ConfigBuilder::new()
+                    .host("127.0.0.1")
+                    .port(port)
+                    .timeout_ms(20)
+                    .max_retries(2)
+                    .retry_delay_ms(1)
+                    .retry_on_timeout(retry_on_timeout)
+                    .build(),
+            )
+        };
+
+        let cautious = client(false);
+        assert!(matches!(
+            cautious.add_message("once").await,
+            Err(TlqError::Timeout(20))
+        ));
+        assert_eq!(cautious.stats().requests, 1);
+
+        // Reads keep retrying timeouts
+        cautious.reset_stats();
+        assert!(matches!(
+            cautious.get_messages(1).await,
+            Err(TlqError::MaxRetriesExceeded { source, .. })
+                if matches!(*source, TlqError::Timeout(20))
+        ));
+        assert_eq!(cautious.stats().requests, 3);
+
+        let retrying = client(true);
+        assert!(matches!(
+            retrying.add_message("again").await,
+            Err(TlqError::MaxRetriesExceeded { source, .. })
+                if matches!(*source, TlqError::Timeout(20))
+        ));
+        assert_eq!(retrying.stats().requests, 3);
+        drop(listener);
+    }
+
+    #[tokio::test]
+    async fn test_in_flight_counts_pending_requests() {
+        // Accepts connections (via the backlog) but never responds
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let client = TlqClient::with_config(
+            ConfigBuilder::new()
+                .host("127.0.0.1")
+                .port(port)
+                .max_retries(0)
+                .build(),
+        );
+
+        let pending: Vec<_> = (0..2)
+            .map(|_| {
+                let client = client.with_queue("jobs");
+                tokio::spawn(async move { client.get_messages(1).await })
+            })
+            .collect();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(client.in_flight(), 2);
+
+        for request in pending {
+            request.abort();
+            let _ = request.await;
+        }
+        assert_eq!(client.in_flight(), 0);
+        drop(listener);
+    }
+
+    // Serves deletes and retries, rejecting any request containing `bad`.
+    async fn chunk_server(bad: Uuid) -> (TlqClient, crate::test_support::Captured) {
+        use crate::test_support::mock_server;
+
+        let (port, captured) = mock_server(move |_, body| {
+            if body.contains(&bad.to_string()) {
+                (400, "Invalid message ID".to_string())
+            } else {
+                (200, "\"Success\"".to_string())
+            }
+        })
+        .await;
+        let client = TlqClient::with_config(
+            ConfigBuilder::new()
+                .host("127.0.0.1")
+                .port(port)
+                .max_retries(0)
+                .build(),
+        );
+        (client, captured)
+    }
+
+    #[tokio::test]
+    async fn test_delete_messages_with_fail_fast() {
+        let ids: Vec<_> = (0..5).map(|_| Uuid::now_v7()).collect();
+        let (client, captured) = chunk_server(ids[2]).await;
+        let options = BatchOptions {
+            chunk_size: Some(2),
+            fail_fast: true,
+        };
+
+        match client.delete_messages_with(&ids, &options).await {
+            Err(TlqError::Server { status: 400, .. }) => {}
+            other => panic!("Expected Server error, got {:?}", other),
+        }
+        assert_eq!(captured.lock().unwrap().len(), 2);
+
+        let outcome = client
+            .delete_messages_with(&ids[..2], &BatchOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(outcome.processed, 2);
+        assert!(outcome.is_complete());
+        assert_eq!(captured.lock().unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_retry_messages_with_collects_failures() {
+        let ids: Vec<_> = (0..5).map(|_| Uuid::now_v7()).collect();
+        let (client, captured) = chunk_server(ids[2]).await;
+        let options = BatchOptions {
+            chunk_size: Some(2),
+            fail_fast: false,
+        };
+
+        let outcome = client.retry_messages_with(&ids, &options).await.unwrap();
+        assert_eq!(outcome.processed, 3);
+        assert_eq!(outcome.failures.len(), 1);
+        assert_eq!(outcome.failures[0].ids, &ids[2..4]);
+        assert!(matches!(
+            outcome.failures[0].error,
+            TlqError::Server { status: 400, .. }
+        ));
+
+        let requests = captured.lock().unwrap();
+        assert_eq!(requests.len(), 3);
+        assert!(requests.iter().all(|r| r.path == "/retry"));
+    }
+
+    #[tokio::test]
+    async fn test_chunked_operations_validate_input() {
+        let client = TlqClient::new("localhost", 1337).unwrap();
+        let options = BatchOptions {
+            chunk_size: Some(0),
+            ..BatchOptions::default()
+        };
+
+        assert!(matches!(
+            client
+                .delete_messages_with(&[Uuid::now_v7()], &options)
+                .await,
+            Err(TlqError::Validation(_))
+        ));
+        assert!(matches!(
+            client
+                .retry_messages_with(&[], &BatchOptions::default())
+                .await,
+            Err(TlqError::Validation(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_on_retry_observes_backoff_delays() {
+        use std::sync::Mutex;
+
+        // Nothing listens on the port, so every attempt fails to connect
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let delays = Arc::new(Mutex::new(Vec::new()));
+        let sink = delays.clone();
+        let client = TlqClient::with_config(
+            ConfigBuilder::new()
+                .host("127.0.0.1")
+                .port(port)
+                .max_retries(3)
+                .retry_delay_ms(5)
+                .backoff(crate::BackoffKind::Linear)
+                .on_retry(move |_, delay| sink.lock().unwrap().push(delay))
+                .build(),
+        );
+
+        assert!(client.get_messages(1).await.is_err());
+        assert_eq!(
+            *delays.lock().unwrap(),
+            [5, 10, 15].map(Duration::from_millis)
+        );
+    }
+
+    #[test]
+    fn test_ndjson_detection_and_conversion() {
+        let response =
+            "HTTP/1.1 200 OK\r\nContent-Type: application/x-ndjson; charset=utf-8\r\n\r\n";
+        assert!(is_ndjson(response));
+        assert!(!is_ndjson(
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\n\r\n"
+        ));
+        assert_eq!(
+            ndjson_to_array("{\"a\":1}\r\n\n{\"a\":2}\n"),
+            "[{\"a\":1},{\"a\":2}]"
+        );
+        assert_eq!(ndjson_to_array(""), "[]");
+    }
+
+    #[tokio::test]
+    async fn test_get_messages_accepts_ndjson() {
+        use crate::test_support::{message_json, mock_server};
+
+        let ids = [Uuid::now_v7(), Uuid::now_v7()];
+        let lines = format!(
+            "{}\n{}\n",
+            message_json(ids[0], "first", "Processing"),
+            message_json(ids[1], "second", "Processing")
+        );
+        let array = format!("[{}]", lines.trim_end().replace('\n', ","));
+        let (port, _) = mock_server(move |path, _| match path {
+            "/get" => (200, lines.clone()),
+            _ => (200, array.clone()),
+        })
+        .await;
+        let client = TlqClient::new("127.0.0.1", port).unwrap();
+
+        let from_ndjson = client.get_messages(2).await.unwrap();
+        let from_array = client.peek_messages(2).await.unwrap();
+        assert_eq!(from_ndjson, from_array);
+        assert_eq!(from_ndjson.iter().map(|m| m.id).collect::<Vec<_>>(), ids);
+    }
+
+    #[tokio::test]
+    async fn test_ndjson_content_type() {
+        use crate::test_support::message_json;
+        use tokio::io::AsyncWriteExt;
+
+        let id = Uuid::now_v7();
+        let body = format!("{}\n", message_json(id, "only", "Processing"));
+        let response = format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: application/x-ndjson\r\nConnection: close\r\n\r\n{}",
+            body
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let mut buf = [0u8; 1024];
+                let _ = stream.read(&mut buf).await;
+                let _ = stream.write_all(response.as_bytes()).await;
+            }
+        });
+        let client = TlqClient::new("127.0.0.1", port).unwrap();
+
+        let messages = client.get_messages(1).await.unwrap();
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].id, id);
+    }
+
+    #[tokio::test]
+    async fn test_detailed_variants_return_response_meta() {
+        use crate::test_support::message_json;
+        use tokio::io::AsyncWriteExt;
+
+        let id = Uuid::now_v7();
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let mut buf = [0u8; 1024];
+                let n = stream.read(&mut buf).await.unwrap_or(0);
+                let request = String::from_utf8_lossy(&buf[..n]);
+                let body = if request.starts_with("POST /add") {
+                    message_json(id, "hi", "Ready")
+                } else if request.starts_with("POST /get") {
+                    format!("[{}]", message_json(id, "hi", "Processing"))
+                } else {
+                    "\"Success\"".to_string()
+                };
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nX-Request-Id: req-7\r\nServer-Timing: total;dur=3\r\n\
+                     Warning: 199 - \"slow disk\"\r\nConnection: close\r\n\r\n{}",
+                    body
+                );
+                let _ = stream.write_all(response.as_bytes()).await;
+            }
+        });
+        let client = TlqClient::new("127.0.0.1", port).unwrap();
+        let expected = ResponseMeta {
+            request_id: Some("req-7".to_string()),
+            server_timing: Some("total;dur=3".to_string()),
+            warnings: vec!["199 - \"slow disk\"".to_string()],
+        };
+
+        let added = client.add_message_detailed("hi").await.unwrap();
+        assert_eq!(added.value.id, id);
+        assert_eq!(added.meta, expected);
+
+        let fetched = client.get_messages_detailed(1).await.unwrap();
+        assert_eq!(fetched.value.len(), 1);
+        assert_eq!(fetched.meta, expected);
+
+        let deleted = client.delete_messages_detailed(&[id]).await.unwrap();
+        assert_eq!(deleted.into_value(), "Success");
+        let retried = client.retry_messages_detailed(&[id]).await.unwrap();
+        assert_eq!(retried.meta.request_id.as_deref(), Some("req-7"));
+        let purged = client.purge_queue_detailed().await.unwrap();
+        assert_eq!(purged.meta, expected);
+    }
+
+    #[tokio::test]
+    async fn test_purge_confirmation_guard() {
+        use crate::test_support::mock_server;
+
+        let (port, captured) = mock_server(|_, _| (200, "\"Success\"".to_string())).await;
+        let client = TlqClient::with_config(
+            ConfigBuilder::new()
+                .host("127.0.0.1")
+                .port(port)
+                .require_purge_confirmation(true)
+                .build(),
+        );
+
+        match client.purge_queue().await {
+            Err(TlqError::Validation(message)) => {
+                assert_eq!(message, "purge requires confirmation")
+            }
+            other => panic!("Expected Validation error, got {:?}", other),
+        }
+        assert!(matches!(
+            client.purge_queue_confirmed("prod.example.com").await,
+            Err(TlqError::Validation(_))
+        ));
+        assert!(matches!(
+            client
+                .with_queue("orders")
+                .purge_queue_confirmed("127.0.0.1")
+                .await,
+            Err(TlqError::Validation(_))
+        ));
+        assert!(captured.lock().unwrap().is_empty());
+
+        assert_eq!(
+            client.purge_queue_confirmed("127.0.0.1").await.unwrap(),
+            "Success"
+        );
+        client
+            .with_queue("orders")
+            .purge_queue_confirmed("127.0.0.1/orders")
+            .await
+            .unwrap();
+
+        let requests = captured.lock().unwrap();
+        let paths: Vec<_> = requests.iter().map(|r| r.path.as_str()).collect();
+        assert_eq!(paths, ["/purge", "/purge?queue=orders"]);
+    }
+
+    #[tokio::test]
+    async fn test_peek_next() {
+        use crate::test_support::{message_json, mock_server};
+
+        let id = Uuid::now_v7();
+        let (port, captured) = mock_server(move |_, body| {
+            if body.contains("\"Failed\"") {
+                (200, message_json(id, "stuck", "Failed"))
+            } else {
+                (200, "null".to_string())
+            }
+        })
+        .await;
+        let client = TlqClient::new("127.0.0.1", port).unwrap();
+
+        let next = client.peek_next(MessageState::Failed).await.unwrap();
+        assert_eq!(next.unwrap().id, id);
+        assert!(client
+            .peek_next(MessageState::Ready)
+            .await
+            .unwrap()
+            .is_none());
+
+        let requests = captured.lock().unwrap();
+        assert_eq!(requests[0].path, "/peek_next");
+        assert_eq!(requests[0].body, r#"{"state":"Failed"}"#);
+    }
+
+    #[tokio::test]
+    async fn test_peek_next_falls_back_to_peek() {
+        use crate::test_support::{message_json, mock_server};
+
+        let ready = Uuid::now_v7();
+        let failed = Uuid::now_v7();
+        let peeked = format!(
+            "[{},{}]",
+            message_json(ready, "a", "Ready"),
+            message_json(failed, "b", "Failed")
+        );
+        let (port, captured) = mock_server(move |path, _| match path {
+            "/peek" => (200, peeked.clone()),
+            _ => (404, "Not Found".to_string()),
+        })
+        .await;
+        let client = TlqClient::with_config(
+            ConfigBuilder::new()
+                .host("127.0.0.1")
+                .port(port)
+                .max_retries(0)
+                .build(),
+        );
+
+        let next = client.peek_next(MessageState::Failed).await.unwrap();
+        assert_eq!(next.unwrap().id, failed);
+        assert!(client
+            .peek_next(MessageState::Processing)
+            .await
+            .unwrap()
+            .is_none());
+
+        let requests = captured.lock().unwrap();
+        assert!(requests[1].body.contains("100"));
+    }
+
+    #[tokio::test]
+    async fn test_local_port_range_skips_ports_in_use() {
+        use crate::test_support::mock_server;
+
+        let (port, captured) = mock_server(|_, _| (200, "\"Success\"".to_string())).await;
+
+        // Occupy the first port of the range so the client has to move on.
+        let occupied = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let first = occupied.local_addr().unwrap().port();
+        let range = first..=first.saturating_add(20);
+
+        let client = TlqClient::with_config(
+            ConfigBuilder::new()
+                .host("127.0.0.1")
+                .port(port)
+                .bind_address("127.0.0.1".parse().unwrap())
+                .local_port_range(range.clone())
+                .build(),
+        );
+        assert_eq!(client.purge_queue().await.unwrap(), "Success");
+
+        let peer = captured.lock().unwrap()[0].peer;
+        assert_eq!(peer.ip().to_string(), "127.0.0.1");
+        assert!(range.contains(&peer.port()));
+        assert_ne!(peer.port(), first);
+    }
+
+    #[tokio::test]
+    async fn test_bind_failure_is_connection_error() {
+        use crate::test_support::mock_server;
+
+        let (port, captured) = mock_server(|_, _| (200, "\"Success\"".to_string())).await;
+
+        // 192.0.2.1 is reserved for documentation and not assigned locally.
+        let client = TlqClient::with_config(
+            ConfigBuilder::new()
+                .host("127.0.0.1")
+                .port(port)
+                .max_retries(0)
+                .bind_address("192.0.2.1".parse().unwrap())
+                .build(),
+        );
+        match client.purge_queue().await {
+            Err(TlqError::Connection(msg)) => {
+                assert!(msg.contains("Failed to bind to local address 192.0.2.1:0"))
+            }
+            other => panic!("Expected Connection error, got {:?}", other),
+        }
+
+        // Every port in the range is taken.
+        let occupied = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let taken = occupied.local_addr().unwrap().port();
+        let client = TlqClient::with_config(
+            ConfigBuilder::new()
+                .host("127.0.0.1")
+                .port(port)
+                .max_retries(0)
+                .local_port_range(taken..=taken)
+                .build(),
+        );
+        match client.purge_queue().await {
+            Err(TlqError::Connection(msg)) => assert!(msg.contains("No usable local port")),
+            other => panic!("Expected Connection error, got {:?}", other),
+        }
+        assert!(captured.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_with_timeout_and_with_retries() {
+        use crate::test_support::{mock_server, DROP_CONNECTION};
+
+        let (port, captured) = mock_server(|_, _| (DROP_CONNECTION, String::new())).await;
+        let client = TlqClient::with_config(
+            ConfigBuilder::new()
+                .host("127.0.0.1")
+                .port(port)
+                .max_retries(2)
+                .retry_delay_ms(1)
+                .build(),
+        );
+
+        let no_retries = client.with_retries(0);
+        assert!(no_retries.purge_queue().await.is_err());
+        assert_eq!(captured.lock().unwrap().len(), 1);
+        assert!(client.purge_queue().await.is_err());
+        assert_eq!(captured.lock().unwrap().len(), 4);
+        assert_eq!(client.stats().requests, 4);
+
+        // Accepts connections (via the backlog) but never responds
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let client = TlqClient::with_config(
+            ConfigBuilder::new()
+                .host("127.0.0.1")
+                .port(port)
+                .max_retries(0)
+                .build(),
+        );
+        assert!(matches!(
+            client
+                .with_timeout(Duration::from_millis(50))
+                .get_messages(1)
+                .await,
+            Err(TlqError::Timeout(50))
+        ));
+        assert_eq!(client.config.timeout, Duration::from_secs(30));
+    }
+
+    #[tokio::test]
+    async fn test_health_check_detailed() {
+        use crate::test_support::mock_server;
+
+        let (port, captured) = mock_server(|_, _| (200, "\"Hello World\"".to_string())).await;
+        let client = TlqClient::new("127.0.0.1", port).unwrap();
+        let info = client.health_check_detailed().await.unwrap();
+        assert_eq!(info.message, "Hello World");
+        assert_eq!(info.version, None);
+        assert_eq!(captured.lock().unwrap()[0].path, "/hello");
+
+        let (port, _) = mock_server(|_, _| {
+            (
+                200,
+                r#"{"message":"Hello World","version":"0.3.0","uptime":3600}"#.to_string(),
+            )
+        })
+        .await;
+        let client = TlqClient::new("127.0.0.1", port).unwrap();
+        let info = client.health_check_detailed().await.unwrap();
+        assert_eq!(info.message, "Hello World");
+        assert_eq!(info.version.as_deref(), Some("0.3.0"));
+        assert_eq!(info.uptime, Some(3600));
+
+        let (port, _) = mock_server(|_, _| (503, "starting".to_string())).await;
+        let client = TlqClient::new("127.0.0.1", port).unwrap();
+        assert!(matches!(
+            client.health_check_detailed().await,
+            Err(TlqError::Server { status: 503, .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_fail_message() {
+        use crate::test_support::{message_json, mock_server};
+
+        let id = Uuid::now_v7();
+        let (port, captured) = mock_server(move |_, body| {
+            let request: serde_json::Value = serde_json::from_str(body).unwrap();
+            let mut message: serde_json::Value =
+                serde_json::from_str(&message_json(id, "job", "Failed")).unwrap();
+            message["failure_reason"] = request["reason"].clone();
+            (200, message.to_string())
+        })
+        .await;
+        let client = TlqClient::new("127.0.0.1", port).unwrap();
+
+        let failed = client.fail_message(id, "bad input").await.unwrap().unwrap();
+        assert_eq!(failed.state, MessageState::Failed);
+        assert_eq!(failed.failure_reason.as_deref(), Some("bad input"));
+
+        let requests = captured.lock().unwrap();
+        assert_eq!(requests[0].path, "/fail");
+        assert!(requests[0].body.contains("\"reason\":\"bad input\""));
+    }
+
+    #[tokio::test]
+    async fn test_fail_message_falls_back_to_retry() {
+        use crate::test_support::mock_server;
+
+        let (port, captured) = mock_server(|path, _| match path {
+            "/fail" => (404, "Not Found".to_string()),
+            _ => (200, "\"Success\"".to_string()),
+        })
+        .await;
+        let client = TlqClient::with_config(
+            ConfigBuilder::new()
+                .host("127.0.0.1")
+                .port(port)
+                .max_retries(0)
+                .build(),
+        );
+
+        assert_eq!(
+            client
+                .fail_message(Uuid::now_v7(), "bad input")
+                .await
+                .unwrap(),
+            None
+        );
+        let paths: Vec<_> = captured
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|r| r.path.clone())
+            .collect();
+        assert_eq!(paths, ["/fail", "/retry"]);
+    }
+
+    #[tokio::test]
+    async fn test_handle_failure_at_attempt_boundary() {
+        use crate::test_support::mock_server;
+
+        let (port, captured) = mock_server(|_, _| (200, "\"Success\"".to_string())).await;
+        let client = TlqClient::new("127.0.0.1", port).unwrap();
+        let mut message = Message::new("job".to_string());
+
+        message.retry_count = 1;
+        assert_eq!(
+            client.handle_failure(&message, 3).await.unwrap(),
+            FailureDisposition::Retried
+        );
+        message.retry_count = 2;
+        assert_eq!(
+            client.handle_failure(&message, 3).await.unwrap(),
+            FailureDisposition::Deleted
+        );
+
+        let paths: Vec<_> = captured
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|r| r.path.clone())
+            .collect();
+        assert_eq!(paths, ["/retry", "/delete"]);
+    }
+
+    #[tokio::test]
+    async fn test_get_messages_range_waits_for_min() {
+        use crate::test_support::{message_json, mock_server};
+
+        // Delivers one message per fetch.
+        let (port, captured) = mock_server(|_, _| {
+            let message = message_json(Uuid::now_v7(), "job", "Processing");
+            (200, format!("[{}]", message))
+        })
+        .await;
+        let client = TlqClient::new("127.0.0.1", port).unwrap();
+
+        let messages = client
+            .get_messages_range(3, 5, Duration::from_secs(5))
+            .await
+            .unwrap();
+        assert_eq!(messages.len(), 3);
+
+        let counts: Vec<_> = captured
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|r| serde_json::from_str::<serde_json::Value>(&r.body).unwrap()["count"].clone())
+            .collect();
+        assert_eq!(counts, [5, 4, 3]);
+    }
+
+    #[tokio::test]
+    async fn test_get_messages_range_returns_partial_batch_after_wait() {
+        use crate::test_support::http_response;
+        use std::sync::atomic::AtomicU32;
+        use tokio::io::AsyncWriteExt;
+
+        // Holds each poll open for its `wait_ms`, then answers with no messages
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let polls = Arc::new(AtomicU32::new(0));
+        let counter = polls.clone();
+        tokio::spawn(async move {
+            loop {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                counter.fetch_add(1, Ordering::SeqCst);
+                tokio::spawn(async move {
+                    let mut buf = [0u8; 1024];
+                    let n = stream.read(&mut buf).await.unwrap_or(0);
+                    let request = String::from_utf8_lossy(&buf[..n]);
+                    let body = &request[request.find("\r\n\r\n").unwrap() + 4..];
+                    let wait_ms = serde_json::from_str::<serde_json::Value>(body).unwrap()
+                        ["wait_ms"]
+                        .as_u64()
+                        .unwrap();
+                    tokio::time::sleep(Duration::from_millis(wait_ms)).await;
+                    let _ = stream.write_all(http_response(200, "[]").as_bytes()).await;
+                });
+            }
+        });
+        let client = TlqClient::new("127.0.0.1", port).unwrap();
+
+        let start = tokio::time::Instant::now();
+        let messages = client
+            .get_messages_range(2, 10, Duration::from_millis(120))
+            .await
+            .unwrap();
+        assert!(messages.is_empty());
+        assert!(start.elapsed() >= Duration::from_millis(120));
+        // The server does the waiting; the client doesn't poll in between
+        assert!(polls.load(Ordering::SeqCst) <= 3);
+
+        for (min, max) in [(0, 10), (5, 4)] {
+            assert!(matches!(
+                client.get_messages_range(min, max, Duration::ZERO).await,
+                Err(TlqError::Validation(_))
+            ));
+        }
+    }
+
+    // Spawns a TLS server with a fresh self-signed certificate for
+    // "localhost" that answers one request. Returns the port, the certificate
+    // and a handle resolving to the raw request (empty if the handshake failed).
+    #[cfg(feature = "tls")]
+    async fn self_signed_tls_server() -> (
+        u16,
+        rustls::pki_types::CertificateDer<'static>,
+        JoinHandle<String>,
+    ) {
+        use tokio::io::AsyncWriteExt;
+        use tokio_rustls::rustls::pki_types::{PrivateKeyDer, PrivatePkcs8KeyDer};
+
+        let certified = rcgen::generate_simple_self_signed(vec!["localhost".to_string()]).unwrap();
+        let cert = certified.cert.der().clone();
+        let key = PrivateKeyDer::Pkcs8(PrivatePkcs8KeyDer::from(
+            certified.signing_key.serialize_der(),
+        ));
+        let provider = Arc::new(rustls::crypto::ring::default_provider());
+
+        let server_config = rustls::ServerConfig::builder_with_provider(provider)
+            .with_safe_default_protocol_versions()
+            .unwrap()
+            .with_no_client_auth()
+            .with_single_cert(vec![cert.clone()], key)
+            .unwrap();
+        let acceptor = tokio_rustls::TlsAcceptor::from(Arc::new(server_config));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            // A client that rejects the certificate aborts the handshake
+            let Ok(mut stream) = acceptor.accept(stream).await else {
+                return String::new();
+            };
+            // Requests are framed like responses, so the same reader works
+            let request = read_response(&mut stream).await.unwrap();
+            let response = crate::test_support::http_response(200, "\"Success\"");
+            stream.write_all(response.as_bytes()).await.unwrap();
+            stream.shutdown().await.unwrap();
+            String::from_utf8(request).unwrap()
+        });
+        (port, cert, server)
+    }
+
+    #[cfg(feature = "tls")]
+    #[tokio::test]
+    async fn test_tls_round_trip() {
+        let (port, cert, server) = self_signed_tls_server().await;
+
+        let mut roots = rustls::RootCertStore::empty();
+        roots.add(cert).unwrap();
+        let tls_config = rustls::ClientConfig::builder_with_provider(Arc::new(
+            rustls::crypto::ring::default_provider(),
+        ))
+        .with_safe_default_protocol_versions()
+        .unwrap()
+        .with_root_certificates(roots)
+        .with_no_client_auth();
+        let client = TlqClient::with_config(
+            ConfigBuilder::new()
+                .host("localhost")
+                .port(port)
+                .ip_version(IpPreference::V4Only)
+                .max_retries(0)
+                .tls_config(Arc::new(tls_config))
+                .build(),
+        );
+
+        assert_eq!(client.purge_queue().await.unwrap(), "Success");
+        assert!(server.await.unwrap().starts_with("POST /purge HTTP/1.1"));
+    }
+
+    #[cfg(feature = "tls")]
+    #[tokio::test]
+    async fn test_tls_insecure_skip_verify_accepts_self_signed() {
+        use crate::ConfigWarning;
+
+        let builder = |port| {
+            ConfigBuilder::new()
+                .host("localhost")
+                .port(port)
+                .ip_version(IpPreference::V4Only)
+                .max_retries(0)
+        };
+
+        // Verified against the default roots, the self-signed certificate fails
+        let (port, _, _) = self_signed_tls_server().await;
+        let client = TlqClient::with_config(builder(port).tls(true).build());
+        assert!(matches!(
+            client.purge_queue().await,
+            Err(TlqError::Connection(_))
+        ));
+
+        let (port, _, server) = self_signed_tls_server().await;
+        let (config, warnings) = builder(port)
+            .tls_insecure_skip_verify(true)
+            .try_build()
+            .unwrap();
+        assert_eq!(warnings, [ConfigWarning::TlsVerificationDisabled]);
+        let client = TlqClient::with_config(config);
+
+        assert_eq!(client.purge_queue().await.unwrap(), "Success");
+        assert!(server.await.unwrap().starts_with("POST /purge HTTP/1.1"));
+    }
+
+    #[cfg(feature = "tls")]
+    #[tokio::test]
+    async fn test_tls_handshake_failure_is_connection_error() {
+        use tokio::io::AsyncWriteExt;
+
+        // A plaintext server answering the TLS hello with HTTP
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let response = crate::test_support::http_response(400, "plaintext only");
+            let _ = stream.write_all(response.as_bytes()).await;
+            let _ = stream.shutdown().await;
+        });
+        let client = TlqClient::with_config(
+            ConfigBuilder::new()
+                .host("localhost")
+                .port(port)
+                .ip_version(IpPreference::V4Only)
+                .max_retries(0)
+                .tls(true)
+                .build(),
+        );
+
+        match client.purge_queue().await {
+            Err(TlqError::Connection(msg)) => assert!(msg.contains("TLS handshake failed")),
+            other => panic!("Expected Connection error, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_max_request_body_counts_json_envelope() {
+        use crate::test_support::{message_json, mock_server};
+
+        let (port, captured) = mock_server(|_, body| {
+            let request: serde_json::Value = serde_json::from_str(body).unwrap();
+            let body = request["body"].as_str().unwrap();
+            (200, message_json(Uuid::now_v7(), body, "Ready"))
+        })
+        .await;
+        let client = TlqClient::with_config(
+            ConfigBuilder::new()
+                .host("127.0.0.1")
+                .port(port)
+                .max_request_body(100)
+                .build(),
+        );
+
+        // 60 bytes is a valid message, but escaping doubles it in the envelope
+        let quotes = "\"".repeat(60);
+        match client.add_message(quotes).await {
+            Err(TlqError::RequestTooLarge { size }) => assert!(size > 120),
+            other => panic!("Expected RequestTooLarge error, got {:?}", other),
+        }
+        assert!(captured.lock().unwrap().is_empty());
+        assert_eq!(client.stats().requests, 0);
+
+        client.add_message("x".repeat(60)).await.unwrap();
+        assert_eq!(captured.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_protocol_version_header_and_mismatch() {
+        use crate::test_support::mock_server;
+
+        let (port, captured) = mock_server(|_, _| (200, "\"Success\"".to_string())).await;
+        let client = TlqClient::new("127.0.0.1", port).unwrap();
+        client.purge_queue().await.unwrap();
+        assert_eq!(
+            captured.lock().unwrap()[0].header("X-TLQ-Client-Protocol"),
+            Some(CLIENT_PROTOCOL_VERSION)
+        );
+
+        let (port, _) = mock_server(|_, _| (426, "upgrade".to_string())).await;
+        let client = TlqClient::with_config(
+            ConfigBuilder::new()
+                .host("127.0.0.1")
+                .port(port)
+                .max_retries(0)
+                .build(),
+        );
+        assert!(matches!(
+            client.purge_queue().await,
+            Err(TlqError::ProtocolMismatch { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_non_retryable_server_error_not_retried() {
+        use crate::test_support::mock_server;
+
+        let (port, captured) = mock_server(|_, _| (400, "bad request".to_string())).await;
+        let client = TlqClient::with_config(
+            ConfigBuilder::new()
+                .host("127.0.0.1")
+                .port(port)
+                .max_retries(3)
+                .retry_delay(Duration::from_secs(10))
+                .build(),
+        );
+
+        let start = std::time::Instant::now();
+        assert!(matches!(
+            client.get_messages(1).await,
+            Err(TlqError::Server { status: 400, .. })
+        ));
+        assert_eq!(captured.lock().unwrap().len(), 1);
+        assert_eq!(client.stats().retries, 0);
+        assert!(start.elapsed() < Duration::from_secs(1));
+    }
+
+    #[tokio::test]
+    async fn test_retry_waits_for_retry_after() {
+        use tokio::io::AsyncWriteExt;
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            let responses: [&[u8]; 2] = [
+                b"HTTP/1.1 429 Too Many Requests\r\nRetry-After: 1\r\nContent-Length: 0\r\n\r\n",
+                b"HTTP/1.1 200 OK\r\nContent-Length: 9\r\n\r\n\"Success\"",
+            ];
+            for response in responses {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let mut buf = [0u8; 1024];
+                let _ = stream.read(&mut buf).await;
+                let _ = stream.write_all(response).await;
+            }
+        });
+
+        let client = TlqClient::with_config(
+            ConfigBuilder::new()
+                .host("127.0.0.1")
+                .port(port)
+                .retry_delay_ms(1)
+                .build(),
+        );
+        let start = std::time::Instant::now();
+        assert_eq!(client.purge_queue().await.unwrap(), "Success");
+        assert!(start.elapsed() >= Duration::from_secs(1));
+        assert_eq!(client.stats().retries, 1);
+    }
+
+    #[tokio::test]
+    async fn test_long_retry_after_fails_fast() {
+        use tokio::io::AsyncWriteExt;
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            loop {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let mut buf = [0u8; 1024];
+                let _ = stream.read(&mut buf).await;
+                let _ = stream
+                    .write_all(
+                        b"HTTP/1.1 503 Service Unavailable\r\nRetry-After: 86400\r\nContent-Length: 0\r\n\r\n",
+                    )
+                    .await;
+            }
+        });
+
+        let client = TlqClient::with_config(
+            ConfigBuilder::new()
+                .host("127.0.0.1")
+                .port(port)
+                .retry_delay_ms(1)
+                .build(),
+        );
+        let start = std::time::Instant::now();
+        match client.purge_queue().await {
+            Err(TlqError::Throttled { retry_after, .. }) => {
+                assert_eq!(retry_after, Duration::from_secs(86400))
+            }
+            other => panic!("Expected Throttled error, got {:?}", other),
+        }
+        assert!(start.elapsed() < Duration::from_secs(5));
+        assert_eq!(client.stats().retries, 0);
+    }
+
+    #[tokio::test]
+    async fn test_get_messages_wait() {
+        use crate::test_support::{http_response, message_json, mock_server};
+        use tokio::io::AsyncWriteExt;
+
+        let (port, captured) = mock_server(|_, _| (200, "[]".to_string())).await;
+        let client = TlqClient::new("127.0.0.1", port).unwrap();
+        let messages = client
+            .get_messages_wait(10, Duration::from_millis(2500))
+            .await
+            .unwrap();
+        assert!(messages.is_empty());
+        assert_eq!(
+            captured.lock().unwrap()[0].body,
+            r#"{"count":10,"wait_ms":2500}"#
+        );
+        assert!(matches!(
+            client.get_messages_wait(0, Duration::from_secs(1)).await,
+            Err(TlqError::Validation(_))
+        ));
+
+        // The server holds the poll open longer than the request timeout
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let id = Uuid::now_v7();
+        tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut buf = [0u8; 1024];
+            let _ = stream.read(&mut buf).await;
+            tokio::time::sleep(Duration::from_millis(150)).await;
+            let body = format!("[{}]", message_json(id, "late", "Processing"));
+            let _ = stream.write_all(http_response(200, &body).as_bytes()).await;
+        });
+        let client = TlqClient::with_config(
+            ConfigBuilder::new()
+                .host("127.0.0.1")
+                .port(port)
+                .timeout_ms(100)
+                .max_retries(0)
+                .build(),
+        );
+        let messages = client
+            .get_messages_wait(1, Duration::from_millis(200))
+            .await
+            .unwrap();
+        assert_eq!(messages[0].id, id);
+    }
+
+    #[tokio::test]
+    async fn test_get_messages_wait_timeout_uses_network_slack() {
+        use tokio::io::AsyncWriteExt;
+
+        let client = TlqClient::with_config(
+            ConfigBuilder::new()
+                .timeout(Duration::from_secs(60))
+                .network_slack(Duration::from_millis(500))
+                .build(),
+        );
+        assert_eq!(
+            client.long_poll_timeout(Duration::from_secs(2)),
+            Duration::from_millis(2500)
+        );
+        assert_eq!(
+            TlqClient::new("localhost", 1337)
+                .unwrap()
+                .long_poll_timeout(Duration::from_secs(1)),
+            Duration::from_secs(3)
+        );
+
+        // A server that holds the poll past wait plus slack times it out
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut buf = [0u8; 1024];
+            let _ = stream.read(&mut buf).await;
+            tokio::time::sleep(Duration::from_secs(5)).await;
+            let _ = stream.shutdown().await;
+        });
+        let client = TlqClient::with_config(
+            ConfigBuilder::new()
+                .host("127.0.0.1")
+                .port(port)
+                .timeout(Duration::from_secs(60))
+                .network_slack(Duration::from_millis(50))
+                .max_retries(0)
+                .build(),
+        );
+        assert!(matches!(
+            client
+                .get_messages_wait(1, Duration::from_millis(100))
+                .await,
+            Err(TlqError::Timeout(150))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_consume_acknowledges_by_handler_result() {
+        use crate::test_support::mock_server;
+        use crate::CancellationToken;
+        use std::sync::atomic::{AtomicBool, Ordering};
+
+        let ids: Vec<Uuid> = (0..3).map(|_| Uuid::now_v7()).collect();
+        let batch = serde_json::json!([
+            {"id": ids[0], "body": "ok", "state": "Processing", "lock_until": null, "retry_count": 0},
+            {"id": ids[1], "body": "fail", "state": "Processing", "lock_until": null, "retry_count": 2},
+            {"id": ids[2], "body": "fail", "state": "Processing", "lock_until": null, "retry_count": 3}
+        ])
+        .to_string();
+        let served = Arc::new(AtomicBool::new(false));
+        let (port, captured) = mock_server(move |path, _| match path {
+            "/get" if !served.swap(true, Ordering::SeqCst) => (200, batch.clone()),
+            "/get" => (200, "[]".to_string()),
+            _ => (200, "\"Success\"".to_string()),
+        })
+        .await;
+        let client = TlqClient::new("127.0.0.1", port).unwrap();
+
+        let shutdown = CancellationToken::new();
+        let options = ConsumeOptions {
+            batch_size: 3,
+            shutdown: shutdown.clone(),
+            ..ConsumeOptions::default()
+        };
+        let mut handled = 0;
+        client
+            .consume(options, |message| {
+                handled += 1;
+                // Cancelling mid-batch still lets the batch finish
+                shutdown.cancel();
+                async move {
+                    match message.body.as_str() {
+                        "ok" => Ok(()),
+                        _ => Err("failed"),
+                    }
+                }
+            })
+            .await
+            .unwrap();
+        assert_eq!(handled, 3);
+
+        let requests = captured.lock().unwrap();
+        let paths: Vec<&str> = requests.iter().map(|r| r.path.as_str()).collect();
+        assert_eq!(paths, ["/get", "/delete", "/retry", "/delete"]);
+        assert!(requests[1].body.contains(&ids[0].to_string()));
+        assert!(requests[2].body.contains(&ids[1].to_string()));
+        assert!(requests[3].body.contains(&ids[2].to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_consume_dead_letter_queue_and_shutdown() {
+        use crate::test_support::mock_server;
+        use crate::CancellationToken;
+        use std::sync::atomic::{AtomicBool, Ordering};
+
+        let id = Uuid::now_v7();
+        let batch = serde_json::json!([
+            {"id": id, "body": "poison", "state": "Processing", "lock_until": null, "retry_count": 1}
+        ])
+        .to_string();
+        let served = Arc::new(AtomicBool::new(false));
+        let (port, captured) = mock_server(move |path, _| match path {
+            "/get" if !served.swap(true, Ordering::SeqCst) => (200, batch.clone()),
+            "/get" => (200, "[]".to_string()),
+            _ => (200, "1".to_string()),
+        })
+        .await;
+        let client = TlqClient::new("127.0.0.1", port).unwrap();
+
+        let shutdown = CancellationToken::new();
+        let options = ConsumeOptions {
+            max_message_retries: 1,
+            poll_interval: Duration::from_secs(10),
+            dead_letter_queue: Some("dead-letters".to_string()),
+            shutdown: shutdown.clone(),
+            ..ConsumeOptions::default()
+        };
+        let cancel = shutdown.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            cancel.cancel();
+        });
+
+        // Returns once cancelled, without waiting out the poll interval
+        let start = std::time::Instant::now();
+        client
+            .consume(options, |_| async { Err::<(), _>("poison") })
+            .await
+            .unwrap();
+        assert!(start.elapsed() < Duration::from_secs(5));
+
+        let requests = captured.lock().unwrap();
+        let paths: Vec<&str> = requests.iter().map(|r| r.path.as_str()).collect();
+        assert_eq!(paths, ["/get", "/move", "/get"]);
+        assert_eq!(
+            requests[1].body,
+            format!(r#"{{"ids":["{}"],"to_queue":"dead-letters"}}"#, id)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_consume_strict_ordering_blocks_on_failure() {
+        use crate::test_support::mock_server;
+        use crate::CancellationToken;
+        use std::sync::atomic::{AtomicBool, Ordering};
+
+        let ids: Vec<Uuid> = (0..2).map(|_| Uuid::now_v7()).collect();
+        let batch = serde_json::json!([
+            {"id": ids[0], "body": "first", "state": "Processing", "lock_until": null, "retry_count": 0},
+            {"id": ids[1], "body": "second", "state": "Processing", "lock_until": null, "retry_count": 0}
+        ])
+        .to_string();
+        let served = Arc::new(AtomicBool::new(false));
+        let (port, captured) = mock_server(move |path, _| match path {
+            "/get" if !served.swap(true, Ordering::SeqCst) => (200, batch.clone()),
+            "/get" => (200, "[]".to_string()),
+            _ => (200, "\"Success\"".to_string()),
+        })
+        .await;
+        let client = TlqClient::with_config(
+            ConfigBuilder::new()
+                .host("127.0.0.1")
+                .port(port)
+                .retry_delay(Duration::from_millis(1))
+                .build(),
+        );
+
+        let shutdown = CancellationToken::new();
+        let options = ConsumeOptions {
+            batch_size: 2,
+            strict_ordering: true,
+            shutdown: shutdown.clone(),
+            ..ConsumeOptions::default()
+        };
+        let mut handled = Vec::new();
+        client
+            .consume(options, |message| {
+                handled.push(message.body.clone());
+                // The first message fails twice before it goes through
+                let calls = handled.iter().filter(|body| *body == "first").count();
+                if message.body == "second" {
+                    shutdown.cancel();
+                }
+                async move {
+                    if message.body == "first" && calls <= 2 {
+                        Err("failed")
+                    } else {
+                        Ok(())
+                    }
+                }
+            })
+            .await
+            .unwrap();
+        assert_eq!(handled, ["first", "first", "first", "second"]);
+
+        let requests = captured.lock().unwrap();
+        let paths: Vec<&str> = requests.iter().map(|r| r.path.as_str()).collect();
+        assert_eq!(paths, ["/get", "/delete", "/delete"]);
+        assert!(requests[1].body.contains(&ids[0].to_string()));
+        assert!(requests[2].body.contains(&ids[1].to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_consume_batches_acks() {
+        use crate::test_support::{message_json, mock_server};
+        use crate::CancellationToken;
+        use std::sync::atomic::{AtomicBool, Ordering};
+
+        let ids: Vec<Uuid> = (0..5).map(|_| Uuid::now_v7()).collect();
+        let messages: Vec<String> = ids
+            .iter()
+            .map(|id| message_json(*id, "work", "Processing"))
+            .collect();
+        let batch = format!("[{}]", messages.join(","));
+        let served = Arc::new(AtomicBool::new(false));
+        let (port, captured) = mock_server(move |path, _| match path {
+            "/get" if !served.swap(true, Ordering::SeqCst) => (200, batch.clone()),
+            "/get" => (200, "[]".to_string()),
+            _ => (200, "\"Success\"".to_string()),
+        })
+        .await;
+        let client = TlqClient::new("127.0.0.1", port).unwrap();
+
+        let shutdown = CancellationToken::new();
+        let options = ConsumeOptions {
+            batch_size: 5,
+            ack_batch_size: 3,
+            ack_linger: Duration::from_secs(60),
+            shutdown: shutdown.clone(),
+            ..ConsumeOptions::default()
+        };
+        let mut handled = 0;
+        client
+            .consume(options, |_| {
+                handled += 1;
+                if handled == 5 {
+                    shutdown.cancel();
+                }
+                async { Ok::<_, String>(()) }
+            })
+            .await
+            .unwrap();
+
+        // One delete for a full batch of 3, and one for the 2 left at shutdown
+        let requests = captured.lock().unwrap();
+        let paths: Vec<&str> = requests.iter().map(|r| r.path.as_str()).collect();
+        assert_eq!(paths, ["/get", "/delete", "/delete"]);
+        for (i, id) in ids.iter().enumerate() {
+            let request = if i < 3 { &requests[1] } else { &requests[2] };
+            assert!(request.body.contains(&id.to_string()));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_consume_flushes_lingering_acks() {
+        use crate::test_support::{message_json, mock_server};
+        use crate::CancellationToken;
+        use std::sync::atomic::{AtomicBool, Ordering};
+
+        let messages: Vec<String> = (0..3)
+            .map(|_| message_json(Uuid::now_v7(), "work", "Processing"))
+            .collect();
+        let batch = format!("[{}]", messages.join(","));
+        let served = Arc::new(AtomicBool::new(false));
+        let (port, captured) = mock_server(move |path, _| match path {
+            "/get" if !served.swap(true, Ordering::SeqCst) => (200, batch.clone()),
+            "/get" => (200, "[]".to_string()),
+            _ => (200, "\"Success\"".to_string()),
+        })
+        .await;
+        let client = TlqClient::new("127.0.0.1", port).unwrap();
+
+        let shutdown = CancellationToken::new();
+        let options = ConsumeOptions {
+            batch_size: 3,
+            ack_batch_size: 10,
+            ack_linger: Duration::ZERO,
+            shutdown: shutdown.clone(),
+            ..ConsumeOptions::default()
+        };
+        client
+            .consume(options, |_| {
+                shutdown.cancel();
+                async { Ok::<_, String>(()) }
+            })
+            .await
+            .unwrap();
+
+        // Every acknowledgement has lingered long enough by the next check
+        let requests = captured.lock().unwrap();
+        let paths: Vec<&str> = requests.iter().map(|r| r.path.as_str()).collect();
+        assert_eq!(paths, ["/get", "/delete", "/delete", "/delete"]);
+    }
+
+    #[tokio::test]
+    async fn test_consume_max_in_flight_bounds_unacknowledged() {
+        use crate::test_support::{message_json, mock_server};
+        use crate::CancellationToken;
+
+        // Messages handed out and not yet deleted, and the most seen at once
+        #[derive(Default)]
+        struct Queue {
+            remaining: u32,
+            in_flight: u32,
+            max_in_flight: u32,
+            max_requested: u32,
+        }
+        let queue = Arc::new(Mutex::new(Queue {
+            remaining: 10,
+            ..Queue::default()
+        }));
+        let state = queue.clone();
+        let (port, _) = mock_server(move |path, body| {
+            let mut queue = state.lock().unwrap();
+            let request: serde_json::Value = serde_json::from_str(body).unwrap();
+            match path {
+                "/get" => {
+                    let count = request["count"].as_u64().unwrap() as u32;
+                    queue.max_requested = queue.max_requested.max(count);
+                    let served = count.min(queue.remaining);
+                    queue.remaining -= served;
+                    queue.in_flight += served;
+                    queue.max_in_flight = queue.max_in_flight.max(queue.in_flight);
+                    let messages: Vec<String> = (0..served)
+                        .map(|_| message_json(Uuid::now_v7(), "work", "Processing"))
+                        .collect();
+                    (200, format!("[{}]", messages.join(",")))
+                }
+                _ => {
+                    queue.in_flight -= request["ids"].as_array().unwrap().len() as u32;
+                    (200, "\"Success\"".to_string())
+                }
+            }
+        })
+        .await;
+        let client = TlqClient::new("127.0.0.1", port).unwrap();
+
+        let shutdown = CancellationToken::new();
+        let options = ConsumeOptions {
+            batch_size: 8,
+            ack_batch_size: 8,
+            ack_linger: Duration::from_secs(60),
+            max_in_flight: Some(3),
+            shutdown: shutdown.clone(),
+            ..ConsumeOptions::default()
+        };
+        let mut handled = 0;
+        client
+            .consume(options, |_| {
+                handled += 1;
+                if handled == 10 {
+                    shutdown.cancel();
+                }
+                // A slow handler leaves every fetched message in flight for a while
+                async {
+                    tokio::time::sleep(Duration::from_millis(10)).await;
+                    Ok::<_, String>(())
+                }
+            })
+            .await
+            .unwrap();
+        assert_eq!(handled, 10);
+
+        let queue = queue.lock().unwrap();
+        assert_eq!(queue.in_flight, 0);
+        assert_eq!(queue.max_in_flight, 3);
+        assert_eq!(queue.max_requested, 3);
+    }
+
+    #[tokio::test]
+    async fn test_queue_stats() {
+        use crate::test_support::mock_server;
+
+        let (port, captured) = mock_server(|_, _| {
+            (
+                200,
+                r#"{"ready":3,"processing":2,"failed":1,"total":6}"#.to_string(),
+            )
+        })
+        .await;
+        let client = TlqClient::new("127.0.0.1", port).unwrap();
+
+        let stats = client.queue_stats().await.unwrap();
+        assert_eq!(
+            stats,
+            QueueStats {
+                ready: 3,
+                processing: 2,
+                failed: 1
+            }
+        );
+        assert_eq!(stats.total(), 6);
+        assert_eq!(captured.lock().unwrap()[0].path, "/stats");
+
+        let (port, _) = mock_server(|_, _| (404, "Not Found".to_string())).await;
+        let client = TlqClient::with_config(
+            ConfigBuilder::new()
+                .host("127.0.0.1")
+                .port(port)
+                .max_retries(0)
+                .build(),
+        );
+        assert!(matches!(
+            client.queue_stats().await,
+            Err(TlqError::Unsupported(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_add_message_json() {
+        use crate::test_support::{message_json, mock_server};
+
+        let (port, captured) = mock_server(|_, body| {
+            let request: serde_json::Value = serde_json::from_str(body).unwrap();
+            let body = request["body"].as_str().unwrap();
+            (200, message_json(Uuid::now_v7(), body, "Ready"))
+        })
+        .await;
+        let client = TlqClient::new("127.0.0.1", port).unwrap();
+
+        let value = serde_json::json!({"order": 42, "items": ["a", "b"]});
+        let message = client.add_message_json(&value).await.unwrap();
+        assert_eq!(message.body, r#"{"items":["a","b"],"order":42}"#);
+        assert_eq!(
+            captured.lock().unwrap()[0].body,
+            r#"{"body":"{\"items\":[\"a\",\"b\"],\"order\":42}"}"#
+        );
 
-        let result = TlqClient::parse_http_response(response);
-        assert!(result.is_ok());
-        assert_eq!(result.unwrap(), "{\"message\":\"success\"}");
+        // The limit applies to the serialized body, quotes included
+        let value = "x".repeat(65535);
+        assert!(matches!(
+            client.add_message_json(&value).await,
+            Err(TlqError::MessageTooLarge { size: 65537, .. })
+        ));
     }
 
-    #[test]
-    fn test_parse_http_response_server_error() {
-        let response = "HTTP/1.1 500 Internal Server Error\r\nContent-Type: text/plain\r\n\r\nInternal server error occurred";
+    #[tokio::test]
+    async fn test_get_messages_json() {
+        use crate::test_support::{message_json, mock_server};
 
-        let result = TlqClient::parse_http_response(response);
-        match result {
-            Err(TlqError::Server { status, message }) => {
-                assert_eq!(status, 500);
-                assert_eq!(message, "Internal server error occurred");
-            }
-            _ => panic!("Expected server error"),
+        #[derive(Debug, PartialEq, serde::Deserialize)]
+        struct Order {
+            id: u64,
         }
-    }
 
-    #[test]
-    fn test_parse_http_response_client_error() {
-        let response = "HTTP/1.1 400 Bad Request\r\nContent-Type: text/plain\r\n\r\nBad request";
+        let ids: Vec<Uuid> = (0..3).map(|_| Uuid::now_v7()).collect();
+        let bodies = ids.clone();
+        let (port, captured) = mock_server(move |path, _| match path {
+            "/get" => {
+                let messages = [
+                    message_json(bodies[0], r#"{"id":1}"#, "Processing"),
+                    message_json(bodies[1], r#"{"id":2}"#, "Processing"),
+                ];
+                (200, format!("[{}]", messages.join(",")))
+            }
+            _ => (200, "\"Success\"".to_string()),
+        })
+        .await;
+        let client = TlqClient::new("127.0.0.1", port).unwrap();
 
-        let result = TlqClient::parse_http_response(response);
-        match result {
-            Err(TlqError::Server { status, message }) => {
-                assert_eq!(status, 400);
-                assert_eq!(message, "Bad request");
+        let orders = client.get_messages_json::<Order>(2).await.unwrap();
+        assert_eq!(orders.len(), 2);
+        assert_eq!(orders[0].0.id, ids[0]);
+        assert_eq!(orders[1].1, Order { id: 2 });
+        assert_eq!(captured.lock().unwrap().len(), 1);
+
+        // A malformed body fails the call and releases the rest of the batch
+        let bodies = ids.clone();
+        let (port, captured) = mock_server(move |path, _| match path {
+            "/get" => {
+                let messages = [
+                    message_json(bodies[0], r#"{"id":1}"#, "Processing"),
+                    message_json(bodies[1], "not json", "Processing"),
+                    message_json(bodies[2], r#"{"id":3}"#, "Processing"),
+                ];
+                (200, format!("[{}]", messages.join(",")))
             }
-            _ => panic!("Expected client error"),
+            _ => (200, "\"Success\"".to_string()),
+        })
+        .await;
+        let client = TlqClient::new("127.0.0.1", port).unwrap();
+
+        match client.get_messages_json::<Order>(3).await {
+            Err(TlqError::InvalidBody { message, .. }) => {
+                assert_eq!(message.id, ids[1]);
+                assert_eq!(message.body, "not json");
+            }
+            other => panic!("Expected InvalidBody error, got {:?}", other),
         }
+        let requests = captured.lock().unwrap();
+        assert_eq!(requests[1].path, "/retry");
+        assert_eq!(
+            requests[1].body,
+            format!(r#"{{"ids":["{}","{}"]}}"#, ids[0], ids[2])
+        );
     }
 
-    #[test]
-    fn test_parse_http_response_no_headers_separator() {
-        let response =
-            "HTTP/1.1 200 OK\nContent-Type: application/json\n{\"incomplete\":\"response\"}";
+    #[tokio::test]
+    async fn test_add_message_uses_configured_max_size() {
+        use crate::test_support::{message_json, mock_server};
 
-        let result = TlqClient::parse_http_response(response);
-        match result {
-            Err(TlqError::Connection(msg)) => {
-                assert_eq!(msg, "Invalid HTTP response");
+        let (port, captured) =
+            mock_server(|_, _| (200, message_json(Uuid::now_v7(), "body", "Ready"))).await;
+        let client = TlqClient::with_config(
+            ConfigBuilder::new()
+                .host("127.0.0.1")
+                .port(port)
+                .max_message_size(100)
+                .build(),
+        );
+
+        match client.add_message("x".repeat(101)).await {
+            Err(err @ TlqError::MessageTooLarge { .. }) => {
+                assert_eq!(err.to_string(), "Message too large: 101 bytes (max: 100)");
             }
-            _ => panic!("Expected connection error"),
+            other => panic!("Expected MessageTooLarge error, got {:?}", other),
+        }
+        match client
+            .add_messages(["ok".to_string(), "x".repeat(101)])
+            .await
+        {
+            Err(TlqError::MessageTooLarge { max, index, .. }) => {
+                assert_eq!(max, 100);
+                assert_eq!(index, Some(1));
+            }
+            other => panic!("Expected MessageTooLarge error, got {:?}", other),
         }
+        assert!(captured.lock().unwrap().is_empty());
+
+        // A raised limit lets bodies over the default through
+        let client = TlqClient::with_config(
+            ConfigBuilder::new()
+                .host("127.0.0.1")
+                .port(port)
+                .max_message_size(MAX_MESSAGE_SIZE * 2)
+                .build(),
+        );
+        assert!(client
+            .add_message("x".repeat(MAX_MESSAGE_SIZE + 1))
+            .await
+            .is_ok());
     }
 
-    #[test]
-    fn test_parse_http_response_malformed_status_line() {
-        let response = "INVALID_STATUS_LINE\r\n\r\n{\"data\":\"test\"}";
+    #[cfg(feature = "tracing")]
+    #[tokio::test]
+    async fn test_request_tracing() {
+        use crate::test_support::{message_json, mock_server, DROP_CONNECTION};
+        use std::sync::atomic::AtomicU32;
+        use tracing_subscriber::fmt::format::FmtSpan;
 
-        let result = TlqClient::parse_http_response(response);
-        // Should still succeed because we only check if parts.len() >= 2 and parse fails gracefully
-        assert!(result.is_ok());
-        assert_eq!(result.unwrap(), "{\"data\":\"test\"}");
-    }
+        #[derive(Clone, Default)]
+        struct Output(Arc<Mutex<Vec<u8>>>);
 
-    #[test]
-    fn test_parse_http_response_empty_body() {
-        let response = "HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n";
+        impl std::io::Write for Output {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                self.0.lock().unwrap().extend_from_slice(buf);
+                Ok(buf.len())
+            }
 
-        let result = TlqClient::parse_http_response(response);
-        assert!(result.is_ok());
-        assert_eq!(result.unwrap(), "");
-    }
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
 
-    #[test]
-    fn test_parse_http_response_with_extra_headers() {
-        let response = "HTTP/1.1 201 Created\r\nContent-Type: application/json\r\nServer: TLQ/1.0\r\nConnection: close\r\n\r\n{\"id\":\"123\",\"status\":\"created\"}";
+        let output = Output::default();
+        let writer = output.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_max_level(tracing::Level::DEBUG)
+            .with_span_events(FmtSpan::CLOSE)
+            .with_writer(move || writer.clone())
+            .finish();
+        let _guard = tracing::subscriber::set_default(subscriber);
 
-        let result = TlqClient::parse_http_response(response);
-        assert!(result.is_ok());
-        assert_eq!(result.unwrap(), "{\"id\":\"123\",\"status\":\"created\"}");
+        let calls = Arc::new(AtomicU32::new(0));
+        let counter = calls.clone();
+        let (port, _) = mock_server(move |_, _| {
+            if counter.fetch_add(1, Ordering::SeqCst) == 0 {
+                (DROP_CONNECTION, String::new())
+            } else {
+                (200, message_json(Uuid::now_v7(), "body", "Ready"))
+            }
+        })
+        .await;
+        let client = TlqClient::with_config(
+            ConfigBuilder::new()
+                .host("127.0.0.1")
+                .port(port)
+                .retry_delay(Duration::from_millis(1))
+                .build(),
+        );
+        client.add_message("hello").await.unwrap();
+
+        let output = String::from_utf8(output.0.lock().unwrap().clone()).unwrap();
+        assert!(output.contains("WARN"), "{output}");
+        assert!(output.contains("retrying request"), "{output}");
+        let close = output
+            .lines()
+            .find(|line| line.contains("close"))
+            .unwrap_or_else(|| panic!("no span close in {output}"));
+        assert!(close.contains("tlq_request{endpoint=/add"), "{close}");
+        assert!(close.contains("attempt=2"), "{close}");
+        assert!(close.contains("status=200"), "{close}");
+        assert!(close.contains("elapsed_ms="), "{close}");
     }
 
-    #[test]
-    fn test_parse_http_response_status_code_edge_cases() {
-        // Test various status codes around the 400 boundary
+    #[cfg(feature = "tracing")]
+    #[tokio::test]
+    async fn test_single_request_tracing_has_no_attempt() {
+        use crate::test_support::mock_server;
+        use tracing_subscriber::fmt::format::FmtSpan;
 
-        // 399 should be success (< 400)
-        let response_399 = "HTTP/1.1 399 Custom Success\r\n\r\n{\"ok\":true}";
-        let result = TlqClient::parse_http_response(response_399);
-        assert!(result.is_ok());
+        #[derive(Clone, Default)]
+        struct Output(Arc<Mutex<Vec<u8>>>);
 
-        // 400 should be error (>= 400)
-        let response_400 = "HTTP/1.1 400 Bad Request\r\n\r\nBad request";
-        let result = TlqClient::parse_http_response(response_400);
-        assert!(matches!(result, Err(TlqError::Server { status: 400, .. })));
+        impl std::io::Write for Output {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                self.0.lock().unwrap().extend_from_slice(buf);
+                Ok(buf.len())
+            }
 
-        // 599 should be error
-        let response_599 = "HTTP/1.1 599 Custom Error\r\n\r\nCustom error";
-        let result = TlqClient::parse_http_response(response_599);
-        assert!(matches!(result, Err(TlqError::Server { status: 599, .. })));
-    }
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
 
-    #[test]
-    fn test_max_message_size_constant() {
-        assert_eq!(MAX_MESSAGE_SIZE, 65536);
-    }
+        let output = Output::default();
+        let writer = output.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_max_level(tracing::Level::DEBUG)
+            .with_span_events(FmtSpan::CLOSE)
+            .with_writer(move || writer.clone())
+            .finish();
+        let _guard = tracing::subscriber::set_default(subscriber);
 
-    #[test]
-    fn test_client_creation() {
-        let client = TlqClient::new("test-host", 9999);
-        assert!(client.is_ok());
+        let (port, _) = mock_server(|_, _| (200, r#"{"max_message_size":10}"#.to_string())).await;
+        let client = TlqClient::with_config(
+            ConfigBuilder::new()
+                .host("127.0.0.1")
+                .port(port)
+                .auto_max_size(true)
+                .build(),
+        );
+        assert_eq!(client.max_message_size().await.unwrap(), 10);
 
-        let client = client.unwrap();
-        assert_eq!(client.base_url, "test-host:9999");
+        let output = String::from_utf8(output.0.lock().unwrap().clone()).unwrap();
+        let close = output
+            .lines()
+            .find(|line| line.contains("close"))
+            .unwrap_or_else(|| panic!("no span close in {output}"));
+        assert!(close.contains("tlq_request{endpoint=/info"), "{close}");
+        assert!(!close.contains("attempt="), "{close}");
+        assert!(close.contains("status=200"), "{close}");
+        assert!(close.contains("elapsed_ms="), "{close}");
     }
 
-    #[test]
-    fn test_client_with_config() {
-        let config = Config {
-            host: "custom-host".to_string(),
-            port: 8080,
-            timeout: Duration::from_secs(10),
-            max_retries: 5,
-            retry_delay: Duration::from_millis(200),
-        };
+    #[tokio::test]
+    async fn test_client_name_header() {
+        use crate::test_support::mock_server;
 
-        let client = TlqClient::with_config(config);
-        assert_eq!(client.base_url, "custom-host:8080");
-        assert_eq!(client.config.max_retries, 5);
-        assert_eq!(client.config.timeout, Duration::from_secs(10));
-    }
+        let (port, captured) = mock_server(|_, _| (200, "\"Success\"".to_string())).await;
 
-    #[test]
-    fn test_message_size_validation() {
-        let _client = TlqClient::new("localhost", 1337).unwrap();
+        let named = TlqClient::with_config(
+            ConfigBuilder::new()
+                .host("127.0.0.1")
+                .port(port)
+                .client_name("billing-worker")
+                .build(),
+        );
+        named.purge_queue().await.unwrap();
+        named.health_check().await.unwrap();
 
-        // Test exact limit
-        let message_at_limit = "x".repeat(MAX_MESSAGE_SIZE);
-        let result = std::panic::catch_unwind(|| {
-            // We can't actually test async methods in sync tests without tokio,
-            // but we can verify the constant is correct
-            assert_eq!(message_at_limit.len(), MAX_MESSAGE_SIZE);
-        });
-        assert!(result.is_ok());
+        let anonymous = TlqClient::new("127.0.0.1", port).unwrap();
+        anonymous.purge_queue().await.unwrap();
 
-        // Test over limit
-        let message_over_limit = "x".repeat(MAX_MESSAGE_SIZE + 1);
-        assert_eq!(message_over_limit.len(), MAX_MESSAGE_SIZE + 1);
+        let requests = captured.lock().unwrap();
+        assert_eq!(requests[0].header("X-Client-Name"), Some("billing-worker"));
+        assert_eq!(requests[1].header("X-Client-Name"), Some("billing-worker"));
+        assert_eq!(requests[2].header("X-Client-Name"), None);
     }
 
     #[tokio::test]
-    async fn test_add_message_size_validation() {
-        let client = TlqClient::new("localhost", 1337).unwrap();
+    async fn test_auth_token_and_custom_headers() {
+        use crate::test_support::mock_server;
 
-        // Test message at exact size limit (should be rejected because it's over the limit)
-        let large_message = "x".repeat(MAX_MESSAGE_SIZE + 1);
-        let result = client.add_message(large_message).await;
+        let (port, captured) = mock_server(|_, _| (200, "\"Success\"".to_string())).await;
 
-        match result {
-            Err(TlqError::MessageTooLarge { size }) => {
-                assert_eq!(size, MAX_MESSAGE_SIZE + 1);
-            }
-            _ => panic!("Expected MessageTooLarge error"),
-        }
+        let client = TlqClient::with_config(
+            ConfigBuilder::new()
+                .host("127.0.0.1")
+                .port(port)
+                .auth_token("s3cr3t")
+                .header("X-Api-Key", "old")
+                .header("x-api-key", "k-123")
+                .header("Authorization", "Basic ignored")
+                .build(),
+        );
+        client.purge_queue().await.unwrap();
+        client.health_check().await.unwrap();
 
-        // Test empty message (should be valid)
-        let empty_message = "";
-        // We can't actually test without a server, but we can verify it passes size validation
-        assert!(empty_message.len() <= MAX_MESSAGE_SIZE);
+        let anonymous = TlqClient::new("127.0.0.1", port).unwrap();
+        anonymous.purge_queue().await.unwrap();
 
-        // Test message exactly at limit (should be valid)
-        let max_message = "x".repeat(MAX_MESSAGE_SIZE);
-        // Size check should pass
-        assert_eq!(max_message.len(), MAX_MESSAGE_SIZE);
+        let requests = captured.lock().unwrap();
+        for request in &requests[..2] {
+            assert_eq!(request.header("Authorization"), Some("Bearer s3cr3t"));
+            assert_eq!(request.header("X-Api-Key"), Some("k-123"));
+        }
+        assert_eq!(requests[2].header("Authorization"), None);
+        assert_eq!(requests[2].header("X-Api-Key"), None);
     }
 
     #[tokio::test]
-    async fn test_get_messages_validation() {
-        let client = TlqClient::new("localhost", 1337).unwrap();
+    async fn test_invalid_headers_rejected() {
+        use crate::test_support::mock_server;
 
-        // Test zero count (should be rejected)
-        let result = client.get_messages(0).await;
-        match result {
-            Err(TlqError::Validation(msg)) => {
-                assert_eq!(msg, "Count must be greater than 0");
+        let (port, captured) = mock_server(|_, _| (200, "\"Success\"".to_string())).await;
+        let client_with = |builder: ConfigBuilder| {
+            TlqClient::with_config(builder.host("127.0.0.1").port(port).build())
+        };
+
+        for client in [
+            client_with(ConfigBuilder::new().auth_token("s3cr3t\r\nX-Evil: 1")),
+            client_with(ConfigBuilder::new().client_name("worker\r\nX-Evil: 1")),
+            client_with(ConfigBuilder::new().header("X-Api-Key", "k\n")),
+            client_with(ConfigBuilder::new().header("Bad Name", "value")),
+            client_with(ConfigBuilder::new().header("", "value")),
+            client_with(ConfigBuilder::new().header("Content-Length", "0")),
+        ] {
+            match client.purge_queue().await {
+                Err(TlqError::Validation(message)) => assert!(!message.contains("s3cr3t")),
+                other => panic!("Expected Validation error, got {:?}", other),
             }
-            _ => panic!("Expected validation error for zero count"),
         }
-
-        // Test valid counts - these should pass without validation errors
-        let _ = client.get_messages(1).await; // Should be valid
-        let _ = client.get_messages(100).await; // Should be valid
-        let _ = client.get_messages(u32::MAX).await; // Should be valid
+        assert!(captured.lock().unwrap().is_empty());
     }
 
     #[tokio::test]
-    async fn test_delete_messages_validation() {
-        let client = TlqClient::new("localhost", 1337).unwrap();
+    async fn test_auto_max_size_uses_server_limit() {
+        use crate::test_support::{message_json, mock_server};
 
-        // Test empty IDs array
-        let result = client.delete_messages(&[]).await;
-        match result {
-            Err(TlqError::Validation(msg)) => {
-                assert_eq!(msg, "No message IDs provided");
-            }
-            _ => panic!("Expected validation error for empty IDs"),
+        let (port, captured) = mock_server(|path, _| match path {
+            "/info" => (200, r#"{"max_message_size":10}"#.to_string()),
+            _ => (200, message_json(Uuid::now_v7(), "small", "Ready")),
+        })
+        .await;
+
+        let client = TlqClient::with_config(
+            ConfigBuilder::new()
+                .host("127.0.0.1")
+                .port(port)
+                .auto_max_size(true)
+                .build(),
+        );
+
+        assert!(client.add_message("small").await.is_ok());
+        match client.add_message("x".repeat(11)).await {
+            Err(TlqError::MessageTooLarge { size, .. }) => assert_eq!(size, 11),
+            other => panic!("Expected MessageTooLarge error, got {:?}", other),
         }
 
-        // Test delete_message (single ID) - should not have validation issue
-        use uuid::Uuid;
-        let test_id = Uuid::now_v7();
-        // We can't test the actual call without a server, but we can verify
-        // it would call delete_messages with a single-item array
-        assert!(!vec![test_id].is_empty());
+        let requests = captured.lock().unwrap();
+        let info_requests = requests.iter().filter(|r| r.path == "/info").count();
+        assert_eq!(info_requests, 1);
     }
 
     #[tokio::test]
-    async fn test_retry_messages_validation() {
-        let client = TlqClient::new("localhost", 1337).unwrap();
+    async fn test_auto_max_size_retries_info_after_server_error() {
+        use crate::test_support::mock_server;
+        use std::sync::atomic::AtomicU32;
 
-        // Test empty IDs array
-        let result = client.retry_messages(&[]).await;
-        match result {
-            Err(TlqError::Validation(msg)) => {
-                assert_eq!(msg, "No message IDs provided");
+        let calls = Arc::new(AtomicU32::new(0));
+        let counter = calls.clone();
+        let (port, _) = mock_server(move |_, _| {
+            if counter.fetch_add(1, Ordering::SeqCst) == 0 {
+                (503, "Service Unavailable".to_string())
+            } else {
+                (200, r#"{"max_message_size":10}"#.to_string())
             }
-            _ => panic!("Expected validation error for empty IDs"),
-        }
+        })
+        .await;
 
-        // Test retry_message (single ID) - should not have validation issue
-        use uuid::Uuid;
-        let test_id = Uuid::now_v7();
-        // We can't test the actual call without a server, but we can verify
-        // it would call retry_messages with a single-item array
-        assert!(!vec![test_id].is_empty());
+        let client = TlqClient::with_config(
+            ConfigBuilder::new()
+                .host("127.0.0.1")
+                .port(port)
+                .auto_max_size(true)
+                .build(),
+        );
+
+        // The outage falls back without pinning the default
+        assert_eq!(client.max_message_size().await.unwrap(), MAX_MESSAGE_SIZE);
+        assert_eq!(client.max_message_size().await.unwrap(), 10);
+        assert_eq!(client.max_message_size().await.unwrap(), 10);
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
     }
 
-    #[test]
-    fn test_client_builder_edge_cases() {
-        // Test builder with minimum values
-        let config = TlqClient::builder()
-            .host("")
-            .port(0)
-            .timeout_ms(0)
-            .max_retries(0)
-            .retry_delay_ms(0)
-            .build();
+    #[tokio::test]
+    async fn test_auto_max_size_falls_back_without_info_endpoint() {
+        use crate::test_support::{message_json, mock_server};
 
-        let client = TlqClient::with_config(config);
-        assert_eq!(client.base_url, ":0");
-        assert_eq!(client.config.max_retries, 0);
-        assert_eq!(client.config.timeout, Duration::from_millis(0));
+        let (port, _) = mock_server(|path, _| match path {
+            "/info" => (404, "Not Found".to_string()),
+            _ => (200, message_json(Uuid::now_v7(), "body", "Ready")),
+        })
+        .await;
 
-        // Test builder with maximum reasonable values
-        let config = TlqClient::builder()
-            .host("very-long-hostname-that-might-be-used-in-some-environments")
-            .port(65535)
-            .timeout_ms(600000) // 10 minutes
-            .max_retries(100)
-            .retry_delay_ms(10000) // 10 seconds
-            .build();
+        let client = TlqClient::with_config(
+            ConfigBuilder::new()
+                .host("127.0.0.1")
+                .port(port)
+                .auto_max_size(true)
+                .build(),
+        );
 
-        let client = TlqClient::with_config(config);
-        assert!(client.base_url.contains("very-long-hostname"));
-        assert_eq!(client.config.max_retries, 100);
-        assert_eq!(client.config.timeout, Duration::from_secs(600));
+        assert_eq!(client.max_message_size().await.unwrap(), MAX_MESSAGE_SIZE);
+        assert!(client.add_message("x".repeat(1000)).await.is_ok());
     }
 
-    #[test]
-    fn test_config_validation() {
-        use crate::config::ConfigBuilder;
-        use std::time::Duration;
-
-        // Test various duration configurations
-        let config1 = ConfigBuilder::new()
-            .timeout(Duration::from_nanos(1))
-            .build();
-        assert_eq!(config1.timeout, Duration::from_nanos(1));
+    #[tokio::test]
+    async fn test_operation_timeouts_override_global_timeout() {
+        use crate::config::OperationTimeouts;
 
-        let config2 = ConfigBuilder::new()
-            .retry_delay(Duration::from_secs(3600)) // 1 hour
-            .build();
-        assert_eq!(config2.retry_delay, Duration::from_secs(3600));
+        // Accepts connections (via the backlog) but never responds
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
 
-        // Test edge case ports
-        let config3 = ConfigBuilder::new().port(1).build();
-        assert_eq!(config3.port, 1);
+        let client = TlqClient::with_config(
+            ConfigBuilder::new()
+                .host("127.0.0.1")
+                .port(port)
+                .timeout_ms(300)
+                .max_retries(0)
+                .operation_timeouts(OperationTimeouts {
+                    get: Some(Duration::from_millis(50)),
+                    health: Some(Duration::from_millis(80)),
+                    ..OperationTimeouts::default()
+                })
+                .build(),
+        );
 
-        let config4 = ConfigBuilder::new().port(65535).build();
-        assert_eq!(config4.port, 65535);
+        assert!(matches!(
+            client.get_messages(1).await,
+            Err(TlqError::Timeout(50))
+        ));
+        assert!(matches!(
+            client.purge_queue().await,
+            Err(TlqError::Timeout(300))
+        ));
+        assert!(matches!(
+            client.health_check().await,
+            Err(TlqError::Timeout(80))
+        ));
 
-        // Test very high retry counts
-        let config5 = ConfigBuilder::new().max_retries(1000).build();
-        assert_eq!(config5.max_retries, 1000);
+        drop(listener);
     }
 }
@@ -0,0 +1,130 @@
+use std::time::{Duration, Instant};
+use tokio::sync::OwnedSemaphorePermit;
+use tokio_util::sync::CancellationToken;
+use uuid::Uuid;
+
+/// Options for [`TlqClient::consume`](crate::TlqClient::consume).
+///
+/// The defaults fetch one message at a time, delete each handled message right
+/// away, retry a failing message up to 3 times before deleting it, and wait one
+/// second between polls of an empty queue, without strict ordering. `shutdown` is never cancelled by default; pass a clone of a token
+/// you hold to stop the loop.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use tlq_client::{CancellationToken, ConsumeOptions};
+///
+/// let shutdown = CancellationToken::new();
+/// let options = ConsumeOptions {
+///     batch_size: 10,
+///     max_message_retries: 5,
+///     dead_letter_queue: Some("dead-letters".to_string()),
+///     shutdown: shutdown.clone(),
+///     ..ConsumeOptions::default()
+/// };
+/// assert_eq!(options.poll_interval, Duration::from_secs(1));
+/// ```
+#[derive(Debug, Clone)]
+pub struct ConsumeOptions {
+    /// Maximum number of messages fetched per request
+    pub batch_size: u32,
+    /// Number of times a failing message is retried before it is given up on
+    pub max_message_retries: u32,
+    /// How long to wait before polling again when the queue is empty
+    pub poll_interval: Duration,
+    /// Queue that given-up messages are moved to (`None` deletes them)
+    pub dead_letter_queue: Option<String>,
+    /// Stops the loop once cancelled
+    pub shutdown: CancellationToken,
+    /// Number of handled messages whose deletes are sent together
+    ///
+    /// Above 1, the IDs of successfully handled messages are buffered and
+    /// deleted with one [`delete_messages`](crate::TlqClient::delete_messages)
+    /// request once this many have been collected, once the oldest has waited
+    /// [`ack_linger`](Self::ack_linger), when the queue runs empty, and when the
+    /// loop stops. This saves a round-trip per message, but widens the
+    /// at-least-once window: messages handled but not yet deleted when the
+    /// process dies, or when `consume` returns an error, are delivered again.
+    pub ack_batch_size: usize,
+    /// How long a buffered acknowledgement may wait before it is flushed,
+    /// checked between messages
+    pub ack_linger: Duration,
+    /// Maximum number of fetched messages not yet acknowledged (`None` for no limit)
+    ///
+    /// Each fetched message takes a permit from a semaphore of this size until
+    /// it is deleted, retried or moved, including while its delete is buffered
+    /// by [`ack_batch_size`](Self::ack_batch_size). Fetches ask for no more
+    /// messages than there are free permits, so a large `batch_size` and slow
+    /// acknowledgements can't pile up unbounded work. Buffered
+    /// acknowledgements are flushed early when they hold every permit.
+    pub max_in_flight: Option<usize>,
+    /// Retry a failing message in place before handling any later message
+    ///
+    /// This keeps messages in queue order through failures, at the cost of
+    /// throughput: one failing message stalls the whole consumer for its
+    /// retries and their backoff.
+    pub strict_ordering: bool,
+}
+
+impl Default for ConsumeOptions {
+    fn default() -> Self {
+        Self {
+            batch_size: 1,
+            max_message_retries: 3,
+            poll_interval: Duration::from_secs(1),
+            dead_letter_queue: None,
+            shutdown: CancellationToken::new(),
+            ack_batch_size: 1,
+            ack_linger: Duration::from_millis(100),
+            max_in_flight: None,
+            strict_ordering: false,
+        }
+    }
+}
+
+// Acknowledgements buffered by `consume` until they are deleted together.
+pub(crate) struct PendingAcks {
+    ids: Vec<Uuid>,
+    permits: Vec<OwnedSemaphorePermit>,
+    oldest: Option<Instant>,
+    batch_size: usize,
+    linger: Duration,
+}
+
+impl PendingAcks {
+    pub(crate) fn new(batch_size: usize, linger: Duration) -> Self {
+        Self {
+            ids: Vec::new(),
+            permits: Vec::new(),
+            oldest: None,
+            batch_size: batch_size.max(1),
+            linger,
+        }
+    }
+
+    // Buffers `id`, keeping its in-flight permit until the delete is sent.
+    pub(crate) fn push(&mut self, id: Uuid, permit: OwnedSemaphorePermit) {
+        self.ids.push(id);
+        self.permits.push(permit);
+        self.oldest.get_or_insert_with(Instant::now);
+    }
+
+    // Whether the buffer is full or its oldest acknowledgement has lingered
+    // long enough.
+    pub(crate) fn is_due(&self) -> bool {
+        self.ids.len() >= self.batch_size
+            || self
+                .oldest
+                .is_some_and(|oldest| oldest.elapsed() >= self.linger)
+    }
+
+    pub(crate) fn take(&mut self) -> (Vec<Uuid>, Vec<OwnedSemaphorePermit>) {
+        self.oldest = None;
+        (
+            std::mem::take(&mut self.ids),
+            std::mem::take(&mut self.permits),
+        )
+    }
+}
@@ -1,3 +1,6 @@
+#[cfg(feature = "client")]
+use crate::message::Message;
+use std::time::Duration;
 use thiserror::Error;
 
 /// Comprehensive error type for TLQ client operations.
@@ -13,13 +16,19 @@ use thiserror::Error;
 /// - [`Connection`](Self::Connection) - Network connectivity problems
 /// - [`Timeout`](Self::Timeout) - Request timeouts
 /// - [`Io`](Self::Io) - I/O errors from the underlying transport
+/// - [`Throttled`](Self::Throttled) - The server asked the client to retry later
 ///
 /// **Non-retryable errors** (permanent failures that won't succeed on retry):
 /// - [`Server`](Self::Server) - HTTP 4xx/5xx responses from the server
 /// - [`Validation`](Self::Validation) - Invalid request parameters
 /// - [`Serialization`](Self::Serialization) - JSON parsing errors
+/// - [`InvalidBody`](Self::InvalidBody) - A message body doesn't parse as the expected type
 /// - [`MaxRetriesExceeded`](Self::MaxRetriesExceeded) - Retry limit reached
 /// - [`MessageTooLarge`](Self::MessageTooLarge) - Message exceeds size limit
+/// - [`RequestTooLarge`](Self::RequestTooLarge) - Request body exceeds the configured limit
+/// - [`QueueFull`](Self::QueueFull) - The queue stayed full and the server rejected an add
+/// - [`ProtocolMismatch`](Self::ProtocolMismatch) - Client and server protocol versions differ
+/// - [`Unsupported`](Self::Unsupported) - Operation not supported by the server
 ///
 /// # Examples
 ///
@@ -32,7 +41,7 @@ use thiserror::Error;
 ///     
 ///     match client.add_message("test").await {
 ///         Ok(message) => println!("Success: {}", message.id),
-///         Err(TlqError::MessageTooLarge { size }) => {
+///         Err(TlqError::MessageTooLarge { size, .. }) => {
 ///             println!("Message too large: {} bytes", size);
 ///         },
 ///         Err(TlqError::Connection(msg)) => {
@@ -65,6 +74,22 @@ pub enum TlqError {
     #[error("Server error: {status} - {message}")]
     Server { status: u16, message: String },
 
+    /// The server asked the client to back off
+    ///
+    /// The server answered 429 Too Many Requests or 503 Service Unavailable
+    /// with a `Retry-After` header. Retries wait at least `retry_after` before
+    /// the next attempt, even when the configured backoff is shorter. A
+    /// `retry_after` longer than the configured `max_retry_delay` (60 seconds
+    /// when unset) isn't waited for; the error is returned right away so the
+    /// caller can decide when to try again. Without a `Retry-After` header
+    /// these statuses are [`Server`](Self::Server) errors.
+    #[error("Server error: {status} - {message} (retry after {}s)", .retry_after.as_secs())]
+    Throttled {
+        status: u16,
+        message: String,
+        retry_after: Duration,
+    },
+
     /// Request validation error
     ///
     /// Invalid parameters were provided to a client method, such as
@@ -76,9 +101,27 @@ pub enum TlqError {
     ///
     /// Failed to parse JSON responses from the server or serialize
     /// request data to JSON.
+    ///
+    /// Requires the `client` feature.
+    #[cfg(feature = "client")]
     #[error("Serialization error: {0}")]
     Serialization(#[from] serde_json::Error),
 
+    /// A message body doesn't parse as the expected type
+    ///
+    /// Returned by [`TlqClient::get_messages_json`](crate::TlqClient::get_messages_json).
+    /// `message` is the offending message as it was fetched, still locked in the
+    /// `Processing` state, so it can be inspected and then deleted or failed.
+    ///
+    /// Requires the `client` feature.
+    #[cfg(feature = "client")]
+    #[error("Invalid body in message {}: {source}", .message.id)]
+    InvalidBody {
+        message: Box<Message>,
+        #[source]
+        source: serde_json::Error,
+    },
+
     /// I/O error from underlying transport
     ///
     /// Low-level I/O errors from TCP socket operations, such as
@@ -90,31 +133,114 @@ pub enum TlqError {
     ///
     /// The operation was retried the maximum number of times but still failed.
     /// The retry count is configurable via [`ConfigBuilder`](crate::ConfigBuilder).
-    #[error("Max retries exceeded ({max_retries}) for operation")]
-    MaxRetriesExceeded { max_retries: u32 },
+    /// `source` is the error of the final attempt, such as a
+    /// [`Timeout`](Self::Timeout). Errors that are not retried, and failures
+    /// with retries disabled, are returned unwrapped.
+    #[error("Max retries exceeded ({max_retries}) for operation: {source}")]
+    MaxRetriesExceeded {
+        max_retries: u32,
+        #[source]
+        source: Box<TlqError>,
+    },
+
+    /// Message size exceeds the configured limit
+    ///
+    /// Messages larger than `max` bytes are rejected before they are sent. The
+    /// limit is 65,536 bytes (64KB) unless changed with
+    /// [`ConfigBuilder::max_message_size`](crate::ConfigBuilder::max_message_size)
+    /// or learned from the server. For batch adds, `index` is the position of
+    /// the first offending body.
+    #[error(
+        "Message too large: {size} bytes (max: {max}){}",
+        .index.map(|i| format!(" at index {}", i)).unwrap_or_default()
+    )]
+    MessageTooLarge {
+        size: usize,
+        max: usize,
+        index: Option<usize>,
+    },
+
+    /// Request body exceeds the configured limit
+    ///
+    /// The complete request body, including the JSON envelope, is larger than
+    /// [`ConfigBuilder::max_request_body`](crate::ConfigBuilder::max_request_body).
+    /// The request was not sent.
+    #[error("Request too large: {size} bytes")]
+    RequestTooLarge { size: usize },
 
-    /// Message size exceeds the 64KB limit
+    /// The queue stayed full and the server rejected an add
+    ///
+    /// Returned by [`Producer::add_message`](crate::Producer::add_message) when
+    /// the queue held `depth` messages, at or above the producer's high-water
+    /// mark, for its whole `max_wait`, and the add attempted afterwards failed.
+    /// `source` is the server's rejection.
+    #[error("Queue full ({depth} messages): {source}")]
+    QueueFull {
+        depth: u64,
+        #[source]
+        source: Box<TlqError>,
+    },
+
+    /// The server doesn't understand this client's protocol version
     ///
-    /// TLQ enforces a maximum message size of 65,536 bytes (64KB).
-    /// Messages larger than this limit are rejected.
-    #[error("Message too large: {size} bytes (max: 65536)")]
-    MessageTooLarge { size: usize },
+    /// Every request advertises the client's protocol version in the
+    /// `X-TLQ-Client-Protocol` header. The server rejected it with 426 Upgrade
+    /// Required, or with 400 Bad Request and a different version in its
+    /// `X-TLQ-Server-Protocol` header. `server` is that version, if reported.
+    /// Upgrade or downgrade the client to match the server.
+    #[error(
+        "Protocol mismatch: client speaks protocol {client}, server {}",
+        .server.as_deref().map(|v| format!("speaks protocol {}", v)).unwrap_or_else(|| "did not report its protocol".to_string())
+    )]
+    ProtocolMismatch {
+        client: String,
+        server: Option<String>,
+    },
+
+    /// Operation not supported by the server
+    ///
+    /// The server does not implement the endpoint required by the operation,
+    /// typically because it predates the feature.
+    #[error("Unsupported operation: {0}")]
+    Unsupported(String),
+
+    /// An error annotated with the operation that failed
+    ///
+    /// Created by [`TlqResultExt::context_op`]. Displays as
+    /// `"<operation>: <error>"` and exposes the wrapped error as its
+    /// [`source`](std::error::Error::source); use [`root`](Self::root) to match
+    /// on the underlying error.
+    #[error("{operation}: {source}")]
+    Context {
+        operation: &'static str,
+        #[source]
+        source: Box<TlqError>,
+    },
 }
 
 impl TlqError {
     /// Determines if this error type is retryable.
     ///
+    /// [`Context`](Self::Context) errors are classified by the error they wrap.
+    ///
     /// Returns `true` for transient errors that may succeed if retried:
     /// - [`Connection`](Self::Connection) errors
     /// - [`Timeout`](Self::Timeout) errors  
     /// - [`Io`](Self::Io) errors
+    /// - [`Throttled`](Self::Throttled) errors
     ///
     /// Returns `false` for permanent errors that won't succeed on retry:
     /// - [`Server`](Self::Server) errors (4xx/5xx HTTP responses)
     /// - [`Validation`](Self::Validation) errors
     /// - [`Serialization`](Self::Serialization) errors
-    /// - [`MaxRetriesExceeded`](Self::MaxRetriesExceeded) errors
+    /// - [`InvalidBody`](Self::InvalidBody) errors
+    /// - [`MaxRetriesExceeded`](Self::MaxRetriesExceeded) errors, even when the
+    ///   final attempt failed with a retryable error, as the retries are used up
     /// - [`MessageTooLarge`](Self::MessageTooLarge) errors
+    /// - [`RequestTooLarge`](Self::RequestTooLarge) errors
+    /// - [`QueueFull`](Self::QueueFull) errors
+    /// - [`ProtocolMismatch`](Self::ProtocolMismatch) errors
+    /// - [`Unsupported`](Self::Unsupported) errors
     ///
     /// This method is used internally by the retry mechanism to determine
     /// whether to attempt retrying a failed operation.
@@ -132,10 +258,151 @@ impl TlqError {
     /// ```
     pub fn is_retryable(&self) -> bool {
         matches!(
-            self,
-            TlqError::Connection(_) | TlqError::Timeout(_) | TlqError::Io(_)
+            self.root(),
+            TlqError::Connection(_)
+                | TlqError::Timeout(_)
+                | TlqError::Io(_)
+                | TlqError::Throttled { .. }
         )
     }
+
+    /// Returns how long the server asked the client to wait before retrying,
+    /// for [`Throttled`](Self::Throttled) errors.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use tlq_client::TlqError;
+    ///
+    /// let error = TlqError::Throttled {
+    ///     status: 429,
+    ///     message: "slow down".to_string(),
+    ///     retry_after: Duration::from_secs(2),
+    /// };
+    /// assert_eq!(error.retry_after(), Some(Duration::from_secs(2)));
+    /// assert_eq!(TlqError::timeout(5000).retry_after(), None);
+    /// ```
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self.root() {
+            TlqError::Throttled { retry_after, .. } => Some(*retry_after),
+            _ => None,
+        }
+    }
+
+    /// Returns the underlying error, skipping any [`Context`](Self::Context) annotations.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tlq_client::{TlqError, TlqResultExt};
+    ///
+    /// let result: tlq_client::Result<()> = Err(TlqError::Timeout(5000));
+    /// let error = result.context_op("enqueue order").unwrap_err();
+    ///
+    /// assert!(matches!(error.root(), TlqError::Timeout(5000)));
+    /// ```
+    pub fn root(&self) -> &TlqError {
+        let mut error = self;
+        while let TlqError::Context { source, .. } = error {
+            error = source;
+        }
+        error
+    }
+}
+
+/// Constructors for fabricating errors, primarily in tests.
+///
+/// Code that handles [`TlqError`] can feed these to its error paths without a
+/// server or a real failing request. The variants stay public, so matching on
+/// errors is unaffected.
+///
+/// # Examples
+///
+/// ```
+/// use tlq_client::TlqError;
+///
+/// fn should_alert(error: &TlqError) -> bool {
+///     matches!(error, TlqError::Server { status: 500.., .. })
+/// }
+///
+/// assert!(should_alert(&TlqError::server(503, "unavailable")));
+/// assert!(!should_alert(&TlqError::timeout(5000)));
+/// ```
+impl TlqError {
+    /// Creates a [`Connection`](Self::Connection) error.
+    pub fn connection(message: impl Into<String>) -> Self {
+        TlqError::Connection(message.into())
+    }
+
+    /// Creates a [`Timeout`](Self::Timeout) error after `ms` milliseconds.
+    pub fn timeout(ms: u64) -> Self {
+        TlqError::Timeout(ms)
+    }
+
+    /// Creates a [`Server`](Self::Server) error with an HTTP status and message.
+    pub fn server(status: u16, message: impl Into<String>) -> Self {
+        TlqError::Server {
+            status,
+            message: message.into(),
+        }
+    }
+
+    /// Creates a [`Validation`](Self::Validation) error.
+    pub fn validation(message: impl Into<String>) -> Self {
+        TlqError::Validation(message.into())
+    }
+
+    /// Creates a [`Serialization`](Self::Serialization) error with a custom
+    /// message, without needing a real JSON parse failure.
+    ///
+    /// Requires the `client` feature.
+    #[cfg(feature = "client")]
+    pub fn serialization(message: impl std::fmt::Display) -> Self {
+        TlqError::Serialization(serde::de::Error::custom(message))
+    }
+
+    /// Creates an [`Unsupported`](Self::Unsupported) error.
+    pub fn unsupported(message: impl Into<String>) -> Self {
+        TlqError::Unsupported(message.into())
+    }
+}
+
+/// Extension trait for annotating errors with the operation that failed.
+///
+/// When a [`TlqError`] is propagated with `?` through several layers (for
+/// example into an `anyhow::Result`), it is often unclear which operation it
+/// came from. [`context_op`](Self::context_op) wraps the error in
+/// [`TlqError::Context`] with a short operation name, without adding any
+/// dependencies.
+///
+/// # Examples
+///
+/// ```no_run
+/// use tlq_client::{TlqClient, TlqResultExt};
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let client = TlqClient::new("localhost", 1337)?;
+///
+///     // Fails with e.g. "enqueue order: Connection error: ..."
+///     client.add_message("order 42").await.context_op("enqueue order")?;
+///
+///     Ok(())
+/// }
+/// ```
+pub trait TlqResultExt<T> {
+    /// Annotates an error with the name of the failed operation.
+    fn context_op(self, operation: &'static str) -> Result<T>;
+}
+
+impl<T> TlqResultExt<T> for Result<T> {
+    fn context_op(self, operation: &'static str) -> Result<T> {
+        self.map_err(|source| TlqError::Context {
+            operation,
+            source: Box::new(source),
+        })
+    }
 }
 
 /// Type alias for `Result<T, TlqError>`.
@@ -210,6 +477,7 @@ mod tests {
         assert_eq!(error_msg, "Validation error: Invalid input");
     }
 
+    #[cfg(feature = "client")]
     #[test]
     fn test_serialization_error_not_retryable() {
         // Create a serde_json error
@@ -221,24 +489,142 @@ mod tests {
         assert!(error_msg.contains("Serialization error:"));
     }
 
+    #[test]
+    fn test_throttled_is_retryable() {
+        let error = TlqError::Throttled {
+            status: 503,
+            message: "maintenance".to_string(),
+            retry_after: Duration::from_secs(30),
+        };
+        assert!(error.is_retryable());
+        assert_eq!(
+            error.to_string(),
+            "Server error: 503 - maintenance (retry after 30s)"
+        );
+    }
+
     #[test]
     fn test_max_retries_exceeded_not_retryable() {
-        let error = TlqError::MaxRetriesExceeded { max_retries: 3 };
+        let error = TlqError::MaxRetriesExceeded {
+            max_retries: 3,
+            source: Box::new(TlqError::Timeout(5000)),
+        };
         assert!(!error.is_retryable());
 
         let error_msg = format!("{}", error);
-        assert_eq!(error_msg, "Max retries exceeded (3) for operation");
+        assert_eq!(
+            error_msg,
+            "Max retries exceeded (3) for operation: Timeout error after 5000ms"
+        );
+        let source = std::error::Error::source(&error).unwrap();
+        assert_eq!(source.to_string(), "Timeout error after 5000ms");
     }
 
     #[test]
     fn test_message_too_large_not_retryable() {
-        let error = TlqError::MessageTooLarge { size: 70000 };
+        let error = TlqError::MessageTooLarge {
+            size: 70000,
+            max: 65536,
+            index: None,
+        };
         assert!(!error.is_retryable());
 
         let error_msg = format!("{}", error);
         assert_eq!(error_msg, "Message too large: 70000 bytes (max: 65536)");
     }
 
+    #[test]
+    fn test_constructors() {
+        assert_eq!(
+            TlqError::connection("refused").to_string(),
+            "Connection error: refused"
+        );
+        assert!(matches!(TlqError::timeout(250), TlqError::Timeout(250)));
+        assert!(matches!(
+            TlqError::server(404, "Not Found"),
+            TlqError::Server { status: 404, ref message } if message == "Not Found"
+        ));
+        assert!(matches!(
+            TlqError::validation("bad"),
+            TlqError::Validation(ref message) if message == "bad"
+        ));
+        assert!(matches!(
+            TlqError::unsupported("peek"),
+            TlqError::Unsupported(ref message) if message == "peek"
+        ));
+
+        #[cfg(feature = "client")]
+        assert_eq!(
+            TlqError::serialization("missing field `id`").to_string(),
+            "Serialization error: missing field `id`"
+        );
+    }
+
+    #[test]
+    fn test_request_too_large_not_retryable() {
+        let error = TlqError::RequestTooLarge { size: 2_000_000 };
+        assert!(!error.is_retryable());
+
+        let error_msg = format!("{}", error);
+        assert_eq!(error_msg, "Request too large: 2000000 bytes");
+    }
+
+    #[test]
+    fn test_protocol_mismatch_not_retryable() {
+        let error = TlqError::ProtocolMismatch {
+            client: "1".to_string(),
+            server: Some("2".to_string()),
+        };
+        assert!(!error.is_retryable());
+        assert_eq!(
+            error.to_string(),
+            "Protocol mismatch: client speaks protocol 1, server speaks protocol 2"
+        );
+
+        let error = TlqError::ProtocolMismatch {
+            client: "1".to_string(),
+            server: None,
+        };
+        assert_eq!(
+            error.to_string(),
+            "Protocol mismatch: client speaks protocol 1, server did not report its protocol"
+        );
+    }
+
+    #[test]
+    fn test_unsupported_not_retryable() {
+        let error = TlqError::Unsupported("delete_if_unchanged".to_string());
+        assert!(!error.is_retryable());
+
+        let error_msg = format!("{}", error);
+        assert_eq!(error_msg, "Unsupported operation: delete_if_unchanged");
+    }
+
+    #[test]
+    fn test_context_op_annotates_error() {
+        use std::error::Error;
+
+        let result: Result<()> = Err(TlqError::Timeout(5000));
+        let error = result
+            .context_op("fetch orders")
+            .context_op("sync job")
+            .unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "sync job: fetch orders: Timeout error after 5000ms"
+        );
+        assert!(error.is_retryable());
+        assert!(matches!(error.root(), TlqError::Timeout(5000)));
+        assert_eq!(
+            error.source().unwrap().to_string(),
+            "fetch orders: Timeout error after 5000ms"
+        );
+
+        let ok: Result<u32> = Ok(7);
+        assert_eq!(ok.context_op("unused").unwrap(), 7);
+    }
+
     #[test]
     fn test_error_from_io_error() {
         let io_error = IoError::new(ErrorKind::PermissionDenied, "Access denied");
@@ -248,6 +634,7 @@ mod tests {
         assert!(matches!(tlq_error, TlqError::Io(_)));
     }
 
+    #[cfg(feature = "client")]
     #[test]
     fn test_error_from_serde_json_error() {
         let json_error = serde_json::from_str::<serde_json::Value>("{invalid}").unwrap_err();
@@ -349,23 +736,45 @@ mod tests {
     #[test]
     fn test_message_size_edge_cases() {
         // Test various message sizes
-        let size_0 = TlqError::MessageTooLarge { size: 0 };
+        let size_0 = TlqError::MessageTooLarge {
+            size: 0,
+            max: 65536,
+            index: None,
+        };
         assert_eq!(
             format!("{}", size_0),
             "Message too large: 0 bytes (max: 65536)"
         );
 
-        let size_max = TlqError::MessageTooLarge { size: usize::MAX };
+        let size_max = TlqError::MessageTooLarge {
+            size: usize::MAX,
+            max: 65536,
+            index: None,
+        };
         assert_eq!(
             format!("{}", size_max),
             format!("Message too large: {} bytes (max: 65536)", usize::MAX)
         );
 
-        let size_just_over = TlqError::MessageTooLarge { size: 65537 };
+        let size_just_over = TlqError::MessageTooLarge {
+            size: 65537,
+            max: 65536,
+            index: None,
+        };
         assert_eq!(
             format!("{}", size_just_over),
             "Message too large: 65537 bytes (max: 65536)"
         );
+
+        let in_batch = TlqError::MessageTooLarge {
+            size: 65537,
+            max: 65536,
+            index: Some(3),
+        };
+        assert_eq!(
+            format!("{}", in_batch),
+            "Message too large: 65537 bytes (max: 65536) at index 3"
+        );
     }
 
     #[test]
@@ -40,9 +40,35 @@
 //! - **Type safety** - Strong typing with `serde` for JSON serialization
 //! - **Builder pattern** - Flexible configuration with [`ConfigBuilder`]
 //! - **Error handling** - Comprehensive error types with retryable classification
-//! - **Message validation** - Enforces 64KB message size limit
+//! - **Message validation** - Enforces a configurable message size limit (64KB by default)
 //! - **UUID v7 IDs** - Time-ordered message identifiers
 //!
+//! ## Cargo Features
+//!
+//! - `client` *(default)* - The async [`TlqClient`] and everything needing Tokio
+//!   and `serde_json`. Without it, the crate only provides the data types
+//!   ([`Message`], [`MessageState`], [`Config`]) and [`TlqError`].
+//! - `serde` *(enabled by `client`)* - `Serialize`/`Deserialize` for [`Message`]
+//!   and [`MessageState`].
+//! - `bytes` - Zero-copy message bodies with `bytes::Bytes`.
+//! - `testing` - Helpers for end-to-end tests against a TLQ server, in the
+//!   `testing` module.
+//! - `tls` - TLS connections via `rustls`, enabled with
+//!   [`ConfigBuilder::tls`](ConfigBuilder).
+//! - `stream` - [`TlqClient::messages`], a `futures` `Stream` of queue
+//!   messages.
+//! - `tracing` - A `tlq_request` span around each operation, with the
+//!   `endpoint`, `attempt`, `status` and `elapsed_ms` fields, and a warning
+//!   event for every retry.
+//! - `otel` - OpenTelemetry trace propagation. While a span is active in the
+//!   current OpenTelemetry `Context`, each operation runs in a `tlq_request`
+//!   client span with `tlq.endpoint`, `http.request.body.size` and
+//!   `http.response.status_code` attributes, and its requests carry that
+//!   span's W3C trace context as `traceparent: 00-{trace-id}-{span-id}-{flags}`
+//!   and, when not empty, `tracestate`. Without an active span nothing is
+//!   recorded or sent. Headers set with
+//!   [`ConfigBuilder::header`](ConfigBuilder::header) take precedence.
+//!
 //! ## Configuration
 //!
 //! Use [`ConfigBuilder`] for advanced configuration:
@@ -65,13 +91,61 @@
 //! # }
 //! ```
 
+#[cfg(feature = "client")]
+pub mod batch;
+#[cfg(feature = "client")]
 pub mod client;
 pub mod config;
+#[cfg(feature = "client")]
+pub mod consumer;
 pub mod error;
 pub mod message;
+#[cfg(feature = "otel")]
+mod otel;
+#[cfg(feature = "client")]
+pub mod producer;
+pub mod queue;
+#[cfg(feature = "client")]
+pub mod response;
 mod retry;
+pub mod server;
+#[cfg(feature = "client")]
+pub mod stats;
+#[cfg(feature = "stream")]
+pub mod stream;
+#[cfg(all(test, feature = "client"))]
+mod test_support;
+#[cfg(feature = "testing")]
+pub mod testing;
+#[cfg(feature = "client")]
+mod transport;
 
+#[cfg(feature = "client")]
+pub use batch::{Batch, BatchOptions, BatchOutcome, ChunkFailure};
+#[cfg(feature = "client")]
 pub use client::TlqClient;
-pub use config::{Config, ConfigBuilder};
-pub use error::{Result, TlqError};
-pub use message::{Message, MessageState};
+pub use config::{
+    BackoffKind, Config, ConfigBuilder, ConfigWarning, IpPreference, JitterKind, OperationTimeouts,
+    RetryObserver,
+};
+#[cfg(feature = "client")]
+pub use consumer::ConsumeOptions;
+pub use error::{Result, TlqError, TlqResultExt};
+pub use message::{FailureDisposition, Message, MessageState};
+#[cfg(feature = "client")]
+pub use producer::{Producer, ProducerOptions};
+pub use queue::{QueueInfo, QueueOptions, QueueStats};
+#[cfg(feature = "client")]
+pub use response::{Response, ResponseMeta};
+pub use server::HelloInfo;
+#[cfg(feature = "client")]
+pub use stats::ClientStats;
+#[cfg(feature = "stream")]
+pub use stream::MessageStream;
+/// The `rustls` crate used for TLS, re-exported for building a custom
+/// [`ConfigBuilder::tls_config`].
+#[cfg(feature = "tls")]
+pub use tokio_rustls::rustls;
+/// The token that stops [`TlqClient::consume`], re-exported from `tokio-util`.
+#[cfg(feature = "client")]
+pub use tokio_util::sync::CancellationToken;
@@ -1,4 +1,7 @@
+#[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use uuid::Uuid;
 
 /// Represents a message in the TLQ queue system.
@@ -6,6 +9,17 @@ use uuid::Uuid;
 /// Each message has a unique identifier, content, and metadata about its processing state.
 /// Messages are automatically assigned UUID v7 identifiers which provide time-ordering.
 ///
+/// # Ordering
+///
+/// Messages are ordered by [`id`](Self::id) only. Because UUID v7 identifiers embed
+/// their creation timestamp, sorting a batch of messages puts them in creation order.
+///
+/// # Equality and hashing
+///
+/// Equality and hashing compare every field, so a `HashSet<Message>` drops
+/// exact duplicates but keeps two snapshots of the same message whose state or
+/// retry count differ. Key by [`id`](Self::id) to deduplicate by identity.
+///
 /// # Examples
 ///
 /// ```
@@ -15,8 +29,16 @@ use uuid::Uuid;
 /// let message = Message::new("Hello, World!".to_string());
 /// println!("Message ID: {}", message.id);
 /// println!("Message body: {}", message.body);
+///
+/// // Sorting restores creation order
+/// let first = Message::new("first".to_string());
+/// let second = Message::new("second".to_string());
+/// let mut batch = vec![second, first];
+/// batch.sort();
+/// assert_eq!(batch[0].body, "first");
 /// ```
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Message {
     /// Unique identifier for the message (UUID v7 format for time-ordering)
     pub id: Uuid,
@@ -25,10 +47,22 @@ pub struct Message {
     /// Current processing state of the message
     pub state: MessageState,
     /// Optional ISO datetime string indicating when the message lock expires
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
     pub lock_until: Option<String>, // ISO datetime string
     /// Number of times this message has been retried after failure
     pub retry_count: u32,
+    /// The last error recorded for a failed message, if the server reports one
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub failure_reason: Option<String>,
+    /// The message's position in the queue when it was fetched, if the server reports it
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub position: Option<u64>,
 }
 
 /// Represents the current processing state of a message in the queue.
@@ -41,8 +75,8 @@ pub struct Message {
 ///
 /// # Serialization
 ///
-/// States are serialized in PascalCase format ("Ready", "Processing", "Failed")
-/// to match the TLQ server API expectations.
+/// With the `serde` feature, states are serialized in PascalCase format
+/// ("Ready", "Processing", "Failed") to match the TLQ server API expectations.
 ///
 /// # Examples
 ///
@@ -52,8 +86,9 @@ pub struct Message {
 /// let state = MessageState::Ready;
 /// assert_eq!(serde_json::to_string(&state).unwrap(), "\"Ready\"");
 /// ```
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
-#[serde(rename_all = "PascalCase")]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "PascalCase"))]
 pub enum MessageState {
     /// Message is ready to be processed by a consumer
     Ready,
@@ -63,6 +98,25 @@ pub enum MessageState {
     Failed,
 }
 
+/// What [`TlqClient::handle_failure`](crate::TlqClient::handle_failure) did
+/// with a message whose processing failed.
+///
+/// # Examples
+///
+/// ```
+/// use tlq_client::FailureDisposition;
+///
+/// let disposition = FailureDisposition::Deleted;
+/// assert_ne!(disposition, FailureDisposition::Retried);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FailureDisposition {
+    /// The message was returned to the queue for another attempt
+    Retried,
+    /// The message used up its attempts and was deleted
+    Deleted,
+}
+
 impl Message {
     /// Creates a new message with the specified body content.
     ///
@@ -71,6 +125,7 @@ impl Message {
     /// - State set to [`MessageState::Ready`]
     /// - No lock expiration time
     /// - Zero retry count
+    /// - No failure reason or queue position
     ///
     /// # Arguments
     ///
@@ -94,40 +149,231 @@ impl Message {
             state: MessageState::Ready,
             lock_until: None,
             retry_count: 0,
+            failure_reason: None,
+            position: None,
         }
     }
+
+    /// Returns when the message was created, decoded from its UUID v7 identifier.
+    ///
+    /// UUID v7 identifiers embed a millisecond Unix timestamp, so the creation
+    /// time is available without an extra server field. Returns `None` for
+    /// identifiers that carry no timestamp (e.g. UUID v4).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tlq_client::Message;
+    /// use std::time::SystemTime;
+    ///
+    /// let message = Message::new("task".to_string());
+    /// let created_at = message.created_at().unwrap();
+    /// assert!(created_at <= SystemTime::now());
+    /// ```
+    pub fn created_at(&self) -> Option<SystemTime> {
+        let (secs, nanos) = self.id.get_timestamp()?.to_unix();
+        Some(UNIX_EPOCH + Duration::new(secs, nanos))
+    }
+
+    /// Consumes the message and returns its body as [`Bytes`](bytes::Bytes).
+    ///
+    /// The body's existing allocation is reused, so no bytes are copied.
+    /// Requires the `bytes` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tlq_client::Message;
+    ///
+    /// let message = Message::new("payload".to_string());
+    /// assert_eq!(message.into_body_bytes(), "payload");
+    /// ```
+    #[cfg(feature = "bytes")]
+    pub fn into_body_bytes(self) -> bytes::Bytes {
+        bytes::Bytes::from(self.body)
+    }
+}
+
+impl PartialOrd for Message {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Message {
+    /// Compares messages by ID, which for UUID v7 is creation order.
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.id.cmp(&other.id)
+    }
 }
 
 // Internal request structures for TLQ API communication
 
 /// Request structure for adding a message to the queue
+#[cfg(feature = "client")]
 #[derive(Debug, Serialize)]
 pub struct AddMessageRequest {
     pub body: String,
 }
 
+/// Request structure for adding several messages in one request
+#[cfg(feature = "client")]
+#[derive(Debug, Serialize)]
+pub struct AddMessagesRequest<'a> {
+    pub bodies: &'a [String],
+}
+
 /// Request structure for retrieving messages from the queue
+#[cfg(feature = "client")]
 #[derive(Debug, Serialize)]
 pub struct GetMessagesRequest {
     pub count: u32,
+    /// How long the server may hold the request open waiting for messages
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wait_ms: Option<u64>,
+}
+
+/// Request structure for peeking at the next message in a given state
+#[cfg(feature = "client")]
+#[derive(Debug, Serialize)]
+pub struct PeekNextRequest {
+    pub state: MessageState,
 }
 
 /// Request structure for deleting messages from the queue
+#[cfg(feature = "client")]
 #[derive(Debug, Serialize)]
 pub struct DeleteMessagesRequest {
     pub ids: Vec<Uuid>,
 }
 
+/// Request structure for deleting a message only if its retry count is unchanged
+#[cfg(feature = "client")]
+#[derive(Debug, Serialize)]
+pub struct DeleteIfUnchangedRequest {
+    pub id: Uuid,
+    pub expected_retry_count: u32,
+}
+
+/// Request structure for deleting a message and returning its final snapshot
+#[cfg(feature = "client")]
+#[derive(Debug, Serialize)]
+pub struct DeleteReturningRequest {
+    pub id: Uuid,
+}
+
+/// Request structure for marking a message failed with a reason
+#[cfg(feature = "client")]
+#[derive(Debug, Serialize)]
+pub struct FailRequest {
+    pub id: Uuid,
+    pub reason: String,
+}
+
+/// Request structure for moving messages to another queue
+#[cfg(feature = "client")]
+#[derive(Debug, Serialize)]
+pub struct MoveMessagesRequest {
+    pub ids: Vec<Uuid>,
+    pub to_queue: String,
+}
+
+/// Request structure for creating a queue
+#[cfg(feature = "client")]
+#[derive(Debug, Serialize)]
+pub struct CreateQueueRequest {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_size: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lock_duration_ms: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ttl_ms: Option<u64>,
+}
+
+/// Request structure for deleting a queue
+#[cfg(feature = "client")]
+#[derive(Debug, Serialize)]
+pub struct DeleteQueueRequest {
+    pub name: String,
+    pub force: bool,
+}
+
 /// Request structure for retrying failed messages
+#[cfg(feature = "client")]
 #[derive(Debug, Serialize)]
 pub struct RetryMessagesRequest {
     pub ids: Vec<Uuid>,
 }
 
+/// Response structure for the server information endpoint
+#[cfg(feature = "client")]
+#[derive(Debug, Deserialize)]
+pub struct ServerInfoResponse {
+    pub max_message_size: Option<usize>,
+}
+
+/// Response structure for endpoints returning a list of messages
+///
+/// Accepts both a bare `[...]` array and an object wrapping the array in a
+/// `messages` key, as returned by different server versions.
+#[cfg(feature = "client")]
+#[derive(Debug)]
+pub struct MessagesResponse(pub Vec<Message>);
+
+#[cfg(feature = "client")]
+impl<'de> Deserialize<'de> for MessagesResponse {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        use serde::de::{self, IgnoredAny, MapAccess, SeqAccess, Visitor};
+        use std::fmt;
+
+        struct MessagesVisitor;
+
+        impl<'de> Visitor<'de> for MessagesVisitor {
+            type Value = Vec<Message>;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("an array of messages or an object with a `messages` array")
+            }
+
+            fn visit_seq<A>(self, seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: SeqAccess<'de>,
+            {
+                Vec::deserialize(de::value::SeqAccessDeserializer::new(seq))
+            }
+
+            fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+            where
+                A: MapAccess<'de>,
+            {
+                let mut messages = None;
+                while let Some(key) = map.next_key::<String>()? {
+                    if key == "messages" {
+                        if messages.is_some() {
+                            return Err(de::Error::duplicate_field("messages"));
+                        }
+                        messages = Some(map.next_value()?);
+                    } else {
+                        map.next_value::<IgnoredAny>()?;
+                    }
+                }
+                messages.ok_or_else(|| de::Error::missing_field("messages"))
+            }
+        }
+
+        deserializer
+            .deserialize_any(MessagesVisitor)
+            .map(MessagesResponse)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use serde_json;
 
     #[test]
     fn test_message_creation() {
@@ -141,6 +387,43 @@ mod tests {
         assert!(!message.id.to_string().is_empty());
     }
 
+    #[test]
+    fn test_created_at_from_uuid_v7() {
+        let before = SystemTime::now() - Duration::from_millis(1);
+        let message = Message::new("task".to_string());
+        let after = SystemTime::now();
+
+        let created_at = message.created_at().unwrap();
+        assert!(created_at >= before && created_at <= after);
+
+        let v4 = Message {
+            id: Uuid::from_u128(0x67e5_5044_10b1_426f_9247_bb68_0e5f_e0c8),
+            ..message
+        };
+        assert_eq!(v4.created_at(), None);
+    }
+
+    #[test]
+    fn test_message_sort_restores_creation_order() {
+        let created: Vec<Message> = (0..5)
+            .map(|i| Message::new(format!("message {}", i)))
+            .collect();
+
+        let mut shuffled = vec![
+            created[3].clone(),
+            created[0].clone(),
+            created[4].clone(),
+            created[2].clone(),
+            created[1].clone(),
+        ];
+        shuffled.sort();
+
+        assert_eq!(shuffled, created);
+        assert!(created[0] < created[1]);
+        assert_eq!(created.iter().max().unwrap().body, "message 4");
+    }
+
+    #[cfg(feature = "serde")]
     #[test]
     fn test_message_state_serialization() {
         // Test that MessageState serializes to the expected Pascal case
@@ -158,6 +441,7 @@ mod tests {
         );
     }
 
+    #[cfg(feature = "serde")]
     #[test]
     fn test_message_state_deserialization() {
         // Test that MessageState deserializes from Pascal case
@@ -175,6 +459,7 @@ mod tests {
         );
     }
 
+    #[cfg(feature = "serde")]
     #[test]
     fn test_message_state_invalid_deserialization() {
         // Test that invalid states fail to deserialize
@@ -188,6 +473,7 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[cfg(feature = "serde")]
     #[test]
     fn test_message_serialization() {
         let message = Message::new("test body".to_string());
@@ -208,6 +494,7 @@ mod tests {
         assert_eq!(deserialized.id, message.id);
     }
 
+    #[cfg(feature = "serde")]
     #[test]
     fn test_message_with_special_characters() {
         let special_body = "Test with 🦀 emojis and \"quotes\" and \n newlines \t tabs";
@@ -239,6 +526,7 @@ mod tests {
         assert_eq!(message.retry_count, 0);
     }
 
+    #[cfg(feature = "client")]
     #[test]
     fn test_request_response_structures() {
         // Test AddMessageRequest
@@ -249,9 +537,19 @@ mod tests {
         assert!(json.contains("\"body\":\"test message\""));
 
         // Test GetMessagesRequest
-        let get_req = GetMessagesRequest { count: 5 };
+        let get_req = GetMessagesRequest {
+            count: 5,
+            wait_ms: None,
+        };
+        let json = serde_json::to_string(&get_req).unwrap();
+        assert_eq!(json, "{\"count\":5}");
+
+        let get_req = GetMessagesRequest {
+            count: 5,
+            wait_ms: Some(2000),
+        };
         let json = serde_json::to_string(&get_req).unwrap();
-        assert!(json.contains("\"count\":5"));
+        assert_eq!(json, "{\"count\":5,\"wait_ms\":2000}");
 
         // Test DeleteMessagesRequest
         use uuid::Uuid;
@@ -269,6 +567,7 @@ mod tests {
         assert!(json.contains("\"ids\":"));
     }
 
+    #[cfg(feature = "serde")]
     #[test]
     fn test_response_deserialization() {
         // Test direct Message response (for add_message)
@@ -295,6 +594,85 @@ mod tests {
         assert_eq!(health_response, "Hello World");
     }
 
+    #[test]
+    fn test_hash_set_and_state_tallies() {
+        use std::collections::{HashMap, HashSet};
+
+        let first = Message::new("first".to_string());
+        let mut failed = Message::new("second".to_string());
+        failed.state = MessageState::Failed;
+
+        let unique: HashSet<Message> = [first.clone(), first.clone(), failed.clone()]
+            .into_iter()
+            .collect();
+        assert_eq!(unique.len(), 2);
+
+        let mut tallies: HashMap<MessageState, u64> = HashMap::new();
+        for message in [&first, &failed, &failed] {
+            *tallies.entry(message.state.clone()).or_default() += 1;
+        }
+        assert_eq!(tallies[&MessageState::Ready], 1);
+        assert_eq!(tallies[&MessageState::Failed], 2);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_failure_reason_deserialization() {
+        let failed_json = r#"{"id":"0198fbd8-344e-7b70-841f-3fbd4b371e4c","body":"test","state":"Failed","lock_until":null,"retry_count":3,"failure_reason":"lock expired"}"#;
+        let message: Message = serde_json::from_str(failed_json).unwrap();
+        assert_eq!(message.state, MessageState::Failed);
+        assert_eq!(message.failure_reason.as_deref(), Some("lock expired"));
+
+        let plain_json = r#"{"id":"0198fbd8-344e-7b70-841f-3fbd4b371e4c","body":"test","state":"Failed","lock_until":null,"retry_count":3}"#;
+        let message: Message = serde_json::from_str(plain_json).unwrap();
+        assert_eq!(message.failure_reason, None);
+
+        let json = serde_json::to_string(&message).unwrap();
+        assert!(!json.contains("failure_reason"));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_position_deserialization() {
+        let json = r#"{"id":"0198fbd8-344e-7b70-841f-3fbd4b371e4c","body":"test","state":"Processing","lock_until":null,"retry_count":0,"position":10000}"#;
+        let message: Message = serde_json::from_str(json).unwrap();
+        assert_eq!(message.position, Some(10_000));
+
+        let older_json = r#"{"id":"0198fbd8-344e-7b70-841f-3fbd4b371e4c","body":"test","state":"Processing","lock_until":null,"retry_count":0}"#;
+        let message: Message = serde_json::from_str(older_json).unwrap();
+        assert_eq!(message.position, None);
+
+        let json = serde_json::to_string(&message).unwrap();
+        assert!(!json.contains("position"));
+    }
+
+    #[cfg(feature = "client")]
+    #[test]
+    fn test_server_info_deserialization() {
+        let info: ServerInfoResponse =
+            serde_json::from_str(r#"{"max_message_size":1048576,"version":"0.3.0"}"#).unwrap();
+        assert_eq!(info.max_message_size, Some(1_048_576));
+
+        let info: ServerInfoResponse = serde_json::from_str(r#"{"version":"0.3.0"}"#).unwrap();
+        assert_eq!(info.max_message_size, None);
+    }
+
+    #[cfg(feature = "client")]
+    #[test]
+    fn test_messages_response_accepts_both_shapes() {
+        let message = r#"{"id":"0198fbd8-344e-7b70-841f-3fbd4b371e4c","body":"test","state":"Ready","lock_until":null,"retry_count":0}"#;
+
+        let bare: MessagesResponse = serde_json::from_str(&format!("[{}]", message)).unwrap();
+        let wrapped: MessagesResponse =
+            serde_json::from_str(&format!(r#"{{"count":1,"messages":[{}]}}"#, message)).unwrap();
+
+        assert_eq!(bare.0.len(), 1);
+        assert_eq!(bare.0, wrapped.0);
+        assert!(serde_json::from_str::<MessagesResponse>(r#"{"items":[]}"#).is_err());
+        assert!(serde_json::from_str::<MessagesResponse>(r#""nope""#).is_err());
+    }
+
+    #[cfg(feature = "serde")]
     #[test]
     fn test_malformed_response_deserialization() {
         // Test that malformed JSON fails gracefully
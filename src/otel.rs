@@ -0,0 +1,77 @@
+// OpenTelemetry support for the `otel` feature: each request made while an
+// OpenTelemetry span is active gets a client span of its own, and the W3C
+// trace context of that span is sent with the request.
+
+use crate::error::Result;
+use opentelemetry::trace::{SpanKind, Status, TraceContextExt, Tracer};
+use opentelemetry::{global, Context, KeyValue};
+use std::future::Future;
+
+// Runs `request` in a `tlq_request` client span for `endpoint`, a child of
+// the current span. Without an active span, `request` runs as is, so no
+// trace context is sent.
+pub(crate) async fn in_span<T>(
+    endpoint: &str,
+    request: impl Future<Output = Result<T>>,
+) -> Result<T> {
+    use opentelemetry::context::FutureExt;
+
+    let parent = Context::current();
+    if !parent.span().span_context().is_valid() {
+        return request.await;
+    }
+
+    let tracer = global::tracer("tlq-client");
+    let span = tracer
+        .span_builder("tlq_request")
+        .with_kind(SpanKind::Client)
+        .with_attributes([KeyValue::new("tlq.endpoint", endpoint.to_string())])
+        .start_with_context(&tracer, &parent);
+    let cx = parent.with_span(span);
+
+    let result = request.with_context(cx.clone()).await;
+    let span = cx.span();
+    if let Err(err) = &result {
+        span.set_status(Status::error(err.to_string()));
+    }
+    span.end();
+    result
+}
+
+// The `traceparent` and `tracestate` headers for the current span, in the
+// W3C Trace Context format, or nothing without an active span.
+pub(crate) fn trace_context_headers() -> String {
+    let cx = Context::current();
+    let span = cx.span();
+    let context = span.span_context();
+    if !context.is_valid() {
+        return String::new();
+    }
+
+    let mut headers = format!(
+        "traceparent: 00-{:032x}-{:016x}-{:02x}\r\n",
+        context.trace_id(),
+        context.span_id(),
+        context.trace_flags()
+    );
+    let state = context.trace_state().header();
+    if !state.is_empty() {
+        headers.push_str(&format!("tracestate: {}\r\n", state));
+    }
+    headers
+}
+
+// Records the size of the request body on the current span.
+pub(crate) fn record_body_size(size: usize) {
+    Context::current()
+        .span()
+        .set_attribute(KeyValue::new("http.request.body.size", size as i64));
+}
+
+// Records the response status on the current span.
+pub(crate) fn record_status(status: u16) {
+    Context::current().span().set_attribute(KeyValue::new(
+        "http.response.status_code",
+        i64::from(status),
+    ));
+}
@@ -0,0 +1,308 @@
+use crate::{
+    client::TlqClient,
+    error::{Result, TlqError},
+    message::Message,
+};
+use std::time::{Duration, Instant};
+
+/// Options for a [`Producer`], created with [`TlqClient::producer`].
+///
+/// The defaults hold adds back while the queue has 10,000 or more messages,
+/// reuse a depth reading for one second, poll every 500 milliseconds while
+/// waiting, and give up waiting after 30 seconds.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use tlq_client::ProducerOptions;
+///
+/// let options = ProducerOptions {
+///     high_water: 1_000,
+///     poll_interval: Duration::from_millis(200),
+///     ..ProducerOptions::default()
+/// };
+/// assert_eq!(options.max_wait, Duration::from_secs(30));
+/// ```
+#[derive(Debug, Clone)]
+pub struct ProducerOptions {
+    /// Queue depth at or above which adds wait for the queue to drain
+    pub high_water: u64,
+    /// How long to wait between depth checks while the queue is above `high_water`
+    pub poll_interval: Duration,
+    /// How long a depth reading is reused before the next add checks again
+    pub stats_ttl: Duration,
+    /// How long an add waits for the queue to drain before it is attempted anyway
+    pub max_wait: Duration,
+}
+
+impl Default for ProducerOptions {
+    fn default() -> Self {
+        Self {
+            high_water: 10_000,
+            poll_interval: Duration::from_millis(500),
+            stats_ttl: Duration::from_secs(1),
+            max_wait: Duration::from_secs(30),
+        }
+    }
+}
+
+/// A producer that holds adds back while the queue is near capacity.
+///
+/// Returned by [`TlqClient::producer`]. Before each add, the queue depth from
+/// [`queue_stats`](TlqClient::queue_stats) is compared with
+/// [`high_water`](ProducerOptions::high_water). The depth counts messages in
+/// every state and is cached for [`stats_ttl`](ProducerOptions::stats_ttl),
+/// counting this producer's own adds in the meantime, so a burst of adds
+/// costs one stats request. While the queue is at or above the high-water
+/// mark, the add waits, checking again every
+/// [`poll_interval`](ProducerOptions::poll_interval). After
+/// [`max_wait`](ProducerOptions::max_wait) it is attempted anyway, and a
+/// server rejection is returned as [`TlqError::QueueFull`].
+///
+/// Against a server that doesn't report queue statistics, adds are sent
+/// without flow control.
+///
+/// # Examples
+///
+/// ```no_run
+/// use tlq_client::{ProducerOptions, TlqClient};
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), tlq_client::TlqError> {
+///     let client = TlqClient::new("localhost", 1337)?;
+///
+///     let mut producer = client.producer(ProducerOptions {
+///         high_water: 1_000,
+///         ..ProducerOptions::default()
+///     });
+///     for i in 0..10_000 {
+///         producer.add_message(format!("event {}", i)).await?;
+///     }
+///
+///     Ok(())
+/// }
+/// ```
+pub struct Producer<'a> {
+    client: &'a TlqClient,
+    options: ProducerOptions,
+    depth: Option<(Instant, u64)>,
+    stats_supported: bool,
+}
+
+impl<'a> Producer<'a> {
+    pub(crate) fn new(client: &'a TlqClient, options: ProducerOptions) -> Self {
+        Self {
+            client,
+            options,
+            depth: None,
+            stats_supported: true,
+        }
+    }
+
+    /// Adds a message once the queue is below the high-water mark.
+    ///
+    /// # Errors
+    ///
+    /// * [`TlqError::QueueFull`] if the queue stayed at or above the high-water
+    ///   mark for `max_wait` and the server then rejected the add
+    /// * Any error of [`queue_stats`](TlqClient::queue_stats) other than
+    ///   [`TlqError::Unsupported`]
+    /// * Any error of [`add_message`](TlqClient::add_message)
+    pub async fn add_message(&mut self, body: impl Into<String>) -> Result<Message> {
+        let started = Instant::now();
+        // A cached reading at the mark is checked again rather than waited on
+        if matches!(self.depth, Some((_, depth)) if depth >= self.options.high_water) {
+            self.depth = None;
+        }
+        let mut full = None;
+        while let Some(depth) = self.depth().await? {
+            if depth < self.options.high_water {
+                full = None;
+                break;
+            }
+            full = Some(depth);
+            let waited = started.elapsed();
+            if waited >= self.options.max_wait {
+                break;
+            }
+            tokio::time::sleep(
+                self.options
+                    .poll_interval
+                    .min(self.options.max_wait - waited),
+            )
+            .await;
+            // Waiting needs a fresh reading, not the cached one
+            self.depth = None;
+        }
+
+        let result = self.client.add_message(body).await;
+        if let (Ok(_), Some((_, depth))) = (&result, &mut self.depth) {
+            *depth += 1;
+        }
+        match (result, full) {
+            (Err(err @ (TlqError::Server { .. } | TlqError::Throttled { .. })), Some(depth)) => {
+                Err(TlqError::QueueFull {
+                    depth,
+                    source: Box::new(err),
+                })
+            }
+            (result, _) => result,
+        }
+    }
+
+    // The queue depth, from the cache while it is fresh. `None` if the server
+    // doesn't report queue statistics.
+    async fn depth(&mut self) -> Result<Option<u64>> {
+        if !self.stats_supported {
+            return Ok(None);
+        }
+        if let Some((read_at, depth)) = self.depth {
+            if read_at.elapsed() < self.options.stats_ttl {
+                return Ok(Some(depth));
+            }
+        }
+        match self.client.queue_stats().await {
+            Ok(stats) => {
+                let depth = stats.total();
+                self.depth = Some((Instant::now(), depth));
+                Ok(Some(depth))
+            }
+            Err(TlqError::Unsupported(_)) => {
+                self.stats_supported = false;
+                Ok(None)
+            }
+            Err(err) => Err(err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{message_json, mock_server};
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::Arc;
+    use uuid::Uuid;
+
+    fn stats_json(ready: u64) -> String {
+        format!(r#"{{"ready":{},"processing":0,"failed":0}}"#, ready)
+    }
+
+    #[tokio::test]
+    async fn test_add_below_high_water_uses_cached_depth() {
+        let (port, captured) = mock_server(|path, _| match path {
+            "/stats" => (200, stats_json(5)),
+            _ => (200, message_json(Uuid::now_v7(), "event", "Ready")),
+        })
+        .await;
+        let client = TlqClient::new("127.0.0.1", port).unwrap();
+
+        let mut producer = client.producer(ProducerOptions {
+            high_water: 10,
+            stats_ttl: Duration::from_secs(60),
+            ..ProducerOptions::default()
+        });
+        for _ in 0..3 {
+            producer.add_message("event").await.unwrap();
+        }
+
+        let requests = captured.lock().unwrap();
+        let paths: Vec<_> = requests.iter().map(|r| r.path.as_str()).collect();
+        assert_eq!(paths, vec!["/stats", "/add", "/add", "/add"]);
+    }
+
+    #[tokio::test]
+    async fn test_cached_depth_counts_own_adds() {
+        let (port, captured) = mock_server(|path, _| match path {
+            "/stats" => (200, stats_json(8)),
+            _ => (200, message_json(Uuid::now_v7(), "event", "Ready")),
+        })
+        .await;
+        let client = TlqClient::new("127.0.0.1", port).unwrap();
+
+        let mut producer = client.producer(ProducerOptions {
+            high_water: 10,
+            stats_ttl: Duration::from_secs(60),
+            ..ProducerOptions::default()
+        });
+        producer.add_message("event").await.unwrap();
+        producer.add_message("event").await.unwrap();
+        // The cache now says 10, so the third add checks the queue again
+        producer.add_message("event").await.unwrap();
+
+        let requests = captured.lock().unwrap();
+        let paths: Vec<_> = requests.iter().map(|r| r.path.as_str()).collect();
+        assert_eq!(paths, vec!["/stats", "/add", "/add", "/stats", "/add"]);
+    }
+
+    #[tokio::test]
+    async fn test_add_waits_for_queue_to_drain() {
+        let depth = Arc::new(AtomicU64::new(20));
+        let stats = depth.clone();
+        let (port, captured) = mock_server(move |path, _| match path {
+            // Each check sees the queue drained a little further
+            "/stats" => (200, stats_json(stats.fetch_sub(5, Ordering::SeqCst))),
+            _ => (200, message_json(Uuid::now_v7(), "event", "Ready")),
+        })
+        .await;
+        let client = TlqClient::new("127.0.0.1", port).unwrap();
+
+        let mut producer = client.producer(ProducerOptions {
+            high_water: 10,
+            poll_interval: Duration::from_millis(10),
+            ..ProducerOptions::default()
+        });
+        producer.add_message("event").await.unwrap();
+
+        let requests = captured.lock().unwrap();
+        let paths: Vec<_> = requests.iter().map(|r| r.path.as_str()).collect();
+        assert_eq!(paths, vec!["/stats", "/stats", "/stats", "/stats", "/add"]);
+    }
+
+    #[tokio::test]
+    async fn test_queue_full_after_max_wait() {
+        let (port, captured) = mock_server(|path, _| match path {
+            "/stats" => (200, stats_json(100)),
+            _ => (507, "Queue is full".to_string()),
+        })
+        .await;
+        let client = TlqClient::new("127.0.0.1", port).unwrap();
+
+        let mut producer = client.producer(ProducerOptions {
+            high_water: 10,
+            poll_interval: Duration::from_millis(10),
+            max_wait: Duration::from_millis(50),
+            ..ProducerOptions::default()
+        });
+        match producer.add_message("event").await {
+            Err(TlqError::QueueFull { depth, source }) => {
+                assert_eq!(depth, 100);
+                assert!(matches!(*source, TlqError::Server { status: 507, .. }));
+            }
+            other => panic!("Expected QueueFull error, got {:?}", other),
+        }
+
+        // The add was still attempted once the wait ran out
+        let requests = captured.lock().unwrap();
+        assert_eq!(requests.last().unwrap().path, "/add");
+    }
+
+    #[tokio::test]
+    async fn test_add_without_stats_endpoint() {
+        let (port, captured) = mock_server(|path, _| match path {
+            "/stats" => (404, "Not Found".to_string()),
+            _ => (200, message_json(Uuid::now_v7(), "event", "Ready")),
+        })
+        .await;
+        let client = TlqClient::new("127.0.0.1", port).unwrap();
+
+        let mut producer = client.producer(ProducerOptions::default());
+        producer.add_message("event").await.unwrap();
+        producer.add_message("event").await.unwrap();
+
+        let requests = captured.lock().unwrap();
+        let paths: Vec<_> = requests.iter().map(|r| r.path.as_str()).collect();
+        assert_eq!(paths, vec!["/stats", "/add", "/add"]);
+    }
+}
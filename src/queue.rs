@@ -0,0 +1,132 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// A queue hosted by a TLQ server, as returned by
+/// [`TlqClient::list_queues`](crate::TlqClient::list_queues).
+///
+/// # Examples
+///
+/// ```
+/// use tlq_client::QueueInfo;
+///
+/// let queue = QueueInfo {
+///     name: "orders".to_string(),
+///     stats: None,
+/// };
+/// assert_eq!(queue.name, "orders");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct QueueInfo {
+    /// The queue name, usable with [`ConfigBuilder::queue`](crate::ConfigBuilder::queue)
+    pub name: String,
+    /// Message counts for the queue, if the server reports them
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub stats: Option<QueueStats>,
+}
+
+/// Message counts of a queue, broken down by [`MessageState`](crate::MessageState).
+///
+/// Returned by [`TlqClient::queue_stats`](crate::TlqClient::queue_stats) and
+/// as part of [`QueueInfo`]. Counts missing from the server's response default
+/// to zero; a `total` reported by the server is ignored in favor of
+/// [`total`](Self::total).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct QueueStats {
+    /// Number of messages waiting to be processed
+    pub ready: u64,
+    /// Number of messages currently locked by a consumer
+    pub processing: u64,
+    /// Number of messages whose processing failed
+    pub failed: u64,
+}
+
+impl QueueStats {
+    /// Returns the total number of messages in the queue.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tlq_client::QueueStats;
+    ///
+    /// let stats = QueueStats { ready: 3, processing: 2, failed: 1 };
+    /// assert_eq!(stats.total(), 6);
+    /// ```
+    pub fn total(&self) -> u64 {
+        self.ready + self.processing + self.failed
+    }
+}
+
+/// Settings for a new queue, used with
+/// [`TlqClient::create_queue`](crate::TlqClient::create_queue).
+///
+/// Unset options use the server's defaults.
+///
+/// # Examples
+///
+/// ```
+/// use tlq_client::QueueOptions;
+/// use std::time::Duration;
+///
+/// let options = QueueOptions {
+///     max_size: Some(10_000),
+///     ttl: Some(Duration::from_secs(3600)),
+///     ..QueueOptions::default()
+/// };
+/// assert_eq!(options.lock_duration, None);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct QueueOptions {
+    /// Maximum number of messages the queue holds
+    pub max_size: Option<u64>,
+    /// How long a retrieved message stays locked before it is released
+    pub lock_duration: Option<Duration>,
+    /// How long a message is kept before it expires
+    pub ttl: Option<Duration>,
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_queue_list_deserialization() {
+        let json = r#"[
+            {"name":"orders","stats":{"ready":5,"processing":2,"failed":1}},
+            {"name":"invoices"},
+            {"name":"audit","stats":{"ready":7}}
+        ]"#;
+        let queues: Vec<QueueInfo> = serde_json::from_str(json).unwrap();
+
+        assert_eq!(queues.len(), 3);
+        assert_eq!(queues[0].name, "orders");
+        assert_eq!(
+            queues[0].stats,
+            Some(QueueStats {
+                ready: 5,
+                processing: 2,
+                failed: 1
+            })
+        );
+        assert_eq!(queues[1].stats, None);
+        assert_eq!(queues[2].stats.unwrap().total(), 7);
+    }
+
+    #[test]
+    fn test_queue_info_serialization_skips_missing_stats() {
+        let queue = QueueInfo {
+            name: "orders".to_string(),
+            stats: None,
+        };
+        assert_eq!(
+            serde_json::to_string(&queue).unwrap(),
+            r#"{"name":"orders"}"#
+        );
+    }
+}
@@ -0,0 +1,115 @@
+/// The result of an operation together with metadata from the server's response.
+///
+/// Returned by the `_detailed` variants of client operations, such as
+/// [`TlqClient::add_message_detailed`](crate::TlqClient::add_message_detailed).
+///
+/// # Examples
+///
+/// ```no_run
+/// use tlq_client::TlqClient;
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), tlq_client::TlqError> {
+///     let client = TlqClient::new("localhost", 1337)?;
+///
+///     let response = client.add_message_detailed("hello").await?;
+///     println!("Added {} (request {:?})", response.value.id, response.meta.request_id);
+///
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Response<T> {
+    /// The operation's result, as returned by the simple variant
+    pub value: T,
+    /// Metadata taken from the response headers
+    pub meta: ResponseMeta,
+}
+
+impl<T> Response<T> {
+    /// Discards the metadata and returns the value.
+    pub fn into_value(self) -> T {
+        self.value
+    }
+
+    pub(crate) fn map<U>(self, f: impl FnOnce(T) -> U) -> Response<U> {
+        Response {
+            value: f(self.value),
+            meta: self.meta,
+        }
+    }
+}
+
+/// Metadata from the HTTP headers of a server response.
+///
+/// Fields are filled from these headers (matched case-insensitively), and are
+/// empty when the server doesn't send them:
+///
+/// | Field | Header |
+/// |-------|--------|
+/// | `request_id` | `X-Request-Id` |
+/// | `server_timing` | `Server-Timing` |
+/// | `warnings` | every `Warning` header, in order |
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ResponseMeta {
+    /// Identifier the server assigned to the request, useful for correlating logs
+    pub request_id: Option<String>,
+    /// Server-side timing information, unparsed
+    pub server_timing: Option<String>,
+    /// Warnings reported by the server
+    pub warnings: Vec<String>,
+}
+
+impl ResponseMeta {
+    // Collects the metadata from the header block of a raw HTTP response.
+    pub(crate) fn from_headers(headers: &str) -> Self {
+        let mut meta = Self::default();
+
+        for (name, value) in headers
+            .lines()
+            .skip(1)
+            .filter_map(|line| line.split_once(':'))
+        {
+            let name = name.trim();
+            let value = value.trim().to_string();
+            if name.eq_ignore_ascii_case("x-request-id") {
+                meta.request_id = Some(value);
+            } else if name.eq_ignore_ascii_case("server-timing") {
+                meta.server_timing = Some(value);
+            } else if name.eq_ignore_ascii_case("warning") {
+                meta.warnings.push(value);
+            }
+        }
+        meta
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_meta_from_headers() {
+        let headers = "HTTP/1.1 200 OK\r\n\
+                       x-request-id: req-42\r\n\
+                       Server-Timing: db;dur=5.3\r\n\
+                       Warning: 199 - \"queue nearly full\"\r\n\
+                       Warning: 199 - \"deprecated endpoint\"\r\n\
+                       Content-Length: 2";
+        let meta = ResponseMeta::from_headers(headers);
+
+        assert_eq!(meta.request_id.as_deref(), Some("req-42"));
+        assert_eq!(meta.server_timing.as_deref(), Some("db;dur=5.3"));
+        assert_eq!(
+            meta.warnings,
+            [
+                "199 - \"queue nearly full\"",
+                "199 - \"deprecated endpoint\""
+            ]
+        );
+        assert_eq!(
+            ResponseMeta::from_headers("HTTP/1.1 200 OK"),
+            ResponseMeta::default()
+        );
+    }
+}
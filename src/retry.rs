@@ -1,17 +1,40 @@
+use crate::config::BackoffKind;
+#[cfg(feature = "client")]
+use crate::config::{JitterKind, RetryObserver};
+#[cfg(feature = "client")]
+use std::collections::hash_map::RandomState;
+#[cfg(feature = "client")]
+use std::hash::BuildHasher;
 use std::time::Duration;
+#[cfg(feature = "client")]
 use tokio::time::sleep;
 
+/// Longest server-advised delay honored when no `max_delay` is set.
+#[cfg(feature = "client")]
+pub(crate) const MAX_ADVISED_DELAY: Duration = Duration::from_secs(60);
+
 /// Internal retry strategy with exponential backoff for TLQ client operations.
 ///
 /// This struct implements an exponential backoff retry mechanism that automatically
-/// retries failed operations up to a maximum number of attempts. The delay between
-/// retries doubles with each attempt: `base_delay × 2^attempt_number`.
+/// retries failed operations up to a maximum number of attempts. By default the delay
+/// between retries doubles with each attempt: `base_delay × 2^attempt_number`; see
+/// [`BackoffKind`] for the constant and linear alternatives.
 ///
 /// Used internally by [`TlqClient`](crate::TlqClient) to handle transient failures
 /// like network connectivity issues and timeouts.
+#[derive(Debug, Clone)]
 pub struct RetryStrategy {
     max_retries: u32,
     base_delay: Duration,
+    backoff: BackoffKind,
+    max_delay: Option<Duration>,
+    #[cfg(feature = "client")]
+    jitter: JitterKind,
+    #[cfg(feature = "client")]
+    observer: Option<RetryObserver>,
+    // Source of randomness for jitter: returns a duration in `0..max`
+    #[cfg(feature = "client")]
+    random: fn(Duration) -> Duration,
 }
 
 impl RetryStrategy {
@@ -25,57 +48,122 @@ impl RetryStrategy {
         Self {
             max_retries,
             base_delay,
+            backoff: BackoffKind::Exponential,
+            max_delay: None,
+            #[cfg(feature = "client")]
+            jitter: JitterKind::None,
+            #[cfg(feature = "client")]
+            observer: None,
+            #[cfg(feature = "client")]
+            random: random_delay,
         }
     }
 
-    /// Executes an async operation with automatic retry on failure.
-    ///
-    /// This method will execute the provided operation and retry it on failure
-    /// up to `max_retries` times. Between each retry attempt, it waits for an
-    /// exponentially increasing delay.
-    ///
-    /// # Arguments
-    ///
-    /// * `operation` - A closure that returns a Future yielding Result<T, E>
-    ///
-    /// # Returns
-    ///
-    /// Returns the first successful result, or the last error if all attempts fail.
+    /// Returns `true` if failed operations are retried at all.
+    #[cfg(feature = "client")]
+    pub fn is_enabled(&self) -> bool {
+        self.max_retries > 0
+    }
+
+    /// Sets how the delay grows between attempts (exponential by default).
+    pub fn with_backoff(mut self, backoff: BackoffKind) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    /// Caps each delay at `max_delay`, if set (uncapped by default).
+    pub fn with_max_delay(mut self, max_delay: Option<Duration>) -> Self {
+        self.max_delay = max_delay;
+        self
+    }
+
+    /// Sets how each delay is randomized (no jitter by default).
+    #[cfg(feature = "client")]
+    pub fn with_jitter(mut self, jitter: JitterKind) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Replaces the source of randomness used for jitter.
+    #[cfg(all(test, feature = "client"))]
+    pub fn with_random(mut self, random: fn(Duration) -> Duration) -> Self {
+        self.random = random;
+        self
+    }
+
+    /// Sets a callback notified of each retry and its delay before sleeping.
+    #[cfg(feature = "client")]
+    pub fn with_observer(mut self, observer: Option<RetryObserver>) -> Self {
+        self.observer = observer;
+        self
+    }
+
+    /// Executes an async operation, retrying errors for which `should_retry`
+    /// returns `true` up to `max_retries` times with the configured backoff;
+    /// any other error is returned immediately.
     ///
-    /// # Retry Behavior
+    /// Before retrying an error, waits at least the delay returned by
+    /// `min_delay` for it, such as a server's `Retry-After`. The longer of
+    /// that delay and the backoff wins.
     ///
-    /// - Attempt 0: No delay, execute immediately
-    /// - Attempt 1: Wait `base_delay × 2^0` = base_delay
-    /// - Attempt 2: Wait `base_delay × 2^1` = base_delay × 2
-    /// - Attempt 3: Wait `base_delay × 2^2` = base_delay × 4
-    /// - And so on...
-    pub async fn execute<F, Fut, T, E>(&self, mut operation: F) -> Result<T, E>
+    /// An advised delay longer than `max_delay` (or [`MAX_ADVISED_DELAY`] when
+    /// no cap is set) isn't waited for: the error is returned right away, so a
+    /// far-off `Retry-After` can't stall the caller.
+    #[cfg(feature = "client")]
+    pub async fn execute_advised<F, Fut, T, E, P, D>(
+        &self,
+        mut operation: F,
+        mut should_retry: P,
+        min_delay: D,
+    ) -> Result<T, E>
     where
         F: FnMut() -> Fut,
         Fut: std::future::Future<Output = Result<T, E>>,
         E: std::fmt::Debug,
+        P: FnMut(&E) -> bool,
+        D: Fn(&E) -> Option<Duration>,
     {
         let mut attempt = 0;
 
         loop {
-            match operation().await {
+            let err = match operation().await {
                 Ok(result) => return Ok(result),
-                Err(err) if attempt >= self.max_retries => {
-                    return Err(err);
-                }
-                Err(_) if attempt < self.max_retries => {
-                    let delay = self.calculate_delay(attempt);
-                    sleep(delay).await;
-                    attempt += 1;
-                }
-                Err(err) => return Err(err),
+                Err(err) => err,
+            };
+
+            let advised = min_delay(&err);
+            let advised_cap = self.max_delay.unwrap_or(MAX_ADVISED_DELAY);
+            if attempt >= self.max_retries
+                || advised.is_some_and(|min| min > advised_cap)
+                || !should_retry(&err)
+            {
+                return Err(err);
             }
+
+            let delay = self.jittered_delay(attempt);
+            let delay = advised.map_or(delay, |min| delay.max(min));
+            if let Some(observer) = &self.observer {
+                observer.notify(attempt + 1, delay);
+            }
+            sleep(delay).await;
+            attempt += 1;
         }
     }
 
-    /// Calculates the delay duration for a given retry attempt using exponential backoff.
+    /// Returns the total time spent sleeping between attempts when every
+    /// attempt fails, saturating at [`Duration::MAX`].
+    pub fn total_delay(&self) -> Duration {
+        (0..self.max_retries).fold(Duration::ZERO, |total, attempt| {
+            total.saturating_add(self.calculate_delay(attempt))
+        })
+    }
+
+    /// Calculates the delay duration for a given retry attempt.
     ///
-    /// The delay formula is: `base_delay × 2^attempt_number`
+    /// The delay formula depends on the [`BackoffKind`]:
+    /// - `Exponential`: `base_delay × 2^attempt_number`
+    /// - `Constant`: `base_delay`
+    /// - `Linear`: `base_delay × (attempt_number + 1)`
     ///
     /// # Arguments
     ///
@@ -87,21 +175,73 @@ impl RetryStrategy {
     ///
     /// # Examples
     ///
-    /// With `base_delay = 100ms`:
+    /// With `base_delay = 100ms` and exponential backoff:
     /// - Attempt 0: 100ms × 2^0 = 100ms
     /// - Attempt 1: 100ms × 2^1 = 200ms  
     /// - Attempt 2: 100ms × 2^2 = 400ms
+    ///
+    /// The multiplication saturates at [`Duration::MAX`] instead of overflowing,
+    /// so large base delays or attempt numbers never panic. The result is then
+    /// clamped to the maximum delay, if one is set.
     fn calculate_delay(&self, attempt: u32) -> Duration {
-        let multiplier = 2_u32.pow(attempt);
-        self.base_delay * multiplier
+        let delay = match self.backoff {
+            BackoffKind::Exponential => {
+                // Doubles in steps of at most 2^31 so the factor fits in a u32.
+                let mut delay = self.base_delay;
+                let mut remaining = attempt;
+                while remaining > 0 && !delay.is_zero() && delay != Duration::MAX {
+                    let step = remaining.min(31);
+                    delay = delay.saturating_mul(1 << step);
+                    remaining -= step;
+                }
+                delay
+            }
+            BackoffKind::Constant => self.base_delay,
+            BackoffKind::Linear => self.base_delay.saturating_mul(attempt.saturating_add(1)),
+        };
+        match self.max_delay {
+            Some(max) => delay.min(max),
+            None => delay,
+        }
+    }
+}
+
+impl RetryStrategy {
+    /// Applies the jitter mode to the delay for `attempt`. The result never
+    /// exceeds [`calculate_delay`](Self::calculate_delay), so `total_delay`
+    /// remains an upper bound.
+    #[cfg(feature = "client")]
+    pub(crate) fn jittered_delay(&self, attempt: u32) -> Duration {
+        let delay = self.calculate_delay(attempt);
+        match self.jitter {
+            JitterKind::None => delay,
+            JitterKind::Full => (self.random)(delay),
+            JitterKind::Equal => {
+                let half = delay / 2;
+                half + (self.random)(delay - half)
+            }
+        }
     }
 }
 
+/// Returns a random duration in `0..max`, or zero if `max` is zero.
+///
+/// Randomness comes from the per-instance keys of the standard library's
+/// [`RandomState`], which is plenty for spreading out timings.
+#[cfg(feature = "client")]
+pub(crate) fn random_delay(max: Duration) -> Duration {
+    let bits = RandomState::new().hash_one(()) >> 11;
+    max.mul_f64(bits as f64 / (1u64 << 53) as f64)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    #[cfg(feature = "client")]
     use std::sync::atomic::{AtomicU32, Ordering};
+    #[cfg(feature = "client")]
     use std::sync::Arc;
+    #[cfg(feature = "client")]
     use tokio::time::Instant;
 
     #[test]
@@ -115,6 +255,148 @@ mod tests {
         assert_eq!(strategy.calculate_delay(3), Duration::from_millis(800)); // 2^3 * 100
     }
 
+    #[test]
+    fn test_constant_backoff_calculation() {
+        let strategy =
+            RetryStrategy::new(3, Duration::from_millis(100)).with_backoff(BackoffKind::Constant);
+
+        assert_eq!(strategy.calculate_delay(0), Duration::from_millis(100));
+        assert_eq!(strategy.calculate_delay(1), Duration::from_millis(100));
+        assert_eq!(strategy.calculate_delay(2), Duration::from_millis(100));
+        assert_eq!(strategy.calculate_delay(3), Duration::from_millis(100));
+    }
+
+    #[test]
+    fn test_linear_backoff_calculation() {
+        let strategy =
+            RetryStrategy::new(3, Duration::from_millis(100)).with_backoff(BackoffKind::Linear);
+
+        assert_eq!(strategy.calculate_delay(0), Duration::from_millis(100)); // 1 * 100
+        assert_eq!(strategy.calculate_delay(1), Duration::from_millis(200)); // 2 * 100
+        assert_eq!(strategy.calculate_delay(2), Duration::from_millis(300)); // 3 * 100
+        assert_eq!(strategy.calculate_delay(3), Duration::from_millis(400)); // 4 * 100
+    }
+
+    #[cfg(feature = "client")]
+    #[test]
+    fn test_jittered_delay() {
+        // Deterministic stand-ins for the random source
+        fn none(_: Duration) -> Duration {
+            Duration::ZERO
+        }
+        fn most(max: Duration) -> Duration {
+            max - Duration::from_millis(1)
+        }
+
+        let strategy = RetryStrategy::new(3, Duration::from_millis(100));
+        assert_eq!(strategy.jittered_delay(2), Duration::from_millis(400));
+
+        let full = strategy.clone().with_jitter(JitterKind::Full);
+        assert_eq!(
+            full.clone().with_random(none).jittered_delay(2),
+            Duration::ZERO
+        );
+        assert_eq!(
+            full.with_random(most).jittered_delay(2),
+            Duration::from_millis(399)
+        );
+
+        let equal = strategy.with_jitter(JitterKind::Equal);
+        assert_eq!(
+            equal.clone().with_random(none).jittered_delay(2),
+            Duration::from_millis(200)
+        );
+        assert_eq!(
+            equal.with_random(most).jittered_delay(2),
+            Duration::from_millis(399)
+        );
+    }
+
+    #[cfg(feature = "client")]
+    #[test]
+    fn test_jitter_stays_within_backoff() {
+        let strategy = RetryStrategy::new(3, Duration::from_millis(100));
+        for jitter in [JitterKind::Full, JitterKind::Equal] {
+            let strategy = strategy.clone().with_jitter(jitter);
+            for _ in 0..100 {
+                let delay = strategy.jittered_delay(1);
+                assert!(delay <= Duration::from_millis(200));
+                if jitter == JitterKind::Equal {
+                    assert!(delay >= Duration::from_millis(100));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_default_backoff_is_exponential() {
+        let strategy = RetryStrategy::new(3, Duration::from_millis(100));
+        assert_eq!(strategy.backoff, BackoffKind::Exponential);
+    }
+
+    #[cfg(feature = "client")]
+    #[test]
+    fn test_is_enabled() {
+        assert!(RetryStrategy::new(1, Duration::from_millis(1)).is_enabled());
+        assert!(!RetryStrategy::new(0, Duration::from_millis(1)).is_enabled());
+    }
+
+    #[test]
+    fn test_large_delay_saturates() {
+        let hour = Duration::from_secs(3600);
+
+        let strategy = RetryStrategy::new(40, hour);
+        assert_eq!(strategy.calculate_delay(30), hour * 2_u32.pow(30));
+        assert_eq!(strategy.calculate_delay(60), Duration::MAX);
+        assert_eq!(strategy.calculate_delay(u32::MAX), Duration::MAX);
+
+        let strategy = RetryStrategy::new(40, Duration::from_nanos(1));
+        assert_eq!(strategy.calculate_delay(40), Duration::from_nanos(1 << 40));
+
+        let strategy = RetryStrategy::new(40, Duration::MAX).with_backoff(BackoffKind::Linear);
+        assert_eq!(strategy.calculate_delay(30), Duration::MAX);
+        assert_eq!(strategy.calculate_delay(u32::MAX), Duration::MAX);
+    }
+
+    #[test]
+    fn test_max_delay_caps_backoff() {
+        let cap = Duration::from_secs(30);
+        let strategy =
+            RetryStrategy::new(100, Duration::from_millis(100)).with_max_delay(Some(cap));
+        assert_eq!(strategy.calculate_delay(2), Duration::from_millis(400));
+        assert_eq!(strategy.calculate_delay(20), cap);
+        assert_eq!(strategy.calculate_delay(64), cap);
+        assert_eq!(strategy.calculate_delay(u32::MAX), cap);
+
+        let strategy = strategy.with_backoff(BackoffKind::Linear);
+        assert_eq!(strategy.calculate_delay(20), Duration::from_millis(2100));
+        assert_eq!(strategy.calculate_delay(64), Duration::from_millis(6500));
+        assert_eq!(strategy.calculate_delay(1000), cap);
+
+        // 100 retries of at most 30s each
+        let strategy = RetryStrategy::new(100, Duration::from_secs(3600)).with_max_delay(Some(cap));
+        assert_eq!(strategy.total_delay(), cap * 100);
+    }
+
+    #[test]
+    fn test_total_delay() {
+        let strategy = RetryStrategy::new(3, Duration::from_millis(100));
+        assert_eq!(strategy.total_delay(), Duration::from_millis(700)); // 100 + 200 + 400
+
+        let strategy =
+            RetryStrategy::new(3, Duration::from_millis(100)).with_backoff(BackoffKind::Linear);
+        assert_eq!(strategy.total_delay(), Duration::from_millis(600)); // 100 + 200 + 300
+
+        assert_eq!(
+            RetryStrategy::new(0, Duration::from_millis(100)).total_delay(),
+            Duration::ZERO
+        );
+        assert_eq!(
+            RetryStrategy::new(100, Duration::from_secs(3600)).total_delay(),
+            Duration::MAX
+        );
+    }
+
     #[test]
     fn test_zero_base_delay() {
         let strategy = RetryStrategy::new(2, Duration::from_millis(0));
@@ -124,6 +406,7 @@ mod tests {
         assert_eq!(strategy.calculate_delay(5), Duration::from_millis(0));
     }
 
+    #[cfg(feature = "client")]
     #[tokio::test]
     async fn test_immediate_success() {
         let strategy = RetryStrategy::new(3, Duration::from_millis(10));
@@ -131,19 +414,24 @@ mod tests {
 
         let call_count_clone = call_count.clone();
         let result = strategy
-            .execute(|| {
-                let counter = call_count_clone.clone();
-                async move {
-                    counter.fetch_add(1, Ordering::SeqCst);
-                    Ok::<&str, &str>("success")
-                }
-            })
+            .execute_advised(
+                || {
+                    let counter = call_count_clone.clone();
+                    async move {
+                        counter.fetch_add(1, Ordering::SeqCst);
+                        Ok::<&str, &str>("success")
+                    }
+                },
+                |_| true,
+                |_| None,
+            )
             .await;
 
         assert_eq!(result, Ok("success"));
         assert_eq!(call_count.load(Ordering::SeqCst), 1);
     }
 
+    #[cfg(feature = "client")]
     #[tokio::test]
     async fn test_success_after_retries() {
         let strategy = RetryStrategy::new(3, Duration::from_millis(1));
@@ -151,23 +439,28 @@ mod tests {
 
         let call_count_clone = call_count.clone();
         let result = strategy
-            .execute(|| {
-                let counter = call_count_clone.clone();
-                async move {
-                    let count = counter.fetch_add(1, Ordering::SeqCst);
-                    if count < 2 {
-                        Err("temporary failure")
-                    } else {
-                        Ok("success after retries")
+            .execute_advised(
+                || {
+                    let counter = call_count_clone.clone();
+                    async move {
+                        let count = counter.fetch_add(1, Ordering::SeqCst);
+                        if count < 2 {
+                            Err("temporary failure")
+                        } else {
+                            Ok("success after retries")
+                        }
                     }
-                }
-            })
+                },
+                |_| true,
+                |_| None,
+            )
             .await;
 
         assert_eq!(result, Ok("success after retries"));
         assert_eq!(call_count.load(Ordering::SeqCst), 3); // Failed twice, succeeded on 3rd
     }
 
+    #[cfg(feature = "client")]
     #[tokio::test]
     async fn test_max_retries_exceeded() {
         let strategy = RetryStrategy::new(2, Duration::from_millis(1));
@@ -175,19 +468,24 @@ mod tests {
 
         let call_count_clone = call_count.clone();
         let result = strategy
-            .execute(|| {
-                let counter = call_count_clone.clone();
-                async move {
-                    counter.fetch_add(1, Ordering::SeqCst);
-                    Err::<&str, &str>("always fails")
-                }
-            })
+            .execute_advised(
+                || {
+                    let counter = call_count_clone.clone();
+                    async move {
+                        counter.fetch_add(1, Ordering::SeqCst);
+                        Err::<&str, &str>("always fails")
+                    }
+                },
+                |_| true,
+                |_| None,
+            )
             .await;
 
         assert_eq!(result, Err("always fails"));
         assert_eq!(call_count.load(Ordering::SeqCst), 3); // Initial attempt + 2 retries
     }
 
+    #[cfg(feature = "client")]
     #[tokio::test]
     async fn test_zero_max_retries() {
         let strategy = RetryStrategy::new(0, Duration::from_millis(1));
@@ -195,26 +493,35 @@ mod tests {
 
         let call_count_clone = call_count.clone();
         let result = strategy
-            .execute(|| {
-                let counter = call_count_clone.clone();
-                async move {
-                    counter.fetch_add(1, Ordering::SeqCst);
-                    Err::<&str, &str>("fails immediately")
-                }
-            })
+            .execute_advised(
+                || {
+                    let counter = call_count_clone.clone();
+                    async move {
+                        counter.fetch_add(1, Ordering::SeqCst);
+                        Err::<&str, &str>("fails immediately")
+                    }
+                },
+                |_| true,
+                |_| None,
+            )
             .await;
 
         assert_eq!(result, Err("fails immediately"));
         assert_eq!(call_count.load(Ordering::SeqCst), 1); // Only initial attempt, no retries
     }
 
+    #[cfg(feature = "client")]
     #[tokio::test]
     async fn test_retry_timing() {
         let strategy = RetryStrategy::new(2, Duration::from_millis(50));
         let start_time = Instant::now();
 
         let result = strategy
-            .execute(|| async { Err::<&str, &str>("always fails") })
+            .execute_advised(
+                || async { Err::<&str, &str>("always fails") },
+                |_| true,
+                |_| None,
+            )
             .await;
 
         let elapsed = start_time.elapsed();
@@ -226,6 +533,116 @@ mod tests {
         assert_eq!(result, Err("always fails"));
     }
 
+    #[cfg(feature = "client")]
+    #[tokio::test]
+    async fn test_rejected_error_stops_retries() {
+        let strategy = RetryStrategy::new(3, Duration::from_millis(1));
+        let call_count = Arc::new(AtomicU32::new(0));
+
+        let call_count_clone = call_count.clone();
+        let result = strategy
+            .execute_advised(
+                || {
+                    let counter = call_count_clone.clone();
+                    async move {
+                        let count = counter.fetch_add(1, Ordering::SeqCst);
+                        if count == 0 {
+                            Err::<&str, &str>("transient")
+                        } else {
+                            Err("fatal")
+                        }
+                    }
+                },
+                |err| *err == "transient",
+                |_| None,
+            )
+            .await;
+
+        assert_eq!(result, Err("fatal"));
+        assert_eq!(call_count.load(Ordering::SeqCst), 2);
+    }
+
+    #[cfg(feature = "client")]
+    #[tokio::test]
+    async fn test_execute_advised_waits_for_min_delay() {
+        let strategy = RetryStrategy::new(2, Duration::from_millis(1));
+        let call_count = AtomicU32::new(0);
+
+        let start = Instant::now();
+        let result = strategy
+            .execute_advised(
+                || async {
+                    match call_count.fetch_add(1, Ordering::SeqCst) {
+                        0 => Err("busy"),
+                        _ => Ok("done"),
+                    }
+                },
+                |_| true,
+                |err| (*err == "busy").then_some(Duration::from_millis(50)),
+            )
+            .await;
+
+        assert_eq!(result, Ok("done"));
+        assert_eq!(call_count.load(Ordering::SeqCst), 2);
+        assert!(start.elapsed() >= Duration::from_millis(50));
+    }
+
+    #[cfg(feature = "client")]
+    #[tokio::test]
+    async fn test_execute_advised_fails_fast_past_cap() {
+        let call_count = AtomicU32::new(0);
+        let operation = || async {
+            call_count.fetch_add(1, Ordering::SeqCst);
+            Err::<(), _>("busy")
+        };
+
+        // Without a max_delay, advised delays beyond MAX_ADVISED_DELAY fail fast
+        let strategy = RetryStrategy::new(3, Duration::from_millis(1));
+        let start = Instant::now();
+        let result = strategy
+            .execute_advised(operation, |_| true, |_| Some(Duration::from_secs(86400)))
+            .await;
+        assert_eq!(result, Err("busy"));
+        assert_eq!(call_count.load(Ordering::SeqCst), 1);
+        assert!(start.elapsed() < Duration::from_secs(1));
+
+        // With one, the cap is max_delay
+        let strategy = RetryStrategy::new(3, Duration::from_millis(1))
+            .with_max_delay(Some(Duration::from_millis(10)));
+        let result = strategy
+            .execute_advised(operation, |_| true, |_| Some(Duration::from_millis(20)))
+            .await;
+        assert_eq!(result, Err("busy"));
+        assert_eq!(call_count.load(Ordering::SeqCst), 2);
+    }
+
+    #[cfg(feature = "client")]
+    #[tokio::test]
+    async fn test_non_retryable_returns_immediately() {
+        use crate::TlqError;
+
+        // A delay long enough that any sleep would be noticed
+        let strategy = RetryStrategy::new(3, Duration::from_secs(10));
+        let call_count = AtomicU32::new(0);
+
+        let start = Instant::now();
+        let result = strategy
+            .execute_advised(
+                || async {
+                    call_count.fetch_add(1, Ordering::SeqCst);
+                    Err::<(), _>(TlqError::Validation("bad input".to_string()))
+                },
+                TlqError::is_retryable,
+                |_| None,
+            )
+            .await;
+
+        assert!(matches!(result, Err(TlqError::Validation(_))));
+        assert_eq!(call_count.load(Ordering::SeqCst), 1);
+        assert!(start.elapsed() < Duration::from_secs(1));
+    }
+
+    #[cfg(feature = "client")]
     #[tokio::test]
     async fn test_retry_with_different_error_types() {
         #[derive(Debug, PartialEq)]
@@ -239,18 +656,22 @@ mod tests {
 
         let call_count_clone = call_count.clone();
         let result = strategy
-            .execute(|| {
-                let counter = call_count_clone.clone();
-                async move {
-                    let count = counter.fetch_add(1, Ordering::SeqCst);
-                    match count {
-                        0 => Err(TestError::Recoverable),
-                        1 => Err(TestError::Fatal),
-                        2 => Ok("success on third attempt"),
-                        _ => panic!("Should not reach more than 3 attempts"),
+            .execute_advised(
+                || {
+                    let counter = call_count_clone.clone();
+                    async move {
+                        let count = counter.fetch_add(1, Ordering::SeqCst);
+                        match count {
+                            0 => Err(TestError::Recoverable),
+                            1 => Err(TestError::Fatal),
+                            2 => Ok("success on third attempt"),
+                            _ => panic!("Should not reach more than 3 attempts"),
+                        }
                     }
-                }
-            })
+                },
+                |_| true,
+                |_| None,
+            )
             .await;
 
         // The strategy should continue retrying through all error types
@@ -258,4 +679,40 @@ mod tests {
         assert_eq!(result, Ok("success on third attempt"));
         assert_eq!(call_count.load(Ordering::SeqCst), 3);
     }
+
+    #[cfg(feature = "client")]
+    #[test]
+    fn test_random_delay_bounds() {
+        assert_eq!(random_delay(Duration::ZERO), Duration::ZERO);
+
+        let max = Duration::from_millis(10);
+        for _ in 0..100 {
+            assert!(random_delay(max) < max);
+        }
+    }
+
+    #[cfg(feature = "client")]
+    #[tokio::test]
+    async fn test_observer_sees_each_delay() {
+        use std::sync::Mutex;
+
+        let observed = Arc::new(Mutex::new(Vec::new()));
+        let sink = observed.clone();
+        let strategy = RetryStrategy::new(3, Duration::from_millis(1)).with_observer(Some(
+            RetryObserver::new(move |retry, delay| sink.lock().unwrap().push((retry, delay))),
+        ));
+
+        let result: Result<(), &str> = strategy
+            .execute_advised(|| async { Err("down") }, |_| true, |_| None)
+            .await;
+        assert!(result.is_err());
+        assert_eq!(
+            *observed.lock().unwrap(),
+            [
+                (1, Duration::from_millis(1)),
+                (2, Duration::from_millis(2)),
+                (3, Duration::from_millis(4)),
+            ]
+        );
+    }
 }
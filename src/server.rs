@@ -0,0 +1,73 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// What a TLQ server reports about itself from its `/hello` endpoint.
+///
+/// Returned by [`TlqClient::health_check_detailed`](crate::TlqClient::health_check_detailed).
+/// Servers that answer with a JSON object can include their version and
+/// uptime; servers that answer with a plain greeting such as `"Hello World"`
+/// only fill in `message`.
+///
+/// # Examples
+///
+/// ```
+/// use tlq_client::HelloInfo;
+///
+/// let info = HelloInfo {
+///     message: "Hello World".to_string(),
+///     version: Some("0.3.0".to_string()),
+///     uptime: None,
+/// };
+/// assert_eq!(info.version.as_deref(), Some("0.3.0"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct HelloInfo {
+    /// The server's greeting or status message
+    pub message: String,
+    /// The server version, if reported
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub version: Option<String>,
+    /// Seconds since the server started, if reported
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub uptime: Option<u64>,
+}
+
+#[cfg(feature = "client")]
+impl HelloInfo {
+    // Parses a `/hello` body: a JSON object, a JSON string, or plain text.
+    pub(crate) fn from_body(body: &str) -> Self {
+        if let Ok(info) = serde_json::from_str::<HelloInfo>(body) {
+            return info;
+        }
+        let message =
+            serde_json::from_str::<String>(body).unwrap_or_else(|_| body.trim().to_string());
+        HelloInfo {
+            message,
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(all(test, feature = "client"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_body() {
+        assert_eq!(
+            HelloInfo::from_body("\"Hello World\""),
+            HelloInfo {
+                message: "Hello World".to_string(),
+                ..Default::default()
+            }
+        );
+        assert_eq!(HelloInfo::from_body("Hello World\n").message, "Hello World");
+
+        let info = HelloInfo::from_body(r#"{"message":"ok","version":"0.3.0","uptime":42}"#);
+        assert_eq!(info.message, "ok");
+        assert_eq!(info.version.as_deref(), Some("0.3.0"));
+        assert_eq!(info.uptime, Some(42));
+    }
+}
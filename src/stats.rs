@@ -0,0 +1,139 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+/// A snapshot of a client's request statistics.
+///
+/// Returned by [`TlqClient::stats`](crate::TlqClient::stats). Counters cover
+/// all clones of a client and accumulate from its creation or from the last
+/// [`reset_stats`](crate::TlqClient::reset_stats).
+///
+/// Every HTTP attempt counts as one request, so a call that succeeds after two
+/// retries adds three `requests`, two `failures`, one `success` and two
+/// `retries`. Health checks aren't counted.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct ClientStats {
+    /// Number of HTTP requests sent, including retries
+    pub requests: u64,
+    /// Number of requests that completed successfully
+    pub successes: u64,
+    /// Number of requests that failed, including timeouts
+    pub failures: u64,
+    /// Number of retries performed after a failed request
+    pub retries: u64,
+    /// Number of requests that failed with [`TlqError::Timeout`](crate::TlqError::Timeout)
+    pub timeouts: u64,
+    /// Total bytes written to the server, including HTTP headers
+    pub bytes_sent: u64,
+    /// Total bytes read from the server, including HTTP headers
+    pub bytes_received: u64,
+    /// Average time per request, or zero if no request was made
+    pub avg_latency: Duration,
+}
+
+// Shared counters behind `ClientStats`, updated by every clone of a client.
+#[derive(Debug, Default)]
+pub(crate) struct AtomicStats {
+    requests: AtomicU64,
+    successes: AtomicU64,
+    failures: AtomicU64,
+    retries: AtomicU64,
+    timeouts: AtomicU64,
+    bytes_sent: AtomicU64,
+    bytes_received: AtomicU64,
+    latency_nanos: AtomicU64,
+}
+
+impl AtomicStats {
+    pub(crate) fn record_request(&self, latency: Duration, success: bool, timed_out: bool) {
+        self.requests.fetch_add(1, Ordering::Relaxed);
+        if success {
+            self.successes.fetch_add(1, Ordering::Relaxed);
+        } else {
+            self.failures.fetch_add(1, Ordering::Relaxed);
+        }
+        if timed_out {
+            self.timeouts.fetch_add(1, Ordering::Relaxed);
+        }
+        let nanos = u64::try_from(latency.as_nanos()).unwrap_or(u64::MAX);
+        self.latency_nanos.fetch_add(nanos, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_retry(&self) {
+        self.retries.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_sent(&self, bytes: usize) {
+        self.bytes_sent.fetch_add(bytes as u64, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_received(&self, bytes: usize) {
+        self.bytes_received
+            .fetch_add(bytes as u64, Ordering::Relaxed);
+    }
+
+    pub(crate) fn snapshot(&self) -> ClientStats {
+        let requests = self.requests.load(Ordering::Relaxed);
+        let latency_nanos = self.latency_nanos.load(Ordering::Relaxed);
+
+        ClientStats {
+            requests,
+            successes: self.successes.load(Ordering::Relaxed),
+            failures: self.failures.load(Ordering::Relaxed),
+            retries: self.retries.load(Ordering::Relaxed),
+            timeouts: self.timeouts.load(Ordering::Relaxed),
+            bytes_sent: self.bytes_sent.load(Ordering::Relaxed),
+            bytes_received: self.bytes_received.load(Ordering::Relaxed),
+            avg_latency: latency_nanos
+                .checked_div(requests)
+                .map(Duration::from_nanos)
+                .unwrap_or_default(),
+        }
+    }
+
+    pub(crate) fn reset(&self) {
+        for counter in [
+            &self.requests,
+            &self.successes,
+            &self.failures,
+            &self.retries,
+            &self.timeouts,
+            &self.bytes_sent,
+            &self.bytes_received,
+            &self.latency_nanos,
+        ] {
+            counter.store(0, Ordering::Relaxed);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snapshot_and_reset() {
+        let stats = AtomicStats::default();
+        stats.record_request(Duration::from_millis(10), true, false);
+        stats.record_request(Duration::from_millis(30), false, true);
+        stats.record_retry();
+        stats.record_sent(100);
+        stats.record_received(250);
+
+        let snapshot = stats.snapshot();
+        assert_eq!(snapshot.requests, 2);
+        assert_eq!(snapshot.successes, 1);
+        assert_eq!(snapshot.failures, 1);
+        assert_eq!(snapshot.retries, 1);
+        assert_eq!(snapshot.timeouts, 1);
+        assert_eq!(snapshot.bytes_sent, 100);
+        assert_eq!(snapshot.bytes_received, 250);
+        assert_eq!(snapshot.avg_latency, Duration::from_millis(20));
+
+        stats.reset();
+        assert_eq!(stats.snapshot(), ClientStats::default());
+    }
+}
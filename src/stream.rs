@@ -0,0 +1,436 @@
+use crate::{
+    client::{TlqClient, CONSUMER_POLL_INTERVAL},
+    error::{Result, TlqError},
+    message::Message,
+    stats::AtomicStats,
+    transport::Transport,
+};
+use futures_core::Stream;
+use std::collections::VecDeque;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{ready, Context, Poll};
+use std::time::Duration;
+use tokio::io::AsyncReadExt;
+use tokio::time::timeout;
+
+type Fetch = Pin<Box<dyn Future<Output = Result<(Vec<Message>, Option<BodyReader>)>> + Send>>;
+type Read = Pin<Box<dyn Future<Output = (BodyReader, Result<Option<Message>>)> + Send>>;
+
+/// A [`Stream`] of messages fetched from the queue in batches.
+///
+/// Returned by [`TlqClient::messages`]. The stream fetches up to `batch_size`
+/// messages with [`get_messages`](TlqClient::get_messages), yields them one by
+/// one, and fetches the next batch only once the buffered messages have been
+/// consumed, so it never claims more than one batch ahead of the consumer.
+/// While the queue is empty it polls again every 100ms.
+///
+/// A failed fetch is yielded as an `Err` item; the stream itself doesn't end,
+/// and the next poll fetches again after a short pause. With
+/// [`incremental_parse`](crate::ConfigBuilder::incremental_parse), messages
+/// are yielded while their batch is still being read, and a batch that
+/// breaks off midway ends with such an `Err` item. The stream never
+/// finishes on its own, so stop it with a combinator such as
+/// [`take`](https://docs.rs/futures/latest/futures/stream/trait.StreamExt.html#method.take)
+/// or by dropping it. Fetched messages are in the `Processing` state; messages
+/// still buffered when the stream is dropped stay locked until their lock
+/// expires.
+///
+/// Requires the `stream` feature.
+///
+/// # Examples
+///
+/// ```no_run
+/// use futures_util::StreamExt;
+/// use tlq_client::TlqClient;
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), tlq_client::TlqError> {
+///     let client = TlqClient::new("localhost", 1337)?;
+///
+///     let mut messages = client.messages(10);
+///     while let Some(message) = messages.next().await {
+///         let message = message?;
+///         println!("Processing: {}", message.body);
+///         client.delete_message(message.id).await?;
+///     }
+///
+///     Ok(())
+/// }
+/// ```
+pub struct MessageStream {
+    client: TlqClient,
+    batch_size: u32,
+    buffer: VecDeque<Message>,
+    reader: Option<BodyReader>,
+    fetch: Option<Fetch>,
+    read: Option<Read>,
+    failed: bool,
+}
+
+impl MessageStream {
+    pub(crate) fn new(client: TlqClient, batch_size: u32) -> Self {
+        Self {
+            client,
+            batch_size: batch_size.max(1),
+            buffer: VecDeque::new(),
+            reader: None,
+            fetch: None,
+            read: None,
+            failed: false,
+        }
+    }
+
+    // Fetches the next non-empty batch, pausing first if the last fetch failed.
+    // A batch still being read comes with its first message parsed.
+    fn fetch(&self) -> Fetch {
+        let client = self.client.clone();
+        let batch_size = self.batch_size;
+        let pause = self.failed;
+
+        Box::pin(async move {
+            if pause {
+                tokio::time::sleep(CONSUMER_POLL_INTERVAL).await;
+            }
+            loop {
+                match client.get_message_body(batch_size).await? {
+                    MessageBody::Parsed(messages) if !messages.is_empty() => {
+                        return Ok((messages, None));
+                    }
+                    MessageBody::Reading(mut reader) => {
+                        if let Some(message) = reader.next().await? {
+                            return Ok((vec![message], Some(reader)));
+                        }
+                    }
+                    MessageBody::Parsed(_) => {}
+                }
+                tokio::time::sleep(CONSUMER_POLL_INTERVAL).await;
+            }
+        })
+    }
+}
+
+impl Stream for MessageStream {
+    type Item = Result<Message>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        if let Some(message) = this.buffer.pop_front() {
+            return Poll::Ready(Some(Ok(message)));
+        }
+
+        if let Some(reader) = this.reader.take() {
+            this.read = Some(Box::pin(async move {
+                let mut reader = reader;
+                let result = reader.next().await;
+                (reader, result)
+            }));
+        }
+        if let Some(read) = this.read.as_mut() {
+            let (reader, result) = ready!(read.as_mut().poll(cx));
+            this.read = None;
+            this.failed = result.is_err();
+            match result {
+                Ok(Some(message)) => {
+                    this.reader = Some(reader);
+                    return Poll::Ready(Some(Ok(message)));
+                }
+                // The batch is done; fetch the next one
+                Ok(None) => {}
+                Err(err) => return Poll::Ready(Some(Err(err))),
+            }
+        }
+
+        if this.fetch.is_none() {
+            this.fetch = Some(this.fetch());
+        }
+        let fetch = this.fetch.as_mut().expect("fetch was just set");
+        let result = ready!(fetch.as_mut().poll(cx));
+        this.fetch = None;
+
+        this.failed = result.is_err();
+        match result {
+            Ok((messages, reader)) => {
+                this.buffer.extend(messages);
+                this.reader = reader;
+                Poll::Ready(this.buffer.pop_front().map(Ok))
+            }
+            Err(err) => Poll::Ready(Some(Err(err))),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.buffer.len(), None)
+    }
+}
+
+// A batch of messages for `MessageStream`: parsed from a complete response,
+// or still being read with `incremental_parse`.
+pub(crate) enum MessageBody {
+    Parsed(Vec<Message>),
+    Reading(BodyReader),
+}
+
+// Parses the messages of a JSON array response body as it is read off the
+// socket. Starts after the opening `[`; only the unparsed part of the body is
+// buffered.
+pub(crate) struct BodyReader {
+    stream: Transport,
+    buf: Vec<u8>,
+    // Body bytes still to be read, or `None` if the body ends with the connection
+    remaining: Option<usize>,
+    after_message: bool,
+    done: bool,
+    read_timeout: Duration,
+    stats: Arc<AtomicStats>,
+}
+
+impl BodyReader {
+    pub(crate) fn new(
+        stream: Transport,
+        buf: Vec<u8>,
+        remaining: Option<usize>,
+        read_timeout: Duration,
+        stats: Arc<AtomicStats>,
+    ) -> Self {
+        Self {
+            stream,
+            buf,
+            remaining,
+            after_message: false,
+            done: false,
+            read_timeout,
+            stats,
+        }
+    }
+
+    // The next message, or `None` once the closing `]` has been read.
+    async fn next(&mut self) -> Result<Option<Message>> {
+        while !self.done {
+            let start = self.buf.iter().position(|b| !b.is_ascii_whitespace());
+            if let Some(start) = start {
+                match self.buf[start] {
+                    b']' => {
+                        self.done = true;
+                        break;
+                    }
+                    b',' if self.after_message => {
+                        self.buf.drain(..=start);
+                        self.after_message = false;
+                        continue;
+                    }
+                    byte if self.after_message => {
+                        return Err(TlqError::Connection(format!(
+                            "Expected ',' or ']' in message list, found '{}'",
+                            byte.escape_ascii()
+                        )));
+                    }
+                    _ => {
+                        let mut messages = serde_json::Deserializer::from_slice(&self.buf[start..])
+                            .into_iter::<Message>();
+                        match messages.next() {
+                            Some(Ok(message)) => {
+                                let end = start + messages.byte_offset();
+                                self.buf.drain(..end);
+                                self.after_message = true;
+                                return Ok(Some(message));
+                            }
+                            // The message hasn't fully arrived yet
+                            Some(Err(err)) if err.is_eof() => {}
+                            Some(Err(err)) => return Err(err.into()),
+                            None => {}
+                        }
+                    }
+                }
+            }
+
+            if !self.fill().await? {
+                return Err(TlqError::Connection(
+                    "Connection closed in the middle of the message list".to_string(),
+                ));
+            }
+        }
+        Ok(None)
+    }
+
+    // Reads more of the body into the buffer. Returns `false` at its end.
+    async fn fill(&mut self) -> Result<bool> {
+        if self.remaining == Some(0) {
+            return Ok(false);
+        }
+        let mut chunk = [0u8; 8192];
+        let limit = self.remaining.map_or(chunk.len(), |n| n.min(chunk.len()));
+        let n = timeout(self.read_timeout, self.stream.read(&mut chunk[..limit]))
+            .await
+            .map_err(|_| TlqError::Timeout(self.read_timeout.as_millis() as u64))??;
+        self.stats.record_received(n);
+        if let Some(remaining) = &mut self.remaining {
+            *remaining -= n;
+        }
+        self.buf.extend_from_slice(&chunk[..n]);
+        Ok(n > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{message_json, mock_server, DROP_CONNECTION};
+    use crate::{ConfigBuilder, TlqError};
+    use futures_util::StreamExt;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use uuid::Uuid;
+
+    #[tokio::test]
+    async fn test_messages_yields_batches_one_by_one() {
+        let ids: Vec<Uuid> = (0..5).map(|_| Uuid::now_v7()).collect();
+        let batches = ids.clone();
+        let calls = Arc::new(AtomicU32::new(0));
+        let counter = calls.clone();
+        let (port, captured) = mock_server(move |_, _| {
+            let batch = match counter.fetch_add(1, Ordering::SeqCst) {
+                0 => &batches[..3],
+                1 => &[][..],
+                _ => &batches[3..],
+            };
+            let messages: Vec<_> = batch
+                .iter()
+                .map(|id| message_json(*id, "body", "Processing"))
+                .collect();
+            (200, format!("[{}]", messages.join(",")))
+        })
+        .await;
+        let client = TlqClient::new("127.0.0.1", port).unwrap();
+
+        let mut stream = client.messages(3);
+        assert_eq!(stream.next().await.unwrap().unwrap().id, ids[0]);
+        // The rest of the batch is buffered; nothing is fetched ahead
+        assert_eq!(stream.size_hint(), (2, None));
+        assert_eq!(captured.lock().unwrap().len(), 1);
+        assert_eq!(captured.lock().unwrap()[0].body, r#"{"count":3}"#);
+
+        let rest: Vec<Uuid> = stream.take(4).map(|m| m.unwrap().id).collect().await;
+        assert_eq!(rest, ids[1..]);
+        // An empty poll between the two batches
+        assert_eq!(captured.lock().unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_messages_yields_errors_and_continues() {
+        let id = Uuid::now_v7();
+        let calls = Arc::new(AtomicU32::new(0));
+        let counter = calls.clone();
+        let (port, _) = mock_server(move |_, _| {
+            if counter.fetch_add(1, Ordering::SeqCst) == 0 {
+                (DROP_CONNECTION, String::new())
+            } else {
+                (200, format!("[{}]", message_json(id, "body", "Processing")))
+            }
+        })
+        .await;
+        let client = TlqClient::with_config(
+            ConfigBuilder::new()
+                .host("127.0.0.1")
+                .port(port)
+                .max_retries(0)
+                .build(),
+        );
+
+        let mut stream = client.messages(0);
+        assert!(matches!(
+            stream.next().await,
+            Some(Err(TlqError::Connection(_)))
+        ));
+        assert_eq!(stream.next().await.unwrap().unwrap().id, id);
+    }
+
+    // Serves one `/get`, answering with `head` followed by the parts of
+    // `body`, each sent once the previous receiver fires.
+    async fn incremental_server(
+        head: String,
+        body: Vec<(String, Option<tokio::sync::oneshot::Receiver<()>>)>,
+    ) -> u16 {
+        use tokio::io::AsyncWriteExt;
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut request = [0u8; 4096];
+            let _ = stream.read(&mut request).await;
+            stream.write_all(head.as_bytes()).await.unwrap();
+            for (part, ready) in body {
+                if let Some(ready) = ready {
+                    let _ = ready.await;
+                }
+                stream.write_all(part.as_bytes()).await.unwrap();
+            }
+        });
+        port
+    }
+
+    fn incremental_client(port: u16) -> TlqClient {
+        TlqClient::with_config(
+            ConfigBuilder::new()
+                .host("127.0.0.1")
+                .port(port)
+                .incremental_parse(true)
+                .build(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_incremental_parse_yields_before_body_ends() {
+        let (first, second) = (Uuid::now_v7(), Uuid::now_v7());
+        let (sent_rest, rest) = tokio::sync::oneshot::channel();
+        let port = incremental_server(
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nConnection: close\r\n\r\n"
+                .to_string(),
+            vec![
+                (
+                    format!("[{},", message_json(first, "a", "Processing")),
+                    None,
+                ),
+                (
+                    format!(" {} ]", message_json(second, "b", "Processing")),
+                    Some(rest),
+                ),
+            ],
+        )
+        .await;
+
+        let mut stream = incremental_client(port).messages(10);
+        // The rest of the body is only sent once the first message was yielded
+        let message = tokio::time::timeout(Duration::from_secs(5), stream.next())
+            .await
+            .expect("first message wasn't yielded before the body ended");
+        assert_eq!(message.unwrap().unwrap().id, first);
+        sent_rest.send(()).unwrap();
+        assert_eq!(stream.next().await.unwrap().unwrap().id, second);
+    }
+
+    #[tokio::test]
+    async fn test_incremental_parse_error_after_messages() {
+        let id = Uuid::now_v7();
+        let body = format!("[{},{{\"id\":", message_json(id, "a", "Processing"));
+        // The declared length promises more than the server sends
+        let port = incremental_server(
+            format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n",
+                body.len() + 100
+            ),
+            vec![(body, None)],
+        )
+        .await;
+
+        let mut stream = incremental_client(port).messages(10);
+        assert_eq!(stream.next().await.unwrap().unwrap().id, id);
+        match stream.next().await {
+            Some(Err(TlqError::Connection(msg))) => {
+                assert!(msg.contains("middle of the message list"))
+            }
+            other => panic!("Expected Connection error, got {:?}", other),
+        }
+    }
+}
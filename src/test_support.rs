@@ -0,0 +1,214 @@
+// Test-only helpers shared by the unit tests of several modules.
+
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use uuid::Uuid;
+
+/// A request received by the mock server.
+#[derive(Debug, Clone)]
+pub struct CapturedRequest {
+    /// Address the client connected from
+    pub peer: SocketAddr,
+    pub path: String,
+    pub headers: String,
+    pub body: String,
+}
+
+impl CapturedRequest {
+    /// Returns the value of header `name` (case-insensitive), if present.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .lines()
+            .filter_map(|line| line.split_once(':'))
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.trim())
+    }
+}
+
+/// Requests captured by the mock server, in arrival order.
+pub type Captured = Arc<Mutex<Vec<CapturedRequest>>>;
+
+/// Spawns a minimal HTTP server on an ephemeral localhost port.
+///
+/// Every incoming request is recorded and answered with the `(status, body)`
+/// pair returned by `handler`, after which the connection is closed. A status
+/// of [`DROP_CONNECTION`] closes the connection without answering, which the
+/// client sees as a retryable connection error.
+pub async fn mock_server<F>(handler: F) -> (u16, Captured)
+where
+    F: Fn(&str, &str) -> (u16, String) + Send + Sync + 'static,
+{
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    serve(listener, handler)
+}
+
+/// Handler status that makes the mock server hang up instead of responding.
+pub const DROP_CONNECTION: u16 = 0;
+
+/// Like [`mock_server`], but serves on an already bound listener.
+pub fn serve<F>(listener: TcpListener, handler: F) -> (u16, Captured)
+where
+    F: Fn(&str, &str) -> (u16, String) + Send + Sync + 'static,
+{
+    let port = listener.local_addr().unwrap().port();
+    let captured: Captured = Arc::new(Mutex::new(Vec::new()));
+    let handler = Arc::new(handler);
+
+    let requests = captured.clone();
+    tokio::spawn(async move {
+        loop {
+            let Ok((mut stream, _)) = listener.accept().await else {
+                return;
+            };
+            let handler = handler.clone();
+            let requests = requests.clone();
+            tokio::spawn(async move {
+                let request = read_request(&mut stream).await;
+                let (status, response_body) = handler(&request.path, &request.body);
+                requests.lock().unwrap().push(request);
+                if status != DROP_CONNECTION {
+                    let response = http_response(status, &response_body);
+                    let _ = stream.write_all(response.as_bytes()).await;
+                }
+                let _ = stream.shutdown().await;
+            });
+        }
+    });
+
+    (port, captured)
+}
+
+/// Like [`mock_server`], but answers any number of requests per connection
+/// and keeps it open until the client closes it. Also returns the number of
+/// connections accepted so far.
+pub async fn keep_alive_server<F>(handler: F) -> (u16, Captured, Arc<AtomicUsize>)
+where
+    F: Fn(&str, &str) -> (u16, String) + Send + Sync + 'static,
+{
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let captured: Captured = Arc::new(Mutex::new(Vec::new()));
+    let connections = Arc::new(AtomicUsize::new(0));
+    let handler = Arc::new(handler);
+
+    let requests = captured.clone();
+    let accepted = connections.clone();
+    tokio::spawn(async move {
+        loop {
+            let Ok((mut stream, _)) = listener.accept().await else {
+                return;
+            };
+            accepted.fetch_add(1, Ordering::SeqCst);
+            let handler = handler.clone();
+            let requests = requests.clone();
+            tokio::spawn(async move {
+                let mut buf = Vec::new();
+                loop {
+                    let request = read_request_buffered(&mut stream, &mut buf).await;
+                    if request.path.is_empty() {
+                        return;
+                    }
+                    let (status, response_body) = handler(&request.path, &request.body);
+                    requests.lock().unwrap().push(request);
+                    let response = http_response(status, &response_body)
+                        .replace("Connection: close", "Connection: keep-alive");
+                    if stream.write_all(response.as_bytes()).await.is_err() {
+                        return;
+                    }
+                }
+            });
+        }
+    });
+
+    (port, captured, connections)
+}
+
+/// Formats a complete `Connection: close` HTTP response.
+pub fn http_response(status: u16, body: &str) -> String {
+    let reason = if status == 200 { "OK" } else { "Mock" };
+    format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        reason,
+        body.len(),
+        body
+    )
+}
+
+/// Serializes a message in the shape returned by the TLQ server.
+pub fn message_json(id: Uuid, body: &str, state: &str) -> String {
+    serde_json::json!({
+        "id": id,
+        "body": body,
+        "state": state,
+        "lock_until": null,
+        "retry_count": 0
+    })
+    .to_string()
+}
+
+async fn read_request(stream: &mut tokio::net::TcpStream) -> CapturedRequest {
+    read_request_buffered(stream, &mut Vec::new()).await
+}
+
+// Reads one request, starting with the bytes in `buf` and leaving any bytes
+// read past the request there.
+async fn read_request_buffered(
+    stream: &mut tokio::net::TcpStream,
+    buf: &mut Vec<u8>,
+) -> CapturedRequest {
+    let peer = stream.peer_addr().unwrap();
+    let mut chunk = [0u8; 4096];
+
+    let header_end = loop {
+        if let Some(pos) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+            break pos;
+        }
+        let n = stream.read(&mut chunk).await.unwrap_or(0);
+        if n == 0 {
+            return CapturedRequest {
+                peer,
+                path: String::new(),
+                headers: String::new(),
+                body: String::new(),
+            };
+        }
+        buf.extend_from_slice(&chunk[..n]);
+    };
+
+    let headers = String::from_utf8_lossy(&buf[..header_end]).to_string();
+    let path = headers
+        .lines()
+        .next()
+        .and_then(|line| line.split_whitespace().nth(1))
+        .unwrap_or_default()
+        .to_string();
+    let content_length = headers
+        .lines()
+        .filter_map(|line| line.split_once(':'))
+        .find(|(name, _)| name.eq_ignore_ascii_case("content-length"))
+        .and_then(|(_, value)| value.trim().parse::<usize>().ok())
+        .unwrap_or(0);
+
+    let body_start = header_end + 4;
+    while buf.len() < body_start + content_length {
+        let n = stream.read(&mut chunk).await.unwrap_or(0);
+        if n == 0 {
+            break;
+        }
+        buf.extend_from_slice(&chunk[..n]);
+    }
+
+    let rest = buf.split_off((body_start + content_length).min(buf.len()));
+    let body = String::from_utf8_lossy(&buf[body_start..]).to_string();
+    *buf = rest;
+    CapturedRequest {
+        peer,
+        path,
+        headers,
+        body,
+    }
+}
@@ -0,0 +1,173 @@
+//! Utilities for testing code that uses TLQ, enabled by the `testing` feature.
+//!
+//! These helpers talk to a real server and are meant for end-to-end tests of
+//! your own setup, for example to check that messages survive a producer and
+//! consumer round trip in order.
+
+use crate::{client::TlqClient, error::TlqError};
+use thiserror::Error;
+use uuid::Uuid;
+
+const MARKER_PREFIX: &str = "tlq-ordered";
+const DRAIN_BATCH: u32 = 100;
+
+/// The ways [`assert_ordered_delivery`] can fail.
+#[derive(Debug, Error)]
+pub enum OrderingError {
+    /// A client operation failed while adding or draining messages
+    #[error(transparent)]
+    Client(#[from] TlqError),
+
+    /// The message delivered at position `index` carried sequence number
+    /// `received` instead of `index`
+    #[error("Message {index} delivered out of order: got sequence {received}")]
+    OutOfOrder { index: usize, received: usize },
+
+    /// The queue ran empty before all messages were delivered
+    #[error("Only {received} of {expected} messages were delivered")]
+    Missing { received: usize, expected: usize },
+}
+
+/// Adds `count` numbered messages and checks that they're consumed in order.
+///
+/// The messages are added one by one, then drained with
+/// [`get_messages`](TlqClient::get_messages) and deleted. Each body carries a
+/// marker unique to this call, so messages from earlier runs are told apart;
+/// any other message that is fetched is left unacknowledged and becomes
+/// visible again once its lock expires. Run this against an otherwise idle
+/// queue, since concurrent consumers would take some of the messages.
+///
+/// On failure, messages not yet drained stay in the queue.
+///
+/// # Examples
+///
+/// ```no_run
+/// use tlq_client::{testing::assert_ordered_delivery, TlqClient};
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let client = TlqClient::new("localhost", 1337)?;
+///     assert_ordered_delivery(&client, 1000).await?;
+///     Ok(())
+/// }
+/// ```
+///
+/// # Errors
+///
+/// * [`OrderingError::OutOfOrder`] for the first message delivered out of sequence
+/// * [`OrderingError::Missing`] if fewer than `count` messages were delivered
+/// * [`OrderingError::Client`] if adding, fetching or deleting failed
+pub async fn assert_ordered_delivery(
+    client: &TlqClient,
+    count: usize,
+) -> Result<(), OrderingError> {
+    let run = format!("{}:{}:", MARKER_PREFIX, Uuid::now_v7());
+
+    for sequence in 0..count {
+        client.add_message(format!("{}{}", run, sequence)).await?;
+    }
+
+    let mut received = 0;
+    while received < count {
+        let batch = (count - received).min(DRAIN_BATCH as usize) as u32;
+        let messages = client.get_messages(batch).await?;
+        if messages.is_empty() {
+            return Err(OrderingError::Missing {
+                received,
+                expected: count,
+            });
+        }
+
+        let ours: Vec<_> = messages
+            .iter()
+            .filter_map(|message| {
+                let sequence = message.body.strip_prefix(&run)?.parse::<usize>().ok()?;
+                Some((message.id, sequence))
+            })
+            .collect();
+        if ours.is_empty() {
+            continue;
+        }
+
+        let ids: Vec<_> = ours.iter().map(|(id, _)| *id).collect();
+        client.delete_messages(&ids).await?;
+
+        for (_, sequence) in ours {
+            if sequence != received {
+                return Err(OrderingError::OutOfOrder {
+                    index: received,
+                    received: sequence,
+                });
+            }
+            received += 1;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{message_json, mock_server};
+    use std::collections::VecDeque;
+    use std::sync::{Arc, Mutex};
+
+    // Serves an in-memory queue that delivers messages in FIFO order, or in
+    // reverse when `reverse` is set.
+    async fn queue_server(reverse: bool) -> TlqClient {
+        let queue = Arc::new(Mutex::new(VecDeque::new()));
+        let (port, _) = mock_server(move |path, body| {
+            let request: serde_json::Value = serde_json::from_str(body).unwrap();
+            let mut queue = queue.lock().unwrap();
+            match path {
+                "/add" => {
+                    let body = request["body"].as_str().unwrap().to_string();
+                    let message = message_json(Uuid::now_v7(), &body, "Ready");
+                    queue.push_back(message.clone());
+                    (200, message)
+                }
+                "/get" => {
+                    let count = request["count"].as_u64().unwrap() as usize;
+                    let mut messages = Vec::new();
+                    while messages.len() < count {
+                        let next = if reverse {
+                            queue.pop_back()
+                        } else {
+                            queue.pop_front()
+                        };
+                        match next {
+                            Some(message) => messages.push(message),
+                            None => break,
+                        }
+                    }
+                    (200, format!("[{}]", messages.join(",")))
+                }
+                _ => (200, "\"Success\"".to_string()),
+            }
+        })
+        .await;
+        TlqClient::new("127.0.0.1", port).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_assert_ordered_delivery() {
+        let client = queue_server(false).await;
+
+        assert_ordered_delivery(&client, 5).await.unwrap();
+        assert!(client.get_messages(10).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_assert_ordered_delivery_reports_first_out_of_order_index() {
+        let client = queue_server(true).await;
+
+        match assert_ordered_delivery(&client, 3).await {
+            Err(OrderingError::OutOfOrder {
+                index: 0,
+                received: 2,
+            }) => {}
+            other => panic!("Expected OutOfOrder error, got {:?}", other),
+        }
+    }
+}
@@ -27,7 +27,7 @@ async fn test_message_size_validation() {
     let result = client.add_message(large_message).await;
 
     match result {
-        Err(TlqError::MessageTooLarge { size }) => {
+        Err(TlqError::MessageTooLarge { size, .. }) => {
             assert_eq!(size, 100_000);
         }
         _ => panic!("Expected MessageTooLarge error"),